@@ -0,0 +1,69 @@
+use audio::{
+    granular_synthesizer::GranularSynthesizer,
+    granular_synthesizer_action::GranularSynthesizerAction, source_buffer::SourceBuffer,
+};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::sync::Arc;
+
+const NUM_VOICES: [u32; 3] = [16, 250, 2000];
+
+const BLOCK_SIZE: usize = 512;
+
+const NUM_OUTPUT_CHANNELS: usize = 2;
+
+/// Creates a synthesizer that has had enough time to fill all of its voices with playing grains
+fn new_synthesizer(num_voices: u32) -> GranularSynthesizer {
+    let sample_rate = GranularSynthesizer::DEFAULT_SAMPLE_RATE;
+    let buffer: Vec<f32> = (0..sample_rate * 4)
+        .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / sample_rate as f32).sin())
+        .collect();
+
+    let mut granular_synthesizer = GranularSynthesizer::new_with_seed(0);
+    granular_synthesizer
+        .set_buffer(Arc::new(SourceBuffer::from_mono(buffer, sample_rate)))
+        .set_max_number_of_channels(num_voices)
+        .set_density(1.0)
+        .set_grain_len_max(1.0)
+        .set_grain_len_min(0.5)
+        .set_grains_per_second(GranularSynthesizer::GRAINS_PER_SECOND_MAX);
+
+    let mut warm_up = vec![0.0; sample_rate as usize * NUM_OUTPUT_CHANNELS];
+    granular_synthesizer.process_block(&mut warm_up, NUM_OUTPUT_CHANNELS);
+
+    granular_synthesizer
+}
+
+fn bench_next_frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("next_frame");
+    for num_voices in NUM_VOICES {
+        let mut granular_synthesizer = new_synthesizer(num_voices);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_voices),
+            &num_voices,
+            |b, _| b.iter(|| black_box(granular_synthesizer.next_frame())),
+        );
+    }
+    group.finish();
+}
+
+fn bench_process_block(c: &mut Criterion) {
+    let mut group = c.benchmark_group("process_block");
+    for num_voices in NUM_VOICES {
+        let mut granular_synthesizer = new_synthesizer(num_voices);
+        let mut block = vec![0.0; BLOCK_SIZE * NUM_OUTPUT_CHANNELS];
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_voices),
+            &num_voices,
+            |b, _| {
+                b.iter(|| {
+                    granular_synthesizer.process_block(&mut block, NUM_OUTPUT_CHANNELS);
+                    black_box(&block);
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_next_frame, bench_process_block);
+criterion_main!(benches);
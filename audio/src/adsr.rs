@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+
+/// Attack / decay / sustain / release settings for an amplitude envelope
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Adsr {
+    /// How long (in milliseconds) it takes to rise from silence to full amplitude
+    pub attack_ms: f32,
+
+    /// How long (in milliseconds) it takes to fall from full amplitude to the sustain level
+    pub decay_ms: f32,
+
+    /// The amplitude (0.0 -> 1.0) held for as long as the note is held
+    pub sustain: f32,
+
+    /// How long (in milliseconds) it takes to fall from the sustain level to silence
+    /// once the note is released
+    pub release_ms: f32,
+}
+
+impl Adsr {
+    pub const TIME_MS_MIN: f32 = 0.0;
+
+    pub const TIME_MS_MAX: f32 = 10000.0;
+
+    pub fn new(attack_ms: f32, decay_ms: f32, sustain: f32, release_ms: f32) -> Self {
+        Self {
+            attack_ms: attack_ms.clamp(Self::TIME_MS_MIN, Self::TIME_MS_MAX),
+            decay_ms: decay_ms.clamp(Self::TIME_MS_MIN, Self::TIME_MS_MAX),
+            sustain: sustain.clamp(0.0, 1.0),
+            release_ms: release_ms.clamp(Self::TIME_MS_MIN, Self::TIME_MS_MAX),
+        }
+    }
+}
+
+impl Default for Adsr {
+    fn default() -> Self {
+        Self::new(10.0, 100.0, 0.8, 300.0)
+    }
+}
+
+/// The part of an `AdsrEnvelope` that is currently playing
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AdsrStage {
+    #[default]
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+/// The current state of a single note's ADSR envelope
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct AdsrEnvelope {
+    stage: AdsrStage,
+
+    /// The envelope's most recent amplitude (0.0 -> 1.0)
+    value: f32,
+
+    /// The amplitude that the release stage started from
+    release_start: f32,
+}
+
+impl AdsrEnvelope {
+    pub fn stage(&self) -> AdsrStage {
+        self.stage
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.stage == AdsrStage::Idle
+    }
+
+    /// Starts (or restarts) the attack stage from the current amplitude,
+    /// so retriggering a note that is still sounding doesn't click
+    pub fn note_on(&mut self) {
+        self.stage = AdsrStage::Attack;
+    }
+
+    /// Starts the release stage from the current amplitude
+    pub fn note_off(&mut self) {
+        if self.stage != AdsrStage::Idle {
+            self.stage = AdsrStage::Release;
+            self.release_start = self.value;
+        }
+    }
+
+    /// Immediately silences the envelope
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Advances the envelope by one frame and returns its new amplitude
+    pub fn next_value(&mut self, adsr: &Adsr, sample_rate: u32) -> f32 {
+        let ms_to_frames = sample_rate as f32 / 1000.0;
+
+        match self.stage {
+            AdsrStage::Idle => self.value = 0.0,
+            AdsrStage::Attack => {
+                self.value += 1.0 / (adsr.attack_ms * ms_to_frames).max(1.0);
+                if self.value >= 1.0 {
+                    self.value = 1.0;
+                    self.stage = AdsrStage::Decay;
+                }
+            }
+            AdsrStage::Decay => {
+                self.value -= (1.0 - adsr.sustain) / (adsr.decay_ms * ms_to_frames).max(1.0);
+                if self.value <= adsr.sustain {
+                    self.value = adsr.sustain;
+                    self.stage = AdsrStage::Sustain;
+                }
+            }
+            AdsrStage::Sustain => {
+                // follow changes to the sustain level while the note is held
+                self.value = adsr.sustain;
+            }
+            AdsrStage::Release => {
+                self.value -= self.release_start / (adsr.release_ms * ms_to_frames).max(1.0);
+                if self.value <= 0.0 {
+                    self.value = 0.0;
+                    self.stage = AdsrStage::Idle;
+                }
+            }
+        }
+
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// At this sample rate, 1 millisecond is exactly 1 frame
+    const SAMPLE_RATE: u32 = 1000;
+
+    fn run(envelope: &mut AdsrEnvelope, adsr: &Adsr, num_frames: usize) -> Vec<f32> {
+        (0..num_frames)
+            .map(|_| envelope.next_value(adsr, SAMPLE_RATE))
+            .collect()
+    }
+
+    #[test]
+    fn new_clamps_its_settings() {
+        let adsr = Adsr::new(-1.0, 20000.0, 1.5, -5.0);
+        assert_eq!(adsr, Adsr::new(0.0, Adsr::TIME_MS_MAX, 1.0, 0.0));
+    }
+
+    #[test]
+    fn moves_through_every_stage() {
+        let adsr = Adsr::new(4.0, 4.0, 0.5, 4.0);
+        let mut envelope = AdsrEnvelope::default();
+        assert!(envelope.is_idle());
+        assert_eq!(run(&mut envelope, &adsr, 2), [0.0, 0.0]);
+
+        envelope.note_on();
+        assert_eq!(run(&mut envelope, &adsr, 4), [0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(envelope.stage(), AdsrStage::Decay);
+
+        assert_eq!(run(&mut envelope, &adsr, 4), [0.875, 0.75, 0.625, 0.5]);
+        assert_eq!(envelope.stage(), AdsrStage::Sustain);
+        assert_eq!(run(&mut envelope, &adsr, 3), [0.5, 0.5, 0.5]);
+
+        envelope.note_off();
+        assert_eq!(envelope.stage(), AdsrStage::Release);
+        assert_eq!(run(&mut envelope, &adsr, 4), [0.375, 0.25, 0.125, 0.0]);
+        assert!(envelope.is_idle());
+    }
+
+    #[test]
+    fn releasing_during_the_attack_starts_from_the_current_value() {
+        let adsr = Adsr::new(4.0, 4.0, 0.5, 2.0);
+        let mut envelope = AdsrEnvelope::default();
+        envelope.note_on();
+        run(&mut envelope, &adsr, 2);
+
+        envelope.note_off();
+        assert_eq!(run(&mut envelope, &adsr, 2), [0.25, 0.0]);
+        assert!(envelope.is_idle());
+    }
+
+    #[test]
+    fn retriggering_continues_from_the_current_value() {
+        let adsr = Adsr::new(4.0, 4.0, 0.5, 4.0);
+        let mut envelope = AdsrEnvelope::default();
+        envelope.note_on();
+        run(&mut envelope, &adsr, 8);
+        envelope.note_off();
+        run(&mut envelope, &adsr, 2);
+
+        envelope.note_on();
+        assert_eq!(run(&mut envelope, &adsr, 2), [0.5, 0.75]);
+    }
+
+    #[test]
+    fn note_off_while_idle_stays_idle() {
+        let mut envelope = AdsrEnvelope::default();
+        envelope.note_off();
+        assert!(envelope.is_idle());
+        assert_eq!(envelope.next_value(&Adsr::default(), SAMPLE_RATE), 0.0);
+    }
+
+    #[test]
+    fn zero_length_stages_take_a_single_frame() {
+        let adsr = Adsr::new(0.0, 0.0, 0.5, 0.0);
+        let mut envelope = AdsrEnvelope::default();
+        envelope.note_on();
+        assert_eq!(run(&mut envelope, &adsr, 2), [1.0, 0.5]);
+
+        envelope.note_off();
+        assert_eq!(run(&mut envelope, &adsr, 1), [0.0]);
+        assert!(envelope.is_idle());
+    }
+}
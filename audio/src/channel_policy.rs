@@ -0,0 +1,77 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Determines which channel of a multi-channel `SourceBuffer` each new grain reads from
+///
+/// Serialized as a table with a `type` (plus a `channel` for `Fixed`),
+/// since TOML has no way to write a bare enum variant that carries data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", content = "channel", rename_all = "snake_case")]
+pub enum ChannelPolicy {
+    /// Every grain reads from the same channel
+    /// (clamped to the last channel if the buffer has fewer channels)
+    Fixed(usize),
+    /// Every grain reads from a randomly chosen channel
+    Random,
+    /// Grains panned to the left read from the first channel, grains panned to the right
+    /// read from the last channel, and any channels in between are spread evenly across the pan range.
+    ///
+    /// For a stereo buffer played through stereo outputs, this preserves the buffer's stereo image.
+    #[default]
+    FollowPan,
+}
+
+impl ChannelPolicy {
+    /// Returns the source channel that a grain panned to `pan` (-1.0 -> 1.0) should read from,
+    /// given a buffer with `num_channels` channels.
+    ///
+    /// `rng` is only drawn from by `ChannelPolicy::Random`.
+    pub fn source_channel(&self, num_channels: usize, pan: f32, rng: &mut impl Rng) -> usize {
+        let last_channel = num_channels.saturating_sub(1);
+        let channel_at =
+            |percent: f32| ((percent * num_channels as f32) as usize).min(last_channel);
+
+        match *self {
+            ChannelPolicy::Fixed(channel) => channel.min(last_channel),
+            ChannelPolicy::Random => rng.gen_range(0..num_channels.max(1)),
+            ChannelPolicy::FollowPan => channel_at(((pan.clamp(-1.0, 1.0) + 1.0) / 2.0).min(1.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn fixed_clamps_to_the_last_channel() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(ChannelPolicy::Fixed(1).source_channel(2, -1.0, &mut rng), 1);
+        assert_eq!(ChannelPolicy::Fixed(5).source_channel(2, 0.0, &mut rng), 1);
+        assert_eq!(ChannelPolicy::Fixed(5).source_channel(0, 0.0, &mut rng), 0);
+    }
+
+    #[test]
+    fn random_stays_within_the_buffer() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut used = [false; 4];
+        for _ in 0..100 {
+            used[ChannelPolicy::Random.source_channel(4, 0.0, &mut rng)] = true;
+        }
+        assert_eq!(used, [true; 4]);
+        assert_eq!(ChannelPolicy::Random.source_channel(0, 0.0, &mut rng), 0);
+    }
+
+    #[test]
+    fn follow_pan_spreads_channels_across_the_pan_range() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let policy = ChannelPolicy::FollowPan;
+        assert_eq!(policy.source_channel(2, -1.0, &mut rng), 0);
+        assert_eq!(policy.source_channel(2, -0.1, &mut rng), 0);
+        assert_eq!(policy.source_channel(2, 0.1, &mut rng), 1);
+        assert_eq!(policy.source_channel(2, 1.0, &mut rng), 1);
+        assert_eq!(policy.source_channel(3, 0.0, &mut rng), 1);
+        assert_eq!(policy.source_channel(1, 1.0, &mut rng), 0);
+    }
+}
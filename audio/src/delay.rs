@@ -0,0 +1,547 @@
+use crate::{
+    effect::{Effect, EffectParameter},
+    note_division::NoteDivision,
+};
+use std::f32::consts::PI;
+
+/// The longest delay (in milliseconds) that the delay lines can hold.
+///
+/// Tempo-synced delays that would be longer than this (e.g. a whole note at a slow tempo) are shortened to fit.
+pub const MAX_DELAY_MS: f32 = 4000.0;
+
+/// How long (in milliseconds) it takes to crossfade from the old delay time to a new one
+const CROSSFADE_MS: f32 = 50.0;
+
+/// The settings of `Delay::SYNC`: off, followed by every note division
+const SYNC_OPTIONS: [&str; NoteDivision::ALL.len() + 1] = {
+    let mut options = [Delay::SYNC_OFF; NoteDivision::ALL.len() + 1];
+    let mut i = 0;
+    while i < NoteDivision::NAMES.len() {
+        options[i + 1] = NoteDivision::NAMES[i];
+        i += 1;
+    }
+    options
+};
+
+/// Stereo feedback delay, with an optional tempo sync, ping-pong mode,
+/// and a low-pass filter in the feedback path (so that each repeat is darker than the last).
+///
+/// Changing the delay time crossfades from the old delay time to the new one, rather than sweeping
+/// the read position (which would bend the pitch of the repeats) or jumping to it (which would click).
+///
+/// The output is only the repeats (fully wet): their level against the dry signal
+/// is set by the mix of the `EffectChain` slot the delay is in.
+///
+/// The delay lines are allocated when the sample rate is set, so processing never allocates.
+/// The first two channels of a block are delayed (a mono block is delayed in mono, without ping-pong);
+/// any further channels are left unchanged.
+#[derive(Clone, Debug)]
+pub struct Delay {
+    time_ms: f32,
+    bpm: f32,
+
+    /// When set, the delay time follows the tempo instead of `time_ms`
+    division: Option<NoteDivision>,
+
+    feedback: f32,
+    tone_hz: f32,
+    ping_pong: bool,
+
+    sample_rate: u32,
+
+    /// One delay line per channel (left, right)
+    lines: [Vec<f32>; 2],
+    write_index: usize,
+
+    /// The delay (in samples) currently being read
+    current_delay: usize,
+
+    /// The delay (in samples) being crossfaded to (equal to `current_delay` when not crossfading)
+    next_delay: usize,
+
+    /// How far the crossfade from `current_delay` to `next_delay` has progressed (0.0 -> 1.0)
+    crossfade_position: f32,
+
+    /// How much of the previous output the feedback filter keeps for each new sample (derived from `tone_hz`)
+    tone_coefficient: f32,
+
+    /// The most recent output of each channel's feedback filter
+    tone_previous: [f32; 2],
+}
+
+impl Delay {
+    pub const TIME_MS: EffectParameter = EffectParameter {
+        name: "time (ms)",
+        min: 1.0,
+        max: 2000.0,
+        default: 375.0,
+        logarithmic: true,
+        options: &[],
+    };
+
+    pub const BPM: EffectParameter = EffectParameter {
+        name: "tempo (bpm)",
+        min: 20.0,
+        max: 300.0,
+        default: 120.0,
+        logarithmic: false,
+        options: &[],
+    };
+
+    /// 0.0 turns tempo sync off, and 1.0 onwards selects from `NoteDivision::ALL`
+    pub const SYNC: EffectParameter = EffectParameter {
+        name: "tempo sync",
+        min: 0.0,
+        max: NoteDivision::ALL.len() as f32,
+        default: 0.0,
+        logarithmic: false,
+        options: &SYNC_OPTIONS,
+    };
+
+    /// The name of the `SYNC` setting that turns tempo sync off
+    pub const SYNC_OFF: &'static str = "off";
+
+    /// How much of each repeat is fed back into the delay (kept below 1.0, so that repeats always fade)
+    pub const FEEDBACK: EffectParameter = EffectParameter {
+        name: "feedback",
+        min: 0.0,
+        max: 0.95,
+        default: 0.4,
+        logarithmic: false,
+        options: &[],
+    };
+
+    /// Cutoff of the low-pass filter in the feedback path
+    pub const TONE_HZ: EffectParameter = EffectParameter {
+        name: "tone (Hz)",
+        min: 200.0,
+        max: 20000.0,
+        default: 5000.0,
+        logarithmic: true,
+        options: &[],
+    };
+
+    /// A switch that bounces the repeats between the left and right channels when on
+    pub const PING_PONG: EffectParameter = EffectParameter {
+        name: "ping-pong",
+        min: 0.0,
+        max: 1.0,
+        default: 0.0,
+        logarithmic: false,
+        options: &["off", "on"],
+    };
+
+    /// How much of the repeats a new effect chain slot mixes in
+    pub const DEFAULT_MIX: f32 = 0.35;
+
+    const PARAMETERS: [EffectParameter; 6] = [
+        Self::TIME_MS,
+        Self::BPM,
+        Self::SYNC,
+        Self::FEEDBACK,
+        Self::TONE_HZ,
+        Self::PING_PONG,
+    ];
+
+    pub fn new(sample_rate: u32) -> Self {
+        let mut delay = Self {
+            time_ms: Self::TIME_MS.default,
+            bpm: Self::BPM.default,
+            division: None,
+            feedback: Self::FEEDBACK.default,
+            tone_hz: Self::TONE_HZ.default,
+            ping_pong: false,
+            sample_rate,
+            lines: [Vec::new(), Vec::new()],
+            write_index: 0,
+            current_delay: 1,
+            next_delay: 1,
+            crossfade_position: 0.0,
+            tone_coefficient: 0.0,
+            tone_previous: [0.0; 2],
+        };
+        delay.set_sample_rate(sample_rate);
+        delay
+    }
+
+    pub fn time_ms(&self) -> f32 {
+        self.time_ms
+    }
+
+    /// Sets the delay time used while tempo sync is off
+    pub fn set_time_ms(&mut self, time_ms: f32) -> &mut Self {
+        self.time_ms = Self::TIME_MS.sanitize(time_ms);
+
+        self
+    }
+
+    pub fn bpm(&self) -> f32 {
+        self.bpm
+    }
+
+    pub fn set_bpm(&mut self, bpm: f32) -> &mut Self {
+        self.bpm = Self::BPM.sanitize(bpm);
+
+        self
+    }
+
+    pub fn division(&self) -> Option<NoteDivision> {
+        self.division
+    }
+
+    /// Syncs the delay time to a note division at the current tempo (or turns tempo sync off with `None`)
+    pub fn set_division(&mut self, division: Option<NoteDivision>) -> &mut Self {
+        self.division = division;
+
+        self
+    }
+
+    pub fn feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    pub fn set_feedback(&mut self, feedback: f32) -> &mut Self {
+        self.feedback = Self::FEEDBACK.sanitize(feedback);
+
+        self
+    }
+
+    pub fn tone_hz(&self) -> f32 {
+        self.tone_hz
+    }
+
+    pub fn set_tone_hz(&mut self, tone_hz: f32) -> &mut Self {
+        self.tone_hz = Self::TONE_HZ.sanitize(tone_hz);
+        self.update_tone_coefficient();
+
+        self
+    }
+
+    pub fn ping_pong(&self) -> bool {
+        self.ping_pong
+    }
+
+    pub fn set_ping_pong(&mut self, ping_pong: bool) -> &mut Self {
+        self.ping_pong = ping_pong;
+
+        self
+    }
+
+    /// The value of the `SYNC` parameter that selects `division` (or turns tempo sync off with `None`)
+    pub fn sync_value(division: Option<NoteDivision>) -> f32 {
+        division.map_or(0.0, |division| (division.index() + 1) as f32)
+    }
+
+    /// The note division that a value of the `SYNC` parameter selects (`None` when tempo sync is off)
+    pub fn sync_division(value: f32) -> Option<NoteDivision> {
+        let sync = Self::SYNC.sanitize(value) as usize;
+        sync.checked_sub(1).map(|i| NoteDivision::ALL[i])
+    }
+
+    /// The delay time that is actually used: either `time_ms` or the synced note division,
+    /// limited to `MAX_DELAY_MS`
+    pub fn delay_ms(&self) -> f32 {
+        let delay_ms = match self.division {
+            Some(division) => division.ms(self.bpm),
+            None => self.time_ms,
+        };
+
+        delay_ms.min(MAX_DELAY_MS)
+    }
+
+    /// The delay, in whole samples (always at least 1 and less than the delay lines' length)
+    fn target_delay(&self) -> usize {
+        let samples = (self.delay_ms() * self.sample_rate as f32 / 1000.0).round() as usize;
+        samples.clamp(1, self.lines[0].len() - 1)
+    }
+
+    fn update_tone_coefficient(&mut self) {
+        let tone_hz = self.tone_hz.min(self.sample_rate as f32 * 0.49);
+        self.tone_coefficient = (-2.0 * PI * tone_hz / self.sample_rate as f32).exp();
+    }
+
+    #[inline]
+    fn read(&self, channel: usize, delay: usize) -> f32 {
+        let line = &self.lines[channel];
+        line[(self.write_index + line.len() - delay) % line.len()]
+    }
+
+    /// Reads the delayed sample of `channel`, blending the old and new delay times while crossfading
+    #[inline]
+    fn read_crossfaded(&self, channel: usize) -> f32 {
+        let current = self.read(channel, self.current_delay);
+        if self.current_delay == self.next_delay {
+            return current;
+        }
+
+        let next = self.read(channel, self.next_delay);
+        current + (next - current) * self.crossfade_position
+    }
+
+    /// Runs a repeat through the feedback path's low-pass filter
+    #[inline]
+    fn filter_feedback(&mut self, channel: usize, input: f32) -> f32 {
+        let coefficient = self.tone_coefficient;
+        let previous = &mut self.tone_previous[channel];
+        *previous = input * (1.0 - coefficient) + *previous * coefficient;
+        // flush values that would otherwise decay into (slow) subnormal numbers
+        if previous.abs() < f32::MIN_POSITIVE {
+            *previous = 0.0;
+        }
+
+        *previous
+    }
+}
+
+impl Effect for Delay {
+    fn name(&self) -> &'static str {
+        "delay"
+    }
+
+    fn parameters(&self) -> &'static [EffectParameter] {
+        &Self::PARAMETERS
+    }
+
+    fn parameter(&self, index: usize) -> Option<f32> {
+        match index {
+            0 => Some(self.time_ms),
+            1 => Some(self.bpm),
+            2 => Some(Self::sync_value(self.division)),
+            3 => Some(self.feedback),
+            4 => Some(self.tone_hz),
+            5 => Some(if self.ping_pong { 1.0 } else { 0.0 }),
+            _ => None,
+        }
+    }
+
+    fn set_parameter(&mut self, index: usize, value: f32) {
+        match index {
+            0 => self.set_time_ms(value),
+            1 => self.set_bpm(value),
+            2 => self.set_division(Self::sync_division(value)),
+            3 => self.set_feedback(value),
+            4 => self.set_tone_hz(value),
+            5 => self.set_ping_pong(Self::PING_PONG.sanitize(value) == 1.0),
+            _ => return,
+        };
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate.max(1);
+        let len = (MAX_DELAY_MS * self.sample_rate as f32 / 1000.0).ceil() as usize + 2;
+        self.lines = [vec![0.0; len], vec![0.0; len]];
+        self.write_index = 0;
+        self.update_tone_coefficient();
+        self.reset();
+    }
+
+    fn default_mix(&self) -> f32 {
+        Self::DEFAULT_MIX
+    }
+
+    fn reset(&mut self) {
+        self.lines.iter_mut().for_each(|line| line.fill(0.0));
+        self.tone_previous = [0.0; 2];
+        self.current_delay = self.target_delay();
+        self.next_delay = self.current_delay;
+        self.crossfade_position = 0.0;
+    }
+
+    fn process_block(&mut self, block: &mut [f32], num_channels: usize) {
+        if num_channels == 0 {
+            return;
+        }
+
+        let target_delay = self.target_delay();
+        let crossfade_step = 1000.0 / (CROSSFADE_MS * self.sample_rate as f32);
+        let feedback = self.feedback;
+        let ping_pong = self.ping_pong && num_channels >= 2;
+        let len = self.lines[0].len();
+
+        for frame in block.chunks_mut(num_channels) {
+            // a new delay time waits for any crossfade in progress to finish first
+            if self.current_delay == self.next_delay && self.current_delay != target_delay {
+                self.next_delay = target_delay;
+                self.crossfade_position = 0.0;
+            }
+
+            let delayed = [self.read_crossfaded(0), self.read_crossfaded(1)];
+            let filtered = [
+                self.filter_feedback(0, delayed[0]),
+                self.filter_feedback(1, delayed[1]),
+            ];
+
+            match frame {
+                [left, right, ..] => {
+                    let (write_left, write_right) = if ping_pong {
+                        // the input enters on the left, and each repeat crosses to the other side
+                        (
+                            (*left + *right) / 2.0 + filtered[1] * feedback,
+                            filtered[0] * feedback,
+                        )
+                    } else {
+                        (
+                            *left + filtered[0] * feedback,
+                            *right + filtered[1] * feedback,
+                        )
+                    };
+                    self.lines[0][self.write_index] = write_left;
+                    self.lines[1][self.write_index] = write_right;
+                    *left = delayed[0];
+                    *right = delayed[1];
+                }
+                [mono] => {
+                    self.lines[0][self.write_index] = *mono + filtered[0] * feedback;
+                    *mono = delayed[0];
+                }
+                [] => {}
+            }
+            self.write_index = (self.write_index + 1) % len;
+
+            if self.current_delay != self.next_delay {
+                self.crossfade_position += crossfade_step;
+                if self.crossfade_position >= 1.0 {
+                    self.current_delay = self.next_delay;
+                    self.crossfade_position = 0.0;
+                }
+            }
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Effect> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 1000;
+
+    /// Runs a single impulse (in every channel) through the delay
+    fn impulse_response(delay: &mut Delay, num_frames: usize, num_channels: usize) -> Vec<f32> {
+        let mut block = vec![0.0; num_frames * num_channels];
+        block[..num_channels].fill(1.0);
+        delay.process_block(&mut block, num_channels);
+        block
+    }
+
+    /// The frames at which `channel` has a (clearly audible) repeat
+    fn echo_frames(block: &[f32], num_channels: usize, channel: usize) -> Vec<usize> {
+        block
+            .chunks(num_channels)
+            .enumerate()
+            .filter(|(_, frame)| frame[channel].abs() > 0.1)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    #[test]
+    fn repeats_at_the_delay_time_and_fades() {
+        let mut delay = Delay::new(SAMPLE_RATE);
+        delay
+            .set_time_ms(100.0)
+            .set_feedback(0.5)
+            .set_tone_hz(Delay::TONE_HZ.max);
+        let block = impulse_response(&mut delay, 350, 1);
+
+        // the output is fully wet, so the impulse itself isn't heard
+        assert_eq!(echo_frames(&block, 1, 0), vec![100, 200, 300]);
+        assert!(block[200].abs() < block[100].abs());
+        assert!(block[300].abs() < block[200].abs());
+    }
+
+    #[test]
+    fn ping_pong_alternates_between_channels() {
+        let mut delay = Delay::new(SAMPLE_RATE);
+        delay
+            .set_time_ms(100.0)
+            .set_feedback(0.5)
+            .set_ping_pong(true);
+        let block = impulse_response(&mut delay, 450, 2);
+
+        assert_eq!(echo_frames(&block, 2, 0), vec![100, 300]);
+        assert_eq!(echo_frames(&block, 2, 1), vec![200, 400]);
+    }
+
+    #[test]
+    fn tempo_sync_overrides_the_delay_time() {
+        let mut delay = Delay::new(SAMPLE_RATE);
+        delay.set_time_ms(100.0).set_bpm(120.0);
+        assert_eq!(delay.delay_ms(), 100.0);
+
+        delay.set_parameter(2, Delay::sync_value(Some(NoteDivision::DottedEighth)));
+        assert_eq!(delay.division(), Some(NoteDivision::DottedEighth));
+        assert_eq!(delay.delay_ms(), 375.0);
+
+        // long notes at slow tempos are shortened to fit in the delay lines
+        delay.set_division(Some(NoteDivision::Whole)).set_bpm(20.0);
+        assert_eq!(delay.delay_ms(), MAX_DELAY_MS);
+
+        delay.set_parameter(2, 0.0);
+        assert_eq!(delay.division(), None);
+        assert_eq!(delay.parameter(2), Some(0.0));
+    }
+
+    #[test]
+    fn changing_the_delay_time_crossfades_without_jumping() {
+        let input: Vec<f32> = (0..600).map(|i| (i as f32 * 0.05).sin()).collect();
+        let mut delay = Delay::new(SAMPLE_RATE);
+        delay.set_time_ms(10.0).set_feedback(0.0);
+        let mut before = input[..200].to_vec();
+        delay.process_block(&mut before, 1);
+
+        delay.set_time_ms(200.0);
+        let mut after = input[200..].to_vec();
+        delay.process_block(&mut after, 1);
+
+        // mid-crossfade, the output is a blend of the old and new delay times
+        let crossfade_frames = (CROSSFADE_MS * SAMPLE_RATE as f32 / 1000.0) as usize;
+        let i = 200 + crossfade_frames / 2;
+        let blend = (input[i - 10] + input[i - 200]) / 2.0;
+        assert!((after[i - 200] - blend).abs() < 0.05);
+        // and once it's done, only the new delay time is heard
+        for i in 200 + crossfade_frames + 1..600 {
+            assert!((after[i - 200] - input[i - 200]).abs() < 1e-6);
+        }
+        // switching between two unrelated parts of the input never jumps
+        let output: Vec<f32> = before.into_iter().chain(after).collect();
+        assert!(output[10..]
+            .windows(2)
+            .all(|pair| (pair[1] - pair[0]).abs() < 0.15));
+    }
+
+    #[test]
+    fn reset_silences_the_repeats() {
+        let mut delay = Delay::new(SAMPLE_RATE);
+        delay.set_time_ms(50.0).set_feedback(0.9);
+        impulse_response(&mut delay, 20, 2);
+
+        delay.reset();
+        let mut silence = vec![0.0; 1000];
+        delay.process_block(&mut silence, 2);
+        assert!(silence.iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn sync_and_ping_pong_are_discrete_settings() {
+        assert_eq!(Delay::SYNC.option_name(0.0), Some(Delay::SYNC_OFF));
+        assert_eq!(Delay::sync_division(0.0), None);
+        for division in NoteDivision::ALL {
+            let value = Delay::SYNC.option_value(division.name()).unwrap();
+            assert_eq!(value, Delay::sync_value(Some(division)));
+            assert_eq!(Delay::sync_division(value), Some(division));
+        }
+
+        let mut delay = Delay::new(SAMPLE_RATE);
+        let ping_pong_on = Delay::PING_PONG.option_value("on").unwrap();
+        delay.set_parameter(5, ping_pong_on);
+        assert!(delay.ping_pong());
+        assert_eq!(delay.parameter(5), Some(ping_pong_on));
+
+        // values in between settings snap to the nearest one
+        delay.set_parameter(5, 0.4);
+        assert!(!delay.ping_pong());
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{delay::Delay, low_pass_filter::LowPassFilter, reverb::Reverb};
+use std::fmt::Debug;
+
+/// Describes one of an effect's adjustable parameters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EffectParameter {
+    pub name: &'static str,
+
+    pub min: f32,
+
+    pub max: f32,
+
+    pub default: f32,
+
+    /// Whether the parameter is best adjusted on a logarithmic scale (e.g. frequencies)
+    pub logarithmic: bool,
+
+    /// For parameters that choose between settings (e.g. a switch), the name of each setting,
+    /// starting at `min` and counting up in whole numbers. Empty for continuous parameters.
+    pub options: &'static [&'static str],
+}
+
+impl EffectParameter {
+    /// Clamps `value` to the parameter's range (and rounds it to the nearest setting, if the parameter is discrete)
+    pub fn sanitize(&self, value: f32) -> f32 {
+        let value = value.clamp(self.min, self.max);
+        if self.is_discrete() {
+            value.round()
+        } else {
+            value
+        }
+    }
+
+    pub fn is_discrete(&self) -> bool {
+        !self.options.is_empty()
+    }
+
+    /// The name of the setting that `value` selects (only discrete parameters have named settings)
+    pub fn option_name(&self, value: f32) -> Option<&'static str> {
+        let index = (self.sanitize(value) - self.min) as usize;
+        self.options.get(index).copied()
+    }
+
+    /// The value that selects the setting called `name` (only discrete parameters have named settings)
+    pub fn option_value(&self, name: &str) -> Option<f32> {
+        self.options
+            .iter()
+            .position(|&option| option == name)
+            .map(|index| self.min + index as f32)
+    }
+}
+
+/// Block-based audio processing that runs on the synthesizer's (mixed-down) output.
+///
+/// Parameters are accessed by index into `parameters()`, so that effects can be edited generically
+/// (e.g. by a ui that knows nothing about the specific effect).
+pub trait Effect: Debug + Send {
+    fn name(&self) -> &'static str;
+
+    /// Descriptions of every parameter that `parameter` / `set_parameter` accept
+    fn parameters(&self) -> &'static [EffectParameter];
+
+    /// Returns the current value of the parameter at `index`, if it exists
+    fn parameter(&self, index: usize) -> Option<f32>;
+
+    /// Sets the parameter at `index`, clamped to its range (does nothing if the parameter doesn't exist)
+    fn set_parameter(&mut self, index: usize, value: f32);
+
+    fn set_sample_rate(&mut self, sample_rate: u32);
+
+    /// Clears any audio the effect is holding onto (e.g. filter state or delay lines),
+    /// without changing its parameters
+    fn reset(&mut self);
+
+    /// How much of the effect's output is mixed with the unprocessed signal when it's added to an
+    /// `EffectChain`. Effects always output a fully processed ("wet") signal, and leave blending it
+    /// with the dry signal to the chain, so effects that are meant to be heard alongside the dry signal
+    /// (e.g. a reverb) override this.
+    fn default_mix(&self) -> f32 {
+        1.0
+    }
+
+    /// Processes a block of interleaved samples in place
+    fn process_block(&mut self, block: &mut [f32], num_channels: usize);
+
+    /// Copies the effect (including its current state) into a new box,
+    /// which allows effect chains to be cloned
+    fn box_clone(&self) -> Box<dyn Effect>;
+}
+
+impl Clone for Box<dyn Effect> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+/// The effects that are built into the `audio` crate
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum EffectKind {
+    #[default]
+    LowPass,
+    Reverb,
+    Delay,
+}
+
+impl EffectKind {
+    pub const NAMES: [&'static str; 3] = ["low-pass", "reverb", "delay"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "low-pass" => Some(EffectKind::LowPass),
+            "reverb" => Some(EffectKind::Reverb),
+            "delay" => Some(EffectKind::Delay),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EffectKind::LowPass => "low-pass",
+            EffectKind::Reverb => "reverb",
+            EffectKind::Delay => "delay",
+        }
+    }
+
+    /// Creates a new instance of the effect with its default parameters
+    pub fn create(&self, sample_rate: u32) -> Box<dyn Effect> {
+        match self {
+            EffectKind::LowPass => Box::new(LowPassFilter::new(sample_rate)),
+            EffectKind::Reverb => Box::new(Reverb::new(sample_rate)),
+            EffectKind::Delay => Box::new(Delay::new(sample_rate)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_names_round_trip() {
+        for name in EffectKind::NAMES {
+            let kind = EffectKind::from_name(name).unwrap();
+            assert_eq!(kind.name(), name);
+            assert_eq!(kind.create(44100).name(), name);
+        }
+        assert_eq!(EffectKind::from_name("chorus"), None);
+    }
+
+    #[test]
+    fn parameters_are_clamped_to_their_range() {
+        for name in EffectKind::NAMES {
+            let mut effect = EffectKind::from_name(name).unwrap().create(44100);
+            for (i, parameter) in effect.parameters().iter().enumerate() {
+                assert_eq!(effect.parameter(i), Some(parameter.default));
+                effect.set_parameter(i, parameter.max + 1000.0);
+                assert_eq!(effect.parameter(i), Some(parameter.max));
+                effect.set_parameter(i, parameter.min - 1000.0);
+                assert_eq!(effect.parameter(i), Some(parameter.min));
+            }
+            assert_eq!(effect.parameter(effect.parameters().len()), None);
+        }
+    }
+
+    #[test]
+    fn discrete_parameters_name_every_setting() {
+        for name in EffectKind::NAMES {
+            let effect = EffectKind::from_name(name).unwrap().create(44100);
+            for parameter in effect.parameters().iter().filter(|p| p.is_discrete()) {
+                assert_eq!(
+                    parameter.options.len(),
+                    (parameter.max - parameter.min) as usize + 1
+                );
+                for &option in parameter.options {
+                    let value = parameter.option_value(option).unwrap();
+                    assert_eq!(parameter.option_name(value), Some(option));
+                }
+            }
+        }
+    }
+}
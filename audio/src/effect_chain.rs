@@ -0,0 +1,306 @@
+use crate::{effect::Effect, source_buffer::SourceBuffer};
+
+/// One effect in an `EffectChain`, along with how much of it is heard
+#[derive(Clone, Debug)]
+pub struct EffectSlot {
+    effect: Box<dyn Effect>,
+
+    /// Bypassed effects are skipped entirely (their input passes through unchanged)
+    bypassed: bool,
+
+    /// How much (0.0 -> 1.0) of the processed signal is mixed with the unprocessed signal
+    mix: f32,
+
+    /// The mix at the end of the last processed block, which the next block ramps from,
+    /// so that changing the mix doesn't click
+    previous_mix: f32,
+}
+
+impl EffectSlot {
+    /// Creates a slot with the effect's own default mix
+    pub fn new(effect: Box<dyn Effect>) -> Self {
+        let mix = effect.default_mix().clamp(0.0, 1.0);
+        Self {
+            effect,
+            bypassed: false,
+            mix,
+            previous_mix: mix,
+        }
+    }
+
+    pub fn effect(&self) -> &dyn Effect {
+        self.effect.as_ref()
+    }
+
+    pub fn effect_mut(&mut self) -> &mut dyn Effect {
+        self.effect.as_mut()
+    }
+
+    pub fn bypassed(&self) -> bool {
+        self.bypassed
+    }
+
+    /// Bypassing an effect clears its state, so that re-enabling it doesn't replay stale audio
+    pub fn set_bypassed(&mut self, bypassed: bool) -> &mut Self {
+        if bypassed && !self.bypassed {
+            self.effect.reset();
+        }
+        self.bypassed = bypassed;
+
+        self
+    }
+
+    pub fn mix(&self) -> f32 {
+        self.mix
+    }
+
+    pub fn set_mix(&mut self, mix: f32) -> &mut Self {
+        self.mix = mix.clamp(0.0, 1.0);
+
+        self
+    }
+}
+
+/// An ordered list of effects, which are applied one after the other to blocks of interleaved audio
+/// (e.g. the synthesizer's output after it has been mixed down to the output channels).
+#[derive(Clone, Debug)]
+pub struct EffectChain {
+    slots: Vec<EffectSlot>,
+
+    sample_rate: u32,
+
+    /// Scratch memory for each effect's unprocessed input, which is only (re)allocated
+    /// when the block size grows
+    dry: Vec<f32>,
+}
+
+impl EffectChain {
+    pub const MAX_EFFECTS: usize = 8;
+
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            slots: Vec::with_capacity(Self::MAX_EFFECTS),
+            sample_rate,
+            dry: Vec::new(),
+        }
+    }
+
+    pub fn slots(&self) -> &[EffectSlot] {
+        &self.slots
+    }
+
+    pub fn slot_mut(&mut self, index: usize) -> Option<&mut EffectSlot> {
+        self.slots.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Adds an effect to the end of the chain, unless `MAX_EFFECTS` effects already exist
+    pub fn add_effect(&mut self, mut effect: Box<dyn Effect>) -> &mut Self {
+        if self.slots.len() < Self::MAX_EFFECTS {
+            effect.set_sample_rate(self.sample_rate);
+            self.slots.push(EffectSlot::new(effect));
+        }
+
+        self
+    }
+
+    pub fn remove_effect(&mut self, index: usize) -> &mut Self {
+        if index < self.slots.len() {
+            self.slots.remove(index);
+        }
+
+        self
+    }
+
+    /// Moves the effect at `from` so that it ends up at `to`, shifting the effects in between
+    pub fn move_effect(&mut self, from: usize, to: usize) -> &mut Self {
+        if from < self.slots.len() && to < self.slots.len() {
+            let slot = self.slots.remove(from);
+            self.slots.insert(to, slot);
+        }
+
+        self
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) -> &mut Self {
+        self.sample_rate = sample_rate;
+        for slot in &mut self.slots {
+            slot.effect.set_sample_rate(sample_rate);
+        }
+
+        self
+    }
+
+    /// Clears the state of every effect (e.g. reverb tails), keeping their parameters
+    pub fn reset(&mut self) -> &mut Self {
+        for slot in &mut self.slots {
+            slot.effect.reset();
+        }
+
+        self
+    }
+
+    /// Runs a block of interleaved samples through every (non-bypassed) effect in order
+    pub fn process_block(&mut self, block: &mut [f32], num_channels: usize) {
+        if num_channels == 0 || block.is_empty() {
+            return;
+        }
+
+        let num_frames = block.len() / num_channels;
+        for slot in self.slots.iter_mut().filter(|slot| !slot.bypassed) {
+            let needs_dry = slot.mix < 1.0 || slot.previous_mix < 1.0;
+            if needs_dry {
+                self.dry.clear();
+                self.dry.extend_from_slice(block);
+            }
+
+            slot.effect.process_block(block, num_channels);
+
+            if needs_dry {
+                // ramp linearly from the previous block's mix to the current one
+                let mix_step = (slot.mix - slot.previous_mix) / num_frames.max(1) as f32;
+                let mut mix = slot.previous_mix;
+                for (frame, dry_frame) in block
+                    .chunks_mut(num_channels)
+                    .zip(self.dry.chunks(num_channels))
+                {
+                    mix += mix_step;
+                    for (sample, dry_sample) in frame.iter_mut().zip(dry_frame) {
+                        *sample = dry_sample + (*sample - dry_sample) * mix;
+                    }
+                }
+            }
+            slot.previous_mix = slot.mix;
+        }
+    }
+}
+
+impl Default for EffectChain {
+    fn default() -> Self {
+        Self::new(SourceBuffer::DEFAULT_SAMPLE_RATE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{effect::EffectParameter, reverb::Reverb};
+
+    /// Doubles every sample and adds a fixed amount, so that the order effects run in is observable
+    #[derive(Clone, Debug)]
+    struct Offset(f32);
+
+    impl Effect for Offset {
+        fn name(&self) -> &'static str {
+            "offset"
+        }
+
+        fn parameters(&self) -> &'static [EffectParameter] {
+            &[]
+        }
+
+        fn parameter(&self, _index: usize) -> Option<f32> {
+            None
+        }
+
+        fn set_parameter(&mut self, _index: usize, _value: f32) {}
+
+        fn set_sample_rate(&mut self, _sample_rate: u32) {}
+
+        fn reset(&mut self) {}
+
+        fn process_block(&mut self, block: &mut [f32], _num_channels: usize) {
+            for sample in block {
+                *sample = *sample * 2.0 + self.0;
+            }
+        }
+
+        fn box_clone(&self) -> Box<dyn Effect> {
+            Box::new(self.clone())
+        }
+    }
+
+    fn process(chain: &mut EffectChain, block: &[f32]) -> Vec<f32> {
+        let mut block = block.to_vec();
+        chain.process_block(&mut block, 2);
+        block
+    }
+
+    #[test]
+    fn effects_run_in_order() {
+        let mut chain = EffectChain::default();
+        chain
+            .add_effect(Box::new(Offset(1.0)))
+            .add_effect(Box::new(Offset(10.0)));
+        // (1 * 2 + 1) * 2 + 10
+        assert_eq!(process(&mut chain, &[1.0, 1.0]), [16.0, 16.0]);
+
+        chain.move_effect(1, 0);
+        // (1 * 2 + 10) * 2 + 1
+        assert_eq!(process(&mut chain, &[1.0, 1.0]), [25.0, 25.0]);
+
+        chain.remove_effect(0);
+        assert_eq!(process(&mut chain, &[1.0, 1.0]), [3.0, 3.0]);
+    }
+
+    #[test]
+    fn bypassed_effects_are_skipped() {
+        let mut chain = EffectChain::default();
+        chain.add_effect(Box::new(Offset(1.0)));
+        chain.slot_mut(0).unwrap().set_bypassed(true);
+        assert_eq!(process(&mut chain, &[0.5, -0.5]), [0.5, -0.5]);
+    }
+
+    #[test]
+    fn mix_blends_with_the_unprocessed_signal() {
+        let mut chain = EffectChain::default();
+        chain.add_effect(Box::new(Offset(1.0)));
+        assert_eq!(chain.slots()[0].mix(), 1.0);
+
+        chain.slot_mut(0).unwrap().set_mix(0.0);
+        // the first block ramps down from the previous mix
+        let ramped = process(&mut chain, &[0.0; 8]);
+        assert_eq!(ramped, [0.75, 0.75, 0.5, 0.5, 0.25, 0.25, 0.0, 0.0]);
+        assert_eq!(process(&mut chain, &[0.0; 4]), [0.0; 4]);
+
+        chain.slot_mut(0).unwrap().set_mix(2.0);
+        assert_eq!(chain.slots()[0].mix(), 1.0);
+    }
+
+    #[test]
+    fn slots_start_at_the_effects_default_mix() {
+        let mut chain = EffectChain::default();
+        chain
+            .add_effect(Box::new(Offset(0.0)))
+            .add_effect(Box::new(Reverb::new(44100)));
+        assert_eq!(chain.slots()[0].mix(), 1.0);
+        assert_eq!(chain.slots()[1].mix(), Reverb::DEFAULT_MIX);
+    }
+
+    #[test]
+    fn chains_are_limited_to_max_effects() {
+        let mut chain = EffectChain::default();
+        for _ in 0..EffectChain::MAX_EFFECTS + 1 {
+            chain.add_effect(Box::new(Offset(0.0)));
+        }
+        assert_eq!(chain.len(), EffectChain::MAX_EFFECTS);
+    }
+
+    #[test]
+    fn empty_chains_leave_audio_untouched() {
+        let mut chain = EffectChain::default();
+        assert!(chain.is_empty());
+        assert_eq!(process(&mut chain, &[0.5, -0.5]), [0.5, -0.5]);
+    }
+}
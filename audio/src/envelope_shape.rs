@@ -0,0 +1,247 @@
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// The amplitude window that is applied over the lifetime of each grain.
+///
+/// Shapes that take a parameter expect it to range from 0.0 -> 1.0.
+///
+/// Serialized as a table with a `type` (plus the shape's parameter, if any),
+/// since TOML has no way to write a bare enum variant that carries data.
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EnvelopeShape {
+    /// Linear ramp up and back down. Cheapest to compute, but can sound buzzy at high densities.
+    #[default]
+    Triangle,
+    /// Raised cosine
+    Hann,
+    /// Bell curve, where `width` is the standard deviation as a percentage of the grain's length
+    Gaussian { width: f32 },
+    /// Cosine-tapered window, where `flatness` is the portion of the grain held at full amplitude
+    /// (0.0 is identical to `Hann`)
+    Tukey { flatness: f32 },
+    /// Linear ramps over the first and last quarter of the grain, with a flat top in between
+    Trapezoid,
+    /// Short attack, followed by an exponential decay down to silence
+    ExponentialDecay,
+}
+
+impl EnvelopeShape {
+    pub const NAMES: [&'static str; 6] = [
+        "triangle",
+        "hann",
+        "gaussian",
+        "tukey",
+        "trapezoid",
+        "exponential",
+    ];
+
+    pub const GAUSSIAN_WIDTH_MIN: f32 = 0.05;
+
+    pub const GAUSSIAN_WIDTH_MAX: f32 = 0.5;
+
+    pub const DEFAULT_GAUSSIAN_WIDTH: f32 = 0.15;
+
+    pub const DEFAULT_TUKEY_FLATNESS: f32 = 0.5;
+
+    /// Portion of the grain spent ramping up (and again ramping down) for `Trapezoid`
+    const TRAPEZOID_RAMP: f32 = 0.25;
+
+    /// Portion of the grain spent ramping up for `ExponentialDecay` (prevents clicks)
+    const EXPONENTIAL_ATTACK: f32 = 0.05;
+
+    /// How steep the `ExponentialDecay` curve is (ln(1000), i.e. -60dB at the end of the grain)
+    const EXPONENTIAL_DECAY_RATE: f32 = 6.908;
+
+    /// Looks up a shape by one of its `NAMES`, using the default parameter (if any)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "triangle" => Some(EnvelopeShape::Triangle),
+            "hann" => Some(EnvelopeShape::Hann),
+            "gaussian" => Some(EnvelopeShape::Gaussian {
+                width: Self::DEFAULT_GAUSSIAN_WIDTH,
+            }),
+            "tukey" => Some(EnvelopeShape::Tukey {
+                flatness: Self::DEFAULT_TUKEY_FLATNESS,
+            }),
+            "trapezoid" => Some(EnvelopeShape::Trapezoid),
+            "exponential" => Some(EnvelopeShape::ExponentialDecay),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnvelopeShape::Triangle => "triangle",
+            EnvelopeShape::Hann => "hann",
+            EnvelopeShape::Gaussian { .. } => "gaussian",
+            EnvelopeShape::Tukey { .. } => "tukey",
+            EnvelopeShape::Trapezoid => "trapezoid",
+            EnvelopeShape::ExponentialDecay => "exponential",
+        }
+    }
+
+    /// The shape's adjustable parameter (if it has one), ranging from 0.0 -> 1.0
+    pub fn parameter(&self) -> Option<f32> {
+        match self {
+            EnvelopeShape::Gaussian { width } => Some(
+                (width - Self::GAUSSIAN_WIDTH_MIN)
+                    / (Self::GAUSSIAN_WIDTH_MAX - Self::GAUSSIAN_WIDTH_MIN),
+            ),
+            EnvelopeShape::Tukey { flatness } => Some(*flatness),
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of the shape with its adjustable parameter (if it has one)
+    /// replaced by `parameter`, which ranges from 0.0 -> 1.0
+    pub fn with_parameter(self, parameter: f32) -> Self {
+        let parameter = parameter.clamp(0.0, 1.0);
+        match self {
+            EnvelopeShape::Gaussian { .. } => EnvelopeShape::Gaussian {
+                width: Self::GAUSSIAN_WIDTH_MIN
+                    + parameter * (Self::GAUSSIAN_WIDTH_MAX - Self::GAUSSIAN_WIDTH_MIN),
+            },
+            EnvelopeShape::Tukey { .. } => EnvelopeShape::Tukey {
+                flatness: parameter,
+            },
+            shape => shape,
+        }
+    }
+
+    /// Generates an envelope value (0.0 -> 1.0) for a grain that is `percent` of the way
+    /// through its lifetime.
+    ///
+    /// `skew` moves the point in time where the envelope reaches its peak (0.5 = unchanged),
+    /// shortening the attack and lengthening the decay (or vice versa).
+    pub fn value(&self, percent: f32, skew: f32) -> f32 {
+        let percent = Self::apply_skew(percent.clamp(0.0, 1.0), skew);
+
+        match *self {
+            EnvelopeShape::Triangle => {
+                utils::generate_triangle_envelope_value_from_percent(percent)
+            }
+            EnvelopeShape::Hann => 0.5 - 0.5 * (2.0 * PI * percent).cos(),
+            EnvelopeShape::Gaussian { width } => {
+                let gaussian = |x: f32| (-0.5 * ((x - 0.5) / width).powi(2)).exp();
+                // scale so that the envelope reaches exactly 0.0 at either edge of the grain
+                let edge = gaussian(0.0);
+                ((gaussian(percent) - edge) / (1.0 - edge)).max(0.0)
+            }
+            EnvelopeShape::Tukey { flatness } => {
+                let taper = (1.0 - flatness).max(f32::EPSILON) / 2.0;
+                if percent < taper {
+                    0.5 - 0.5 * (PI * percent / taper).cos()
+                } else if percent > 1.0 - taper {
+                    0.5 - 0.5 * (PI * (1.0 - percent) / taper).cos()
+                } else {
+                    1.0
+                }
+            }
+            EnvelopeShape::Trapezoid => {
+                (percent.min(1.0 - percent) / Self::TRAPEZOID_RAMP).min(1.0)
+            }
+            EnvelopeShape::ExponentialDecay => {
+                if percent < Self::EXPONENTIAL_ATTACK {
+                    percent / Self::EXPONENTIAL_ATTACK
+                } else {
+                    let decay_percent =
+                        (percent - Self::EXPONENTIAL_ATTACK) / (1.0 - Self::EXPONENTIAL_ATTACK);
+                    let floor = (-Self::EXPONENTIAL_DECAY_RATE).exp();
+                    ((-Self::EXPONENTIAL_DECAY_RATE * decay_percent).exp() - floor) / (1.0 - floor)
+                }
+            }
+        }
+    }
+
+    /// Remaps `percent`, so that the midpoint of the envelope lands at `skew` instead of 0.5
+    fn apply_skew(percent: f32, skew: f32) -> f32 {
+        let skew = skew.clamp(0.01, 0.99);
+        if percent < skew {
+            0.5 * percent / skew
+        } else {
+            0.5 + 0.5 * (percent - skew) / (1.0 - skew)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn every_shape() -> impl Iterator<Item = EnvelopeShape> {
+        EnvelopeShape::NAMES
+            .iter()
+            .map(|name| EnvelopeShape::from_name(name).unwrap())
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for name in EnvelopeShape::NAMES {
+            assert_eq!(EnvelopeShape::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(EnvelopeShape::from_name("square"), None);
+    }
+
+    #[test]
+    fn every_shape_starts_and_ends_silent() {
+        for shape in every_shape() {
+            for skew in [0.1, 0.5, 0.9] {
+                assert!(shape.value(0.0, skew).abs() < 1e-5, "{:?}", shape);
+                assert!(shape.value(1.0, skew).abs() < 1e-5, "{:?}", shape);
+            }
+        }
+    }
+
+    #[test]
+    fn every_shape_stays_within_range_and_reaches_full_amplitude() {
+        for shape in every_shape() {
+            let values: Vec<_> = (0..=1000)
+                .map(|i| shape.value(i as f32 / 1000.0, 0.5))
+                .collect();
+            assert!(values.iter().all(|value| (0.0..=1.0).contains(value)));
+            let peak = values.iter().copied().fold(0.0, f32::max);
+            assert!(peak > 0.99, "{:?} peaked at {}", shape, peak);
+        }
+    }
+
+    #[test]
+    fn skew_moves_the_peak() {
+        let shape = EnvelopeShape::Hann;
+        assert!((shape.value(0.5, 0.5) - 1.0).abs() < 1e-5);
+        assert!((shape.value(0.2, 0.2) - 1.0).abs() < 1e-5);
+        assert!((shape.value(0.8, 0.8) - 1.0).abs() < 1e-5);
+        assert!(shape.value(0.2, 0.5) < 1.0);
+    }
+
+    #[test]
+    fn parameters_round_trip() {
+        for parameter in [0.0, 0.25, 1.0] {
+            let gaussian = EnvelopeShape::from_name("gaussian")
+                .unwrap()
+                .with_parameter(parameter);
+            assert!((gaussian.parameter().unwrap() - parameter).abs() < 1e-5);
+
+            let tukey = EnvelopeShape::from_name("tukey")
+                .unwrap()
+                .with_parameter(parameter);
+            assert_eq!(tukey.parameter(), Some(parameter));
+        }
+
+        assert_eq!(EnvelopeShape::Hann.with_parameter(0.3), EnvelopeShape::Hann);
+        assert_eq!(EnvelopeShape::Hann.parameter(), None);
+        assert_eq!(
+            EnvelopeShape::Tukey { flatness: 0.0 }.with_parameter(2.0),
+            EnvelopeShape::Tukey { flatness: 1.0 }
+        );
+    }
+
+    #[test]
+    fn flat_tukey_holds_full_amplitude() {
+        let tukey = EnvelopeShape::Tukey { flatness: 0.5 };
+        assert_eq!(tukey.value(0.3, 0.5), 1.0);
+        assert_eq!(tukey.value(0.7, 0.5), 1.0);
+        assert!(tukey.value(0.1, 0.5) < 1.0);
+    }
+}
@@ -1,12 +1,56 @@
+use crate::envelope_shape::EnvelopeShape;
+use crate::grain_filter::StateVariableFilter;
+
+/// The direction that a grain reads through its portion of the buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum GrainDirection {
+    #[default]
+    Forward,
+    Reverse,
+}
+
 /// Contains information about where in a buffer the grain should sample from
 #[derive(Clone, Copy, Debug)]
 pub struct Grain {
+    /// the first frame in the buffer that this grain reads from
     pub start_frame: usize,
+    /// one past the last frame in the buffer that this grain reads from
     pub end_frame: usize,
+    /// the number of output frames that this grain has already produced
     pub current_frame: usize,
+    /// the (fractional) frame in the buffer that the grain will read from next
+    pub position: f64,
+    /// how many buffer frames the grain advances for every output frame
+    /// (1.0 = original pitch, 2.0 = an octave up, 0.5 = an octave down)
+    pub playback_rate: f64,
+    pub direction: GrainDirection,
+    /// the amplitude window applied over the grain's lifetime
+    pub envelope_shape: EnvelopeShape,
+    /// where the envelope's peak lands in the grain's lifetime (0.5 = centered)
+    pub envelope_skew: f32,
+    /// stereo position of the grain (-1.0 = left, 0.0 = center, 1.0 = right)
+    pub pan: f32,
+    /// the first of the two adjacent output channels that the grain is panned between
+    pub pan_channel: usize,
+    /// the gains applied to `pan_channel` and the output channel after it
+    pub pan_gains: [f32; 2],
+    /// the channel of the source buffer that this grain reads from
+    pub source_channel: usize,
+    /// the gain applied to every sample of the grain (0.0 -> 1.0)
+    pub amplitude: f32,
+    /// the filter that every sample of the grain is run through (if any)
+    pub filter: Option<StateVariableFilter>,
+    /// the note voice that this grain was started for (only used when playing notes)
+    pub voice: Option<usize>,
     pub finished: bool,
-    /// the number of frames between `start_frame` and `end_frame` in samples
+    /// the number of output frames this grain plays for in samples
     pub len: usize,
+    /// the grain length modulation (as a length multiplier) that was applied when the grain started
+    pub len_modulation: f32,
+    /// the number of frames that the grain fades out over once it has been released (0 = not released)
+    pub release_len: usize,
+    /// the number of frames left until a released grain has faded out
+    pub release_remaining: usize,
 }
 
 impl Default for Grain {
@@ -15,44 +59,233 @@ impl Default for Grain {
             start_frame: 0,
             current_frame: 0,
             end_frame: 0,
+            position: 0.0,
+            playback_rate: 1.0,
+            direction: GrainDirection::Forward,
+            envelope_shape: EnvelopeShape::default(),
+            envelope_skew: 0.5,
+            pan: 0.0,
+            pan_channel: 0,
+            pan_gains: [0.0; 2],
+            source_channel: 0,
+            amplitude: 1.0,
+            filter: None,
+            voice: None,
             finished: true,
             len: 0,
+            len_modulation: 1.0,
+            release_len: 0,
+            release_remaining: 0,
         }
     }
 }
 
 impl Grain {
-    pub fn new(start_frame: usize, end_frame: usize) -> Self {
-        debug_assert!(start_frame < end_frame);
+    /// Creates a grain that plays for `len` output frames, reading through the buffer
+    /// at `playback_rate` from `start_frame` onward.
+    ///
+    /// Reversed grains read the same portion of the buffer, but from back to front.
+    pub fn new(
+        start_frame: usize,
+        len: usize,
+        playback_rate: f64,
+        direction: GrainDirection,
+    ) -> Self {
+        debug_assert!(len > 0);
+        debug_assert!(playback_rate > 0.0);
+        let position = match direction {
+            GrainDirection::Forward => start_frame as f64,
+            GrainDirection::Reverse => start_frame as f64 + (len - 1) as f64 * playback_rate,
+        };
         Grain {
             start_frame,
-            current_frame: start_frame,
-            end_frame,
+            current_frame: 0,
+            end_frame: start_frame + Self::buffer_span(len, playback_rate),
+            position,
+            playback_rate,
+            direction,
+            envelope_shape: EnvelopeShape::default(),
+            envelope_skew: 0.5,
+            pan: 0.0,
+            pan_channel: 0,
+            pan_gains: [0.0; 2],
+            source_channel: 0,
+            amplitude: 1.0,
+            filter: None,
+            voice: None,
             finished: false,
-            len: end_frame - start_frame,
+            len,
+            len_modulation: 1.0,
+            release_len: 0,
+            release_remaining: 0,
         }
     }
 
-    /// Increments the current_frame and returns it.
+    /// The number of buffer frames that a grain of `len` output frames covers at `playback_rate`
+    pub fn buffer_span(len: usize, playback_rate: f64) -> usize {
+        (len as f64 * playback_rate).ceil() as usize
+    }
+
+    /// Advances the grain by one output frame and returns the buffer position
+    /// that was valid before advancing.
     ///
     /// If the grain is already finished, this is a no-op and `None` is returned.
-    pub fn next_frame(&mut self) -> Option<usize> {
+    pub fn next_frame(&mut self) -> Option<f64> {
         if self.finished {
             return None;
         }
 
-        // return the frame that was valid before incrementing the count
-        let frame_to_return = self.current_frame;
+        // return the position that was valid before incrementing the count
+        let position_to_return = self.position;
 
+        match self.direction {
+            GrainDirection::Forward => self.position += self.playback_rate,
+            GrainDirection::Reverse => self.position -= self.playback_rate,
+        }
         self.current_frame += 1;
-        if self.current_frame == self.end_frame {
+        if self.current_frame >= self.len {
             self.finished = true;
         }
+        if self.is_released() {
+            self.release_remaining -= 1;
+            if self.release_remaining == 0 {
+                self.finished = true;
+            }
+        }
 
-        Some(frame_to_return)
+        Some(position_to_return)
+    }
+
+    /// How far through its lifetime the grain currently is (0.0 -> 1.0)
+    pub fn progress(&self) -> f32 {
+        self.current_frame as f32 / self.len.max(1) as f32
+    }
+
+    /// The grain's current envelope value (0.0 -> 1.0), including its release fade (if it has been released)
+    pub fn envelope_value(&self) -> f32 {
+        let release_gain = if self.is_released() {
+            self.release_remaining as f32 / self.release_len as f32
+        } else {
+            1.0
+        };
+        self.envelope_shape
+            .value(self.progress(), self.envelope_skew)
+            * release_gain
     }
 
     pub fn remaining_samples(&self) -> usize {
-        self.end_frame - self.current_frame
+        let remaining_samples = self.len.saturating_sub(self.current_frame);
+        if self.is_released() {
+            remaining_samples.min(self.release_remaining)
+        } else {
+            remaining_samples
+        }
+    }
+
+    /// Fades the grain out over the next `release_len` frames, instead of cutting it off abruptly.
+    ///
+    /// Grains that would end before the fade is over are left to end on their own.
+    pub fn release(&mut self, release_len: usize) {
+        if self.finished || self.is_released() || release_len >= self.remaining_samples() {
+            return;
+        }
+        if release_len == 0 {
+            self.finished = true;
+            return;
+        }
+
+        self.release_len = release_len;
+        self.release_remaining = release_len;
+    }
+
+    pub fn is_released(&self) -> bool {
+        self.release_len > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(grain: &mut Grain) -> Vec<f64> {
+        std::iter::from_fn(|| grain.next_frame()).collect()
+    }
+
+    #[test]
+    fn reads_forward_at_its_playback_rate() {
+        let mut grain = Grain::new(10, 4, 1.5, GrainDirection::Forward);
+        assert_eq!(grain.end_frame, 16);
+        assert_eq!(positions(&mut grain), [10.0, 11.5, 13.0, 14.5]);
+        assert!(grain.finished);
+        assert_eq!(grain.remaining_samples(), 0);
+        assert_eq!(grain.next_frame(), None);
+    }
+
+    #[test]
+    fn reverse_grains_read_the_same_frames_backwards() {
+        let mut forward = Grain::new(10, 4, 1.5, GrainDirection::Forward);
+        let mut reverse = Grain::new(10, 4, 1.5, GrainDirection::Reverse);
+        assert_eq!(reverse.end_frame, forward.end_frame);
+
+        let mut forward_positions = positions(&mut forward);
+        forward_positions.reverse();
+        assert_eq!(positions(&mut reverse), forward_positions);
+        assert!(reverse.finished);
+    }
+
+    #[test]
+    fn buffer_span_rounds_up() {
+        assert_eq!(Grain::buffer_span(4, 1.0), 4);
+        assert_eq!(Grain::buffer_span(3, 0.5), 2);
+        assert_eq!(Grain::buffer_span(3, 2.0), 6);
+    }
+
+    #[test]
+    fn progress_moves_through_the_grain() {
+        let mut grain = Grain::new(0, 4, 1.0, GrainDirection::Forward);
+        assert_eq!(grain.progress(), 0.0);
+        assert_eq!(grain.envelope_value(), 0.0);
+        grain.next_frame();
+        grain.next_frame();
+        assert_eq!(grain.progress(), 0.5);
+        assert_eq!(grain.remaining_samples(), 2);
+        assert_eq!(grain.envelope_value(), 1.0);
+    }
+
+    #[test]
+    fn default_grains_are_finished() {
+        let mut grain = Grain::default();
+        assert!(grain.finished);
+        assert_eq!(grain.next_frame(), None);
+    }
+
+    #[test]
+    fn released_grains_fade_out() {
+        // the release starts on the trapezoid's flat top, so the fade is all that changes the envelope
+        let mut grain = Grain {
+            envelope_shape: EnvelopeShape::Trapezoid,
+            ..Grain::new(0, 100, 1.0, GrainDirection::Forward)
+        };
+        for _ in 0..40 {
+            grain.next_frame();
+        }
+        grain.release(4);
+        assert!(grain.is_released());
+        assert_eq!(grain.remaining_samples(), 4);
+
+        let mut envelope_values = Vec::new();
+        while !grain.finished {
+            envelope_values.push(grain.envelope_value());
+            grain.next_frame();
+        }
+        assert_eq!(envelope_values, [1.0, 0.75, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn grains_that_end_soon_are_not_released() {
+        let mut grain = Grain::new(0, 4, 1.0, GrainDirection::Forward);
+        grain.release(10);
+        assert!(!grain.is_released());
+        assert_eq!(grain.remaining_samples(), 4);
     }
 }
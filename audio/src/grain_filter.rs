@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// The (optional) filter that each new grain is run through
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrainFilterMode {
+    /// Grains play the buffer's samples unfiltered
+    #[default]
+    Off,
+    LowPass,
+    HighPass,
+    BandPass,
+}
+
+impl GrainFilterMode {
+    pub const NAMES: [&'static str; 4] = ["off", "low-pass", "high-pass", "band-pass"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "off" => Some(GrainFilterMode::Off),
+            "low-pass" => Some(GrainFilterMode::LowPass),
+            "high-pass" => Some(GrainFilterMode::HighPass),
+            "band-pass" => Some(GrainFilterMode::BandPass),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GrainFilterMode::Off => "off",
+            GrainFilterMode::LowPass => "low-pass",
+            GrainFilterMode::HighPass => "high-pass",
+            GrainFilterMode::BandPass => "band-pass",
+        }
+    }
+}
+
+/// A resonant state-variable filter (in its trapezoidal / "zero-delay feedback" form,
+/// which stays stable and in tune at any cutoff).
+///
+/// This is small and `Copy`, so that every grain can carry its own filter without allocating.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StateVariableFilter {
+    mode: GrainFilterMode,
+
+    /// Damping (the inverse of the filter's Q)
+    k: f32,
+
+    a1: f32,
+    a2: f32,
+    a3: f32,
+
+    /// The filter's two integrator states
+    ic1eq: f32,
+    ic2eq: f32,
+}
+
+impl StateVariableFilter {
+    /// The Q of the filter at a resonance of 0.0 (no resonant peak)
+    pub const Q_MIN: f32 = 0.5;
+
+    /// The Q of the filter at a resonance of 1.0
+    pub const Q_MAX: f32 = 10.0;
+
+    /// Creates a filter with its cutoff at `cutoff_hz` (limited to just below the nyquist frequency)
+    /// and a `resonance` from 0.0 (none) to 1.0 (a sharp peak at the cutoff)
+    pub fn new(mode: GrainFilterMode, cutoff_hz: f32, resonance: f32, sample_rate: u32) -> Self {
+        let sample_rate = sample_rate.max(1) as f32;
+        let cutoff_hz = cutoff_hz.clamp(1.0, sample_rate * 0.49);
+        // resonance is mapped exponentially, since Q is perceived roughly logarithmically
+        let q = Self::Q_MIN * (Self::Q_MAX / Self::Q_MIN).powf(resonance.clamp(0.0, 1.0));
+
+        let g = (PI * cutoff_hz / sample_rate).tan();
+        let k = 1.0 / q;
+        let a1 = 1.0 / (1.0 + g * (g + k));
+        let a2 = g * a1;
+        let a3 = g * a2;
+
+        Self {
+            mode,
+            k,
+            a1,
+            a2,
+            a3,
+            ic1eq: 0.0,
+            ic2eq: 0.0,
+        }
+    }
+
+    pub fn mode(&self) -> GrainFilterMode {
+        self.mode
+    }
+
+    /// Filters a single sample
+    #[inline]
+    pub fn process(&mut self, input: f32) -> f32 {
+        let v3 = input - self.ic2eq;
+        let v1 = self.a1 * self.ic1eq + self.a2 * v3;
+        let v2 = self.ic2eq + self.a2 * self.ic1eq + self.a3 * v3;
+        self.ic1eq = 2.0 * v1 - self.ic1eq;
+        self.ic2eq = 2.0 * v2 - self.ic2eq;
+
+        match self.mode {
+            GrainFilterMode::Off => input,
+            GrainFilterMode::LowPass => v2,
+            GrainFilterMode::HighPass => input - self.k * v1 - v2,
+            // scaled by the damping, so that the peak at the cutoff stays at unity gain
+            GrainFilterMode::BandPass => self.k * v1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 44100;
+
+    /// The peak level of a full-scale sine at `frequency` once the filter has settled
+    fn gain_at(mut filter: StateVariableFilter, frequency: f32) -> f32 {
+        let num_samples = SAMPLE_RATE as usize / 5;
+        (0..num_samples)
+            .map(|i| filter.process((2.0 * PI * frequency * i as f32 / SAMPLE_RATE as f32).sin()))
+            .skip(num_samples / 2)
+            .fold(0.0, |peak, sample| sample.abs().max(peak))
+    }
+
+    fn filter(mode: GrainFilterMode, resonance: f32) -> StateVariableFilter {
+        StateVariableFilter::new(mode, 1000.0, resonance, SAMPLE_RATE)
+    }
+
+    #[test]
+    fn mode_names_round_trip() {
+        for name in GrainFilterMode::NAMES {
+            assert_eq!(GrainFilterMode::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(GrainFilterMode::from_name("notch"), None);
+    }
+
+    #[test]
+    fn off_passes_samples_through() {
+        let mut filter = filter(GrainFilterMode::Off, 1.0);
+        for sample in [0.0, 0.5, -1.0, 0.25] {
+            assert_eq!(filter.process(sample), sample);
+        }
+    }
+
+    #[test]
+    fn low_pass_keeps_frequencies_below_the_cutoff() {
+        let low_pass = filter(GrainFilterMode::LowPass, 0.0);
+        assert!((gain_at(low_pass, 100.0) - 1.0).abs() < 0.02);
+        assert!(gain_at(low_pass, 10000.0) < 0.02);
+    }
+
+    #[test]
+    fn high_pass_keeps_frequencies_above_the_cutoff() {
+        let high_pass = filter(GrainFilterMode::HighPass, 0.0);
+        assert!(gain_at(high_pass, 100.0) < 0.02);
+        assert!((gain_at(high_pass, 10000.0) - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn band_pass_peaks_at_unity_gain_at_the_cutoff() {
+        for resonance in [0.0, 0.5, 1.0] {
+            let band_pass = filter(GrainFilterMode::BandPass, resonance);
+            assert!((gain_at(band_pass, 1000.0) - 1.0).abs() < 0.02);
+            assert!(gain_at(band_pass, 10000.0) < 0.2);
+        }
+    }
+
+    #[test]
+    fn resonance_raises_the_peak_at_the_cutoff() {
+        let flat = gain_at(filter(GrainFilterMode::LowPass, 0.0), 1000.0);
+        let resonant = gain_at(filter(GrainFilterMode::LowPass, 1.0), 1000.0);
+        assert!((flat - StateVariableFilter::Q_MIN).abs() < 0.02);
+        assert!((resonant - StateVariableFilter::Q_MAX).abs() < 0.2);
+    }
+
+    #[test]
+    fn cutoffs_past_nyquist_stay_stable() {
+        let mut filter =
+            StateVariableFilter::new(GrainFilterMode::LowPass, 100000.0, 1.0, SAMPLE_RATE);
+        let output: Vec<_> = (0..1000)
+            .map(|i| filter.process(if i % 2 == 0 { 1.0 } else { -1.0 }))
+            .collect();
+        assert!(output
+            .iter()
+            .all(|sample| sample.is_finite() && sample.abs() < 100.0));
+    }
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// Determines how the minimum and maximum grain lengths are measured
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrainLenMode {
+    /// Grain lengths are a percentage of the currently selected audio,
+    /// so narrowing the selection also shortens every grain
+    #[default]
+    Relative,
+    /// Grain lengths are a fixed number of milliseconds,
+    /// regardless of the size of the selection or buffer
+    Absolute,
+}
+
+impl GrainLenMode {
+    pub const NAMES: [&'static str; 2] = ["relative", "absolute"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "relative" => Some(GrainLenMode::Relative),
+            "absolute" => Some(GrainLenMode::Absolute),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GrainLenMode::Relative => "relative",
+            GrainLenMode::Absolute => "absolute",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for name in GrainLenMode::NAMES {
+            assert_eq!(GrainLenMode::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(GrainLenMode::from_name("fixed"), None);
+    }
+}
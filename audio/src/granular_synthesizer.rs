@@ -1,136 +1,357 @@
-use crate::grain::Grain;
+use crate::adsr::Adsr;
+use crate::channel_policy::ChannelPolicy;
+use crate::envelope_shape::EnvelopeShape;
+use crate::grain::{Grain, GrainDirection};
+use crate::grain_filter::{GrainFilterMode, StateVariableFilter};
+use crate::grain_len_mode::GrainLenMode;
 use crate::granular_synthesizer_action::GranularSynthesizerAction;
+use crate::interpolation::{Interpolation, Interpolator};
+use crate::lfo::Lfo;
 use crate::max::Max;
 use crate::min::Min;
+use crate::modulation_matrix::{ModulationMatrix, ModulationRouting, ModulationTarget};
+use crate::note_voice::{NoteVoice, PlayMode};
+use crate::pan_law::PanLaw;
 use crate::percentage::Percentage;
-use crate::utils;
+use crate::pitch_quantizer::PitchQuantizer;
+use crate::playhead::{Playhead, PositionMode};
+use crate::preset::Preset;
+use crate::scheduler::{GrainScheduler, SchedulerMode};
+use crate::smoothed_value::{SmoothedValue, SmoothingType};
+use crate::source_buffer::SourceBuffer;
 use rand::prelude::StdRng;
 use rand::{Rng, SeedableRng};
 use std::sync::Arc;
 
-/// Accepts a reference to a buffer of Vec<f32> audio sample data.
+/// Accepts a reference to a multi-channel `SourceBuffer` of audio sample data.
 ///
 /// Generates random multi-channel audio grain output.
+///
+/// Cloning a synthesizer copies all of its state (including its random number generator
+/// and any playing grains), so a clone can be rendered offline without affecting the original.
+#[derive(Clone)]
 pub struct GranularSynthesizer {
     /// The maximum number of channels that can be generating samples via grains at a time.
     /// This can be used in conjunction with `density` to alter the number of playing grains
     /// during runtime.
     max_num_channels: u32,
 
-    /// How many channels of grains to generate per frame (0.0 -> 1.0)
+    /// How many channels of grains can play at once (0.0 -> 1.0)
     /// A value of 1.0 corresponds to `max_num_channels` and a value of 0.o corresponds to no channels.
-    density: Percentage,
+    density: SmoothedValue,
 
     /// Sample rate of the surrounding context
     sample_rate: u32,
 
     /// External audio buffer that this GranularSynthesizer should read grains from
-    buffer: Arc<Vec<f32>>,
+    buffer: Arc<SourceBuffer>,
 
     /// List of grains and their current progress through the buffer.
     ///
     /// 1 array element = 1 grain = 1 channel of audio
     grains: Vec<Grain>,
 
+    /// Indexes of grains that are not currently playing and can be replaced with new ones.
+    ///
+    /// New grains are taken from the end, so finding a spent grain never requires a search.
+    free_grains: Vec<usize>,
+
+    /// Indexes of grains that are currently playing (in no particular order)
+    active_grains: Vec<usize>,
+
+    /// Position in `active_grains` of the next grain to check for being too long
+    long_grain_cursor: usize,
+
     /// used to generate random indexes
     rng: StdRng,
 
+    /// The seed that `rng` was most recently seeded with
+    seed: u64,
+
     /// length as a percentage of the currently selected audio
     grain_len_min: Percentage,
 
     /// length as a percentage of the currently selected audio
     grain_len_max: Percentage,
 
-    /// Samples that have been copied over from the audio buffer.
-    /// This value gets multiplied with its corresponding `output_env_samples`
-    /// to produce the final sample value.
-    ///
-    /// each array value = 1 channel
-    output_buffer_samples: Vec<f32>,
+    /// Whether grain lengths are relative to the selection or given in milliseconds
+    grain_len_mode: GrainLenMode,
+
+    /// length in milliseconds (used with `GrainLenMode::Absolute`)
+    grain_len_min_ms: f32,
 
-    /// Envelope values that have been copied over to match the
-    /// current progress of a grain.
-    /// This value gets multiplied with its corresponding `output_buffer_samples`
-    /// to produce the final sample value.
+    /// length in milliseconds (used with `GrainLenMode::Absolute`)
+    grain_len_max_ms: f32,
+
+    /// The final value of each channel for the most recently rendered frame
+    /// (buffer sample * envelope sample * grain amplitude).
     ///
-    /// each array value = 1 channel
-    output_env_samples: Vec<f32>,
+    /// This is preallocated to `max_num_channels`, so that rendering blocks of frames
+    /// never has to allocate. Channels without a playing grain are always 0.0.
+    frame_samples: Vec<f32>,
 
     /// The minimum index that samples can be taken from,
     /// ranging from 0.0 -> 1.0 (i.e. percentage of the buffer)
-    selection_start: Percentage,
+    selection_start: SmoothedValue,
 
     /// The maximum index that samples can be taken from,
     /// ranging from 0.0 -> 1.0 (i.e. percentage of the buffer)
-    selection_end: Percentage,
-
-    /// This is a counter that gets incremented on every frame.
-    /// This allows performing some actions (such as replacing grains) to occur
-    /// only so often.
-    refresh_counter: u32,
+    selection_end: SmoothedValue,
 
-    /// This determines the interval (in samples) at which too-long grains are marked `finished`
+    /// This determines the frames at which too-long grains are marked `finished`
     /// and `finished` grains are replaced with new ones.
     ///
-    /// A higher interval produces a slower transition to new selected regions.
+    /// A lower rate produces a slower transition to new selected regions.
     ///
-    /// A lower interval produces a faster transition to new selected regions.
-    ///
-    /// It is preferred for this interval to be a prime number to minimize the amount of
-    /// sample overlap, where one grain is exactly in-sync with another, producing a unified
-    /// and/or chorus effect (or exaggerated amplification).
-    refresh_interval: u32,
+    /// A higher rate produces a faster transition to new selected regions.
+    scheduler: GrainScheduler,
+
+    /// Whether new grains start anywhere in the selection or around the `playhead`
+    position_mode: PositionMode,
+
+    /// Moves through the selection (looping at its edges) when using `PositionMode::Scan`
+    playhead: Playhead,
+
+    /// How fast the playhead moves (its speed follows this value every frame)
+    scan_speed: SmoothedValue,
+
+    /// Whether grains play continuously or only while notes are sounding
+    play_mode: PlayMode,
+
+    /// Every note that can be sounding (preallocated to `MAX_VOICES`)
+    voices: Vec<NoteVoice>,
+
+    /// How many of `voices` can be used at once
+    voice_limit: usize,
+
+    /// The MIDI note that plays the buffer at its original pitch
+    root_note: u8,
+
+    /// The amplitude envelope applied to every note
+    adsr: Adsr,
+
+    /// How much a note's velocity affects its amplitude
+    velocity_sensitivity: Percentage,
+
+    /// Counts started notes, so that the oldest voice can be found when stealing voices
+    voice_counter: u64,
+
+    /// The next voice to consider when assigning a new grain to a sounding note
+    voice_cursor: usize,
+
+    /// LFOs and their routings to synthesizer parameters, evaluated every frame
+    modulation_matrix: ModulationMatrix,
+
+    /// How long (in milliseconds) continuous parameters take to reach new values
+    smoothing_ms: f32,
+
+    /// The curve that continuous parameters follow on their way to new values
+    smoothing_type: SmoothingType,
+
+    /// How far (in milliseconds) before or after the playhead new grains can start
+    spray_ms: f32,
+
+    /// Transposition (in semitones) applied to every new grain
+    pitch_semitones: f32,
+
+    /// Fine-tuning (in cents) applied to every new grain
+    pitch_cents: f32,
+
+    /// Maximum random deviation (in semitones) from the base pitch for every new grain
+    pitch_spread: f32,
+
+    /// The pitches that new grains' random / modulated transpositions are snapped to (if any)
+    pitch_quantizer: Option<PitchQuantizer>,
+
+    /// How samples are read from between whole buffer frames when grains are transposed
+    interpolation: Interpolation,
+
+    /// Reads fractional buffer positions using the current `interpolation`
+    interpolator: Interpolator,
+
+    /// The chance that any new grain will play in reverse (0.0 -> 1.0)
+    reverse_probability: Percentage,
+
+    /// The amplitude window that new grains are created with
+    envelope_shape: EnvelopeShape,
+
+    /// Where new grains' envelopes reach their peak (0.5 = centered)
+    envelope_skew: Percentage,
+
+    /// The range of amplitudes that new grains are randomly assigned
+    amplitude_min: Percentage,
+
+    amplitude_max: Percentage,
+
+    /// The type of filter that new grains are run through
+    grain_filter_mode: GrainFilterMode,
+
+    /// The range of cutoffs (in Hz) that new grains' filters are randomly assigned
+    grain_filter_cutoff_min_hz: f32,
+
+    grain_filter_cutoff_max_hz: f32,
+
+    /// How sharply new grains' filters emphasize their cutoff
+    grain_filter_resonance: Percentage,
+
+    /// Whether the output level is compensated for the number of sounding grains
+    normalize: bool,
+
+    /// The (smoothed) amount that panned output is divided by when `normalize` is enabled
+    normalization_divisor: f32,
+
+    /// How much of the distance to its target `normalization_divisor` moves every frame
+    normalization_coefficient: f32,
+
+    /// Which channel of the buffer new grains read from
+    channel_policy: ChannelPolicy,
+
+    /// The pan position (-1.0 -> 1.0) that new grains are centered around
+    pan_center: SmoothedValue,
+
+    /// How far new grains' pan positions can randomly deviate from `pan_center`
+    pan_spread: SmoothedValue,
+
+    /// How grains' amplitudes are split between output channels
+    pan_law: PanLaw,
+
+    /// The number of output channels that every grain's `pan_gains` were calculated for
+    pan_num_channels: usize,
 }
 
 impl GranularSynthesizerAction for GranularSynthesizer {
     const DEFAULT_DENSITY: f32 = 0.5;
 
     fn new() -> Self {
-        let default_buffer = Arc::new(Vec::new());
+        Self::new_with_seed(StdRng::from_entropy().gen())
+    }
+
+    fn new_with_seed(seed: u64) -> Self {
+        let default_buffer = Arc::new(SourceBuffer::default());
 
         Self {
             sample_rate: Self::DEFAULT_SAMPLE_RATE,
             buffer: default_buffer,
             grains: vec![Self::new_grain(); Self::DEFAULT_NUM_CHANNELS as usize],
-            rng: rand::rngs::StdRng::from_entropy(),
+            // reversed, so that the lowest indexes are used first
+            free_grains: (0..Self::DEFAULT_NUM_CHANNELS as usize).rev().collect(),
+            active_grains: Vec::with_capacity(Self::DEFAULT_NUM_CHANNELS as usize),
+            long_grain_cursor: 0,
+            rng: StdRng::seed_from_u64(seed),
+            seed,
             grain_len_min: Percentage::from(Self::GRAIN_LEN_MIN_MIN),
             grain_len_max: Percentage::from(Self::GRAIN_LEN_MAX_MIN),
-            output_buffer_samples: vec![0.0; Self::DEFAULT_NUM_CHANNELS as usize],
-            output_env_samples: vec![0.0; Self::DEFAULT_NUM_CHANNELS as usize],
-            selection_start: Percentage::from(0.0),
-            selection_end: Percentage::from(1.0),
+            grain_len_mode: GrainLenMode::default(),
+            grain_len_min_ms: Self::DEFAULT_GRAIN_LEN_MIN_MS,
+            grain_len_max_ms: Self::DEFAULT_GRAIN_LEN_MAX_MS,
+            frame_samples: vec![0.0; Self::DEFAULT_NUM_CHANNELS as usize],
+            selection_start: Self::new_smoothed_value(0.0),
+            selection_end: Self::new_smoothed_value(1.0),
             max_num_channels: Self::DEFAULT_NUM_CHANNELS,
-            density: Percentage::from(Self::DEFAULT_DENSITY),
-            refresh_counter: 0,
-            refresh_interval: Self::DEFAULT_REFRESH_INTERVAL,
+            density: Self::new_smoothed_value(Self::DEFAULT_DENSITY),
+            scheduler: GrainScheduler::new(
+                Self::DEFAULT_GRAINS_PER_SECOND,
+                SchedulerMode::default(),
+                Self::DEFAULT_SCHEDULER_JITTER,
+            ),
+            position_mode: PositionMode::default(),
+            playhead: Playhead::new(Self::DEFAULT_SCAN_SPEED),
+            scan_speed: Self::new_smoothed_value(Self::DEFAULT_SCAN_SPEED),
+            modulation_matrix: ModulationMatrix::new(),
+            play_mode: PlayMode::default(),
+            voices: vec![NoteVoice::default(); Self::MAX_VOICES],
+            voice_limit: Self::DEFAULT_VOICE_LIMIT,
+            root_note: Self::DEFAULT_ROOT_NOTE,
+            adsr: Adsr::default(),
+            velocity_sensitivity: Percentage::from(Self::DEFAULT_VELOCITY_SENSITIVITY),
+            voice_counter: 0,
+            voice_cursor: 0,
+            smoothing_ms: Self::DEFAULT_SMOOTHING_MS,
+            smoothing_type: SmoothingType::default(),
+            spray_ms: Self::DEFAULT_SPRAY_MS,
+            pitch_semitones: 0.0,
+            pitch_cents: 0.0,
+            pitch_spread: 0.0,
+            pitch_quantizer: None,
+            interpolation: Interpolation::default(),
+            interpolator: Interpolator::new(),
+            reverse_probability: Percentage::from(Self::DEFAULT_REVERSE_PROBABILITY),
+            envelope_shape: EnvelopeShape::default(),
+            envelope_skew: Percentage::from(Self::DEFAULT_ENVELOPE_SKEW),
+            amplitude_min: Percentage::from(Self::DEFAULT_AMPLITUDE_MIN),
+            amplitude_max: Percentage::from(Self::DEFAULT_AMPLITUDE_MAX),
+            grain_filter_mode: GrainFilterMode::default(),
+            grain_filter_cutoff_min_hz: Self::DEFAULT_GRAIN_FILTER_CUTOFF_MIN_HZ,
+            grain_filter_cutoff_max_hz: Self::DEFAULT_GRAIN_FILTER_CUTOFF_MAX_HZ,
+            grain_filter_resonance: Percentage::from(Self::DEFAULT_GRAIN_FILTER_RESONANCE),
+            normalize: false,
+            normalization_divisor: 1.0,
+            normalization_coefficient: Self::normalization_coefficient(Self::DEFAULT_SAMPLE_RATE),
+            channel_policy: ChannelPolicy::default(),
+            pan_center: Self::new_smoothed_value(Self::DEFAULT_PAN_CENTER),
+            pan_spread: Self::new_smoothed_value(Self::DEFAULT_PAN_SPREAD),
+            pan_law: PanLaw::default(),
+            pan_num_channels: Self::DEFAULT_NUM_OUTPUT_CHANNELS,
+        }
+    }
+
+    fn reseed(&mut self, seed: u64) -> &mut Self {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+
+        // start over from the same state as a newly created synthesizer
+        self.grains.fill(Self::new_grain());
+        self.free_grains.clear();
+        self.free_grains.extend((0..self.grains.len()).rev());
+        self.active_grains.clear();
+        self.long_grain_cursor = 0;
+        self.frame_samples.fill(0.0);
+        self.scheduler.reset();
+        self.playhead.set_position(0.0);
+        self.normalization_divisor = 1.0;
+        for smoothed_value in self.smoothed_values_mut() {
+            smoothed_value.skip();
         }
+        self.playhead.set_speed(self.scan_speed.current());
+        self.modulation_matrix.reset();
+        self.voices.fill(NoteVoice::default());
+        self.voice_counter = 0;
+        self.voice_cursor = 0;
+
+        self
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
     }
 
     fn selection_start(&self) -> Percentage {
-        self.selection_start
+        Percentage::from(self.selection_start.target())
     }
 
     fn set_selection_start(&mut self, start: impl Into<Percentage>) -> &mut Self {
-        self.selection_start = start.into();
+        let start = start.into();
+        self.selection_start.set_target(start.get());
 
-        if self.selection_start > self.selection_end {
+        if start > self.selection_end() {
             // move end to "catch up" to the beginning
-            self.set_selection_end(self.selection_start);
+            self.set_selection_end(start);
         }
 
         self
     }
 
     fn selection_end(&self) -> Percentage {
-        self.selection_end
+        Percentage::from(self.selection_end.target())
     }
 
     fn set_selection_end(&mut self, end: impl Into<Percentage>) -> &mut Self {
-        self.selection_end = end.into();
+        let end = end.into();
+        self.selection_end.set_target(end.get());
 
-        if self.selection_end < self.selection_start {
+        if end < self.selection_start() {
             // move beginning to be before the ending
-            self.set_selection_start(self.selection_end);
+            self.set_selection_start(end);
         }
 
         self
@@ -165,51 +386,46 @@ impl GranularSynthesizerAction for GranularSynthesizer {
         // adjust grains to be as long as max number of channels
         if max_num_channels > self.grains.len() {
             let num_extra_samples = max_num_channels - self.grains.len();
+            // new grains are immediately available for use
+            self.free_grains
+                .extend((self.grains.len()..max_num_channels).rev());
             self.grains
                 .extend(vec![GranularSynthesizer::new_grain(); num_extra_samples]);
         } else if max_num_channels < self.grains.len() {
             self.grains.truncate(max_num_channels);
+            // forget about any grains that no longer exist
+            self.free_grains
+                .retain(|&grain_index| grain_index < max_num_channels);
+            self.active_grains
+                .retain(|&grain_index| grain_index < max_num_channels);
         }
 
-        // adjust samples buffer to be as long as max number of channels
-        if max_num_channels > self.output_buffer_samples.len() {
-            let num_extra_samples = max_num_channels - self.output_buffer_samples.len();
-            self.output_buffer_samples
-                .extend(vec![0.0; num_extra_samples]);
-        } else if max_num_channels < self.output_buffer_samples.len() {
-            self.output_buffer_samples.truncate(max_num_channels);
-        }
-
-        // adjust envelope buffer to be as long as max number of channels
-        if max_num_channels > self.output_env_samples.len() {
-            let num_extra_samples = max_num_channels - self.output_env_samples.len();
-            self.output_env_samples.extend(vec![0.0; num_extra_samples]);
-        } else if max_num_channels < self.output_env_samples.len() {
-            self.output_env_samples.truncate(max_num_channels);
-        }
+        // adjust frame buffer to be as long as max number of channels
+        self.frame_samples.resize(max_num_channels, 0.0);
 
         self
     }
 
     fn set_density(&mut self, density: impl Into<Percentage>) -> &mut Self {
-        self.density = density.into();
+        self.density.set_target(density.into().get());
         self
     }
 
     fn density(&self) -> Percentage {
-        self.density
+        Percentage::from(self.density.target())
     }
 
-    fn set_buffer(&mut self, buffer: Arc<Vec<f32>>) -> &mut Self {
-        let buffer_len_samples = buffer.len();
+    fn set_buffer(&mut self, buffer: Arc<SourceBuffer>) -> &mut Self {
+        let buffer_len_samples = buffer.num_frames();
+        let buffer_num_channels = buffer.num_channels();
         self.buffer = buffer;
 
         // replace any buffers that extend past the current buffer length
+        // or read from a channel that the new buffer doesn't have
         for grain in &mut self.grains {
             if grain.end_frame > buffer_len_samples
                 || grain.start_frame > buffer_len_samples
-                || grain.len > buffer_len_samples
-                || grain.current_frame > buffer_len_samples
+                || grain.source_channel >= buffer_num_channels
             {
                 grain.finished = true;
             }
@@ -219,237 +435,1691 @@ impl GranularSynthesizerAction for GranularSynthesizer {
     }
 
     fn refresh_interval(&self) -> u32 {
-        self.refresh_interval
+        let refresh_interval =
+            (Self::DEFAULT_SAMPLE_RATE as f32 / self.scheduler.grains_per_second()).round();
+        Self::sanitize_refresh_interval(refresh_interval as u32)
     }
 
     fn set_refresh_interval(&mut self, refresh_interval: u32) -> &mut Self {
-        self.refresh_interval = Self::sanitize_refresh_interval(refresh_interval);
+        let refresh_interval = Self::sanitize_refresh_interval(refresh_interval);
+        self.set_grains_per_second(Self::DEFAULT_SAMPLE_RATE as f32 / refresh_interval as f32)
+    }
+
+    fn set_grains_per_second(&mut self, grains_per_second: f32) -> &mut Self {
+        self.scheduler
+            .set_grains_per_second(Self::sanitize_grains_per_second(grains_per_second));
 
         self
     }
 
-    fn next_frame(&mut self) -> Vec<f32> {
-        // buy only filtering/refreshing grains at an interval, it blends one sound into the other
-        // decrease speed of refreshes to blend sounds together
-        if self.refresh_counter % self.refresh_interval() == 0 {
-            self.filter_long_grain();
-            self.refresh_grain();
-        }
+    fn grains_per_second(&self) -> f32 {
+        self.scheduler.grains_per_second()
+    }
 
-        self.increment_refresh_counter();
+    fn set_scheduler_mode(&mut self, scheduler_mode: SchedulerMode) -> &mut Self {
+        self.scheduler.set_mode(scheduler_mode);
 
-        self.fill_buffer_and_env_samples();
-        self.frame_data()
+        self
     }
 
-    fn set_sample_rate(&mut self, sample_rate: u32) -> &mut Self {
-        self.sample_rate = sample_rate;
+    fn scheduler_mode(&self) -> SchedulerMode {
+        self.scheduler.mode()
+    }
+
+    fn set_scheduler_jitter(&mut self, scheduler_jitter: impl Into<Percentage>) -> &mut Self {
+        self.scheduler.set_jitter(scheduler_jitter);
 
         self
     }
 
-    fn grain_len_min(&self) -> Percentage {
-        self.grain_len_min
+    fn scheduler_jitter(&self) -> Percentage {
+        self.scheduler.jitter()
     }
 
-    fn grain_len_max(&self) -> Percentage {
-        self.grain_len_max
+    fn set_position_mode(&mut self, position_mode: PositionMode) -> &mut Self {
+        self.position_mode = position_mode;
+
+        self
     }
-}
 
-// internal logic to support public GranularSynthesizer interface
-impl GranularSynthesizer {
-    fn sanitize_grain_len_min(&self, grain_len_min: impl Into<Percentage>) -> Percentage {
-        grain_len_min
-            .into()
-            // should be smaller than existing max
-            .min((self.grain_len_max - Self::GRAIN_LEN_MIN_DIFFERENCE).into())
-            // should be <= largest possible length
-            .min(Self::GRAIN_LEN_MIN_MAX.into())
-            // should be >= smallest possible length
-            .max(Self::GRAIN_LEN_MIN_MIN.into())
+    fn position_mode(&self) -> PositionMode {
+        self.position_mode
     }
 
-    fn sanitize_grain_len_max(&self, grain_len_max: impl Into<Percentage>) -> Percentage {
-        grain_len_max
-            .into()
-            // should be bigger than existing min
-            .max((self.grain_len_min + Self::GRAIN_LEN_MIN_DIFFERENCE).into())
-            // should be >= smallest possible length
-            .max(Self::GRAIN_LEN_MAX_MIN.into())
-            // should be <= largest possible length
-            .min(Self::GRAIN_LEN_MAX_MAX.into())
+    fn set_scan_speed(&mut self, scan_speed: f32) -> &mut Self {
+        self.scan_speed
+            .set_target(Self::sanitize_scan_speed(scan_speed));
+
+        self
     }
 
-    fn grain_len_min_in_samples(&self) -> u32 {
-        let selection_len_in_samples = self.selection_len_in_samples() as f32;
-        let grain_len_min_in_samples = selection_len_in_samples * self.grain_len_min;
-        grain_len_min_in_samples as u32
+    fn scan_speed(&self) -> f32 {
+        self.scan_speed.target()
     }
 
-    fn grain_len_max_in_samples(&self) -> u32 {
-        let selection_len_in_samples = self.selection_len_in_samples() as f32;
-        let grain_len_max_in_samples = selection_len_in_samples * self.grain_len_max;
-        grain_len_max_in_samples as u32
+    fn set_smoothing_ms(&mut self, smoothing_ms: f32) -> &mut Self {
+        self.smoothing_ms = Self::sanitize_smoothing_ms(smoothing_ms);
+        let smoothing_ms = self.smoothing_ms;
+        for smoothed_value in self.smoothed_values_mut() {
+            smoothed_value.set_ramp_ms(smoothing_ms);
+        }
+
+        self
     }
 
-    /// Iterates through array of grains (1 grain for each channel), and refreshes 1
-    /// grain that was previously finished with a new range of buffer indexes.
-    fn refresh_grain(&mut self) {
-        // get start and end of selection
-        let selection_start_index = self.selection_start_in_samples();
-        let selection_end_index = self.selection_end_in_samples();
-        let selection_len_in_samples = self.selection_len_in_samples();
+    fn smoothing_ms(&self) -> f32 {
+        self.smoothing_ms
+    }
 
-        let grain_len_max_samples = self.grain_len_max_in_samples();
-        let grain_len_min_samples = self.grain_len_min_in_samples();
+    fn set_smoothing_type(&mut self, smoothing_type: SmoothingType) -> &mut Self {
+        self.smoothing_type = smoothing_type;
+        for smoothed_value in self.smoothed_values_mut() {
+            smoothed_value.set_smoothing_type(smoothing_type);
+        }
 
-        // get largest possible grain length:
-        // the smaller value between (selection_end - selection_start) & grain_len_max]
-        let largest_possible_grain_len = selection_len_in_samples.min(grain_len_max_samples);
+        self
+    }
 
-        // get smallest possible grain length:
-        // the smaller value between (selection_end - selection_start) & grain_len_min
-        let smallest_possible_grain_len = selection_len_in_samples.min(grain_len_min_samples);
+    fn smoothing_type(&self) -> SmoothingType {
+        self.smoothing_type
+    }
 
-        // if nothing is selected, there's no use in refreshing grains with empty data
-        let selection_is_empty = selection_start_index >= selection_end_index;
-        let grain_len_range_is_small =
-            largest_possible_grain_len - smallest_possible_grain_len <= 20;
+    fn set_play_mode(&mut self, play_mode: PlayMode) -> &mut Self {
+        if play_mode != self.play_mode {
+            self.play_mode = play_mode;
+            // notes from the previous mode shouldn't linger
+            self.voices.fill(NoteVoice::default());
+            self.finish_voice_grains(|_| true);
+        }
 
-        if !selection_is_empty {
-            if let Some(grain) = self.grains.iter_mut().find(|grain| grain.finished) {
-                // get random length
-                let mut grain_len = if grain_len_range_is_small {
-                    // there are fewer errors that can happen with larger grains
-                    // (less divide by 0 errors, etc.)
-                    largest_possible_grain_len
-                } else {
-                    self.rng
-                        .gen_range(smallest_possible_grain_len..=largest_possible_grain_len)
-                };
-
-                let largest_start_index = selection_end_index - grain_len;
-
-                // if the largest possible start index and the actual start index are very close,
-                // then just use the start index (prevents silence when min & max are both at 1.0)
-                let start_index_range_is_close = (largest_start_index - selection_start_index) < 20;
-
-                // if the start is close, then that means the the grain should just play the whole buffer
-                if start_index_range_is_close {
-                    grain_len = selection_end_index;
-                }
+        self
+    }
+
+    fn play_mode(&self) -> PlayMode {
+        self.play_mode
+    }
+
+    fn note_on(&mut self, note: u8, velocity: u8) -> &mut Self {
+        if velocity == 0 {
+            return self.note_off(note);
+        }
 
-                let grain_start_index = if start_index_range_is_close {
-                    selection_start_index
-                } else {
-                    // get random index inside selection
-                    self.rng
-                        .gen_range(selection_start_index..=largest_start_index)
-                };
+        let velocity_sensitivity = self.velocity_sensitivity.get();
+        let velocity_gain =
+            1.0 - velocity_sensitivity + velocity_sensitivity * (velocity as f32 / 127.0);
+
+        let voices = &self.voices[..self.voice_limit];
+        let voice_index = voices
+            // retrigger the same note if it's still sounding
+            .iter()
+            .position(|voice| !voice.is_idle() && voice.note == note)
+            // otherwise, use a silent voice
+            .or_else(|| voices.iter().position(|voice| voice.is_idle()))
+            // otherwise, take over the oldest voice (preferring voices that have already been released)
+            .or_else(|| {
+                voices
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, voice)| (voice.held, voice.started_at))
+                    .map(|(i, _)| i)
+            });
+
+        if let Some(voice_index) = voice_index {
+            // grains that were started for the voice's previous note would keep playing at that note's pitch
+            let voice = &self.voices[voice_index];
+            if !voice.is_idle() && voice.note != note {
+                self.release_voice_grains(voice_index);
+            }
 
-                let grain_end_index = grain_start_index + grain_len;
+            let voice = &mut self.voices[voice_index];
+            voice.note = note;
+            voice.velocity_gain = velocity_gain;
+            voice.held = true;
+            voice.started_at = self.voice_counter;
+            // envelopes restart from their current amplitude, so taking over a voice doesn't click
+            voice.envelope.note_on();
+            self.voice_counter += 1;
+        }
 
-                let new_grain = Grain::new(grain_start_index as usize, grain_end_index as usize);
+        self
+    }
 
-                *grain = new_grain;
+    fn note_off(&mut self, note: u8) -> &mut Self {
+        for voice in &mut self.voices {
+            if voice.held && voice.note == note {
+                voice.held = false;
+                voice.envelope.note_off();
             }
         }
+
+        self
     }
 
-    fn selection_start_in_samples(&self) -> u32 {
-        ((self.buffer.len() as f32 * self.selection_start) as u32)
-            .max(0)
-            .min(self.buffer.len() as u32)
+    fn all_notes_off(&mut self) -> &mut Self {
+        for voice in &mut self.voices {
+            voice.held = false;
+            voice.envelope.note_off();
+        }
+
+        self
     }
 
-    fn selection_end_in_samples(&self) -> u32 {
-        ((self.buffer.len() as f32 * self.selection_end) as u32)
-            .max(0)
-            .min(self.buffer.len() as u32)
+    fn set_root_note(&mut self, root_note: u8) -> &mut Self {
+        self.root_note = root_note.min(127);
+
+        self
     }
 
-    fn selection_len_in_samples(&self) -> u32 {
-        (self.selection_end_in_samples() - self.selection_start_in_samples()).max(0)
+    fn root_note(&self) -> u8 {
+        self.root_note
     }
 
-    /// Prevent long grains from lingering when max length and/or selection has changed
-    ///
-    /// Finds a single grain that exceeds the current selection length and marks it as finished
-    /// If no grain is found that exceeds the current selection length, no other action is taken.
-    fn filter_long_grain(&mut self) {
-        let grain_len_max_in_samples = self.grain_len_max_in_samples() as usize;
-        let selection_len_in_samples = self.selection_len_in_samples() as usize;
+    fn set_voice_limit(&mut self, voice_limit: usize) -> &mut Self {
+        self.voice_limit = voice_limit.clamp(1, Self::MAX_VOICES);
+
+        // voices past the new limit can't be reached anymore, so let them fade out
+        for voice in &mut self.voices[self.voice_limit..] {
+            voice.held = false;
+            voice.envelope.note_off();
+        }
+
+        self
+    }
+
+    fn voice_limit(&self) -> usize {
+        self.voice_limit
+    }
+
+    fn set_adsr(&mut self, adsr: Adsr) -> &mut Self {
+        self.adsr = adsr;
+
+        self
+    }
+
+    fn adsr(&self) -> Adsr {
+        self.adsr
+    }
+
+    fn set_velocity_sensitivity(
+        &mut self,
+        velocity_sensitivity: impl Into<Percentage>,
+    ) -> &mut Self {
+        self.velocity_sensitivity = velocity_sensitivity.into();
+
+        self
+    }
+
+    fn velocity_sensitivity(&self) -> Percentage {
+        self.velocity_sensitivity
+    }
+
+    fn set_lfo(&mut self, index: usize, lfo: Lfo) -> &mut Self {
+        self.modulation_matrix.set_lfo(index, &lfo);
+
+        self
+    }
+
+    fn lfo(&self, index: usize) -> Option<Lfo> {
+        self.modulation_matrix.lfo(index).cloned()
+    }
+
+    fn add_modulation_routing(&mut self, routing: ModulationRouting) -> &mut Self {
+        self.modulation_matrix.add_routing(routing);
+
+        self
+    }
+
+    fn remove_modulation_routing(&mut self, index: usize) -> &mut Self {
+        self.modulation_matrix.remove_routing(index);
+
+        self
+    }
+
+    fn modulation_routings(&self) -> Vec<ModulationRouting> {
+        self.modulation_matrix.routings().to_vec()
+    }
+
+    fn set_spray_ms(&mut self, spray_ms: f32) -> &mut Self {
+        self.spray_ms = Self::sanitize_spray_ms(spray_ms);
+
+        self
+    }
+
+    fn spray_ms(&self) -> f32 {
+        self.spray_ms
+    }
+
+    fn set_playhead(&mut self, playhead: impl Into<Percentage>) -> &mut Self {
+        let position = self.buffer.num_frames() as f64 * playhead.into().get() as f64;
+        self.playhead.set_position(position);
 
-        if let Some(grain) = self.grains.iter_mut().find(|grain| {
-            let remaining_grain_samples = grain.remaining_samples();
+        self
+    }
 
-            remaining_grain_samples > grain_len_max_in_samples
-                || remaining_grain_samples > selection_len_in_samples
-        }) {
-            grain.finished = true;
+    fn playhead(&self) -> Percentage {
+        if self.buffer.is_empty() {
+            return Percentage::from(0.0);
         }
+
+        Percentage::from((self.playhead.position() / self.buffer.num_frames() as f64) as f32)
     }
 
-    /// Fills in buffer & envelope sample data for each channel
-    /// based on the current state of the grain for each channel.
-    ///
-    /// Each buffer sample and envelope sample must be coordinated/aligned to prevent
-    /// audio clipping and/or unexpected audio results.
-    fn fill_buffer_and_env_samples(&mut self) {
-        // get value of each grain's current index in the buffer for each channel
-        self.grains.iter_mut().enumerate().for_each(|(i, grain)| {
-            // this can happen if a grain has finished, but the selection
-            // size is 0, so the grain can't get refreshed with more data
-            if grain.finished {
-                self.output_buffer_samples[i] = 0.0;
-                self.output_env_samples[i] = 0.0;
-                return;
+    fn next_frame(&mut self) -> Vec<f32> {
+        self.render_frame();
+        self.frame().to_vec()
+    }
+
+    fn process_block(&mut self, out: &mut [f32], channels: usize) {
+        if channels == 0 {
+            return;
+        }
+
+        self.set_pan_num_channels(channels);
+
+        for output_frame in out.chunks_mut(channels) {
+            self.render_frame();
+            output_frame.fill(0.0);
+            self.pan_frame(|channel, value| {
+                if let Some(sample) = output_frame.get_mut(channel) {
+                    *sample += value;
+                }
+            });
+        }
+    }
+
+    fn process_block_planar(&mut self, out: &mut [&mut [f32]]) {
+        let channels = out.len();
+        if channels == 0 {
+            return;
+        }
+
+        self.set_pan_num_channels(channels);
+
+        let num_frames = out.iter().map(|channel| channel.len()).min().unwrap_or(0);
+        for frame_i in 0..num_frames {
+            self.render_frame();
+            for channel in out.iter_mut() {
+                channel[frame_i] = 0.0;
             }
+            self.pan_frame(|channel, value| {
+                if let Some(channel) = out.get_mut(channel) {
+                    channel[frame_i] += value;
+                }
+            });
+        }
+    }
 
-            let grain_len = grain.len.max(1) as f32;
-            let envelope_percent = ((grain.current_frame - grain.start_frame) as f32) / grain_len;
-            let envelope_value =
-                utils::generate_triangle_envelope_value_from_percent(envelope_percent);
-            let sample_value = self.buffer[grain.current_frame];
+    fn copy_last_frame(&self, frame_out: &mut Vec<f32>) {
+        frame_out.clear();
+        frame_out.extend_from_slice(self.frame());
+    }
 
-            self.output_buffer_samples[i] = sample_value;
-            self.output_env_samples[i] = envelope_value;
+    fn set_sample_rate(&mut self, sample_rate: u32) -> &mut Self {
+        self.sample_rate = sample_rate;
+        self.normalization_coefficient = Self::normalization_coefficient(sample_rate);
+        for smoothed_value in self.smoothed_values_mut() {
+            smoothed_value.set_sample_rate(sample_rate);
+        }
 
-            grain.next_frame();
-        });
+        self
     }
 
-    /// this represents the number of channels actually in use
-    fn num_channels_for_frame(&self) -> usize {
-        (self.max_num_channels as f32 * self.density) as usize
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn grain_len_min(&self) -> Percentage {
+        self.grain_len_min
+    }
+
+    fn grain_len_max(&self) -> Percentage {
+        self.grain_len_max
+    }
+
+    fn set_grain_len_mode(&mut self, grain_len_mode: GrainLenMode) -> &mut Self {
+        self.grain_len_mode = grain_len_mode;
+
+        self
+    }
+
+    fn grain_len_mode(&self) -> GrainLenMode {
+        self.grain_len_mode
+    }
+
+    fn set_grain_len_min_ms(&mut self, grain_len_min_ms: f32) -> &mut Self {
+        self.grain_len_min_ms = Self::sanitize_grain_len_ms(grain_len_min_ms);
+
+        // increase current grain length max to be at least as long as the new min
+        if self.grain_len_min_ms > self.grain_len_max_ms {
+            self.grain_len_max_ms = self.grain_len_min_ms;
+        }
+
+        self
     }
 
-    /// Combines current buffer and envelope sample values to calculate a full audio frame
-    /// (where each channel gets a single audio output value).
-    fn frame_data(&self) -> Vec<f32> {
-        let num_channels_for_frame = self.num_channels_for_frame();
-        let mut frame_data = vec![0.0; num_channels_for_frame];
-        for (i, channel) in frame_data.iter_mut().enumerate() {
-            let buffer_sample = self
-                .output_buffer_samples
-                .get(i)
-                .copied()
-                .expect("output_buffer_samples length should match max number of grains");
+    fn grain_len_min_ms(&self) -> f32 {
+        self.grain_len_min_ms
+    }
 
-            let envelope_sample = self
-                .output_env_samples
-                .get(i)
-                .copied()
-                .expect("output_env_samples length should match max number of grains");
+    fn set_grain_len_max_ms(&mut self, grain_len_max_ms: f32) -> &mut Self {
+        self.grain_len_max_ms = Self::sanitize_grain_len_ms(grain_len_max_ms);
 
-            // if these buffers have not been filled up yet, just return 0.0
-            *channel = buffer_sample * envelope_sample;
+        // decrease current grain length min to be no longer than the new max
+        if self.grain_len_max_ms < self.grain_len_min_ms {
+            self.grain_len_min_ms = self.grain_len_max_ms;
         }
-        frame_data
+
+        self
+    }
+
+    fn grain_len_max_ms(&self) -> f32 {
+        self.grain_len_max_ms
+    }
+
+    fn set_pitch_semitones(&mut self, semitones: f32) -> &mut Self {
+        self.pitch_semitones = Self::sanitize_pitch_semitones(semitones);
+
+        self
+    }
+
+    fn pitch_semitones(&self) -> f32 {
+        self.pitch_semitones
+    }
+
+    fn set_pitch_cents(&mut self, cents: f32) -> &mut Self {
+        self.pitch_cents = Self::sanitize_pitch_cents(cents);
+
+        self
+    }
+
+    fn pitch_cents(&self) -> f32 {
+        self.pitch_cents
+    }
+
+    fn set_pitch_spread(&mut self, spread: f32) -> &mut Self {
+        self.pitch_spread = Self::sanitize_pitch_spread(spread);
+
+        self
+    }
+
+    fn pitch_spread(&self) -> f32 {
+        self.pitch_spread
+    }
+
+    fn set_pitch_quantizer(&mut self, pitch_quantizer: Option<PitchQuantizer>) -> &mut Self {
+        self.pitch_quantizer = pitch_quantizer;
+
+        self
+    }
+
+    fn pitch_quantizer(&self) -> Option<PitchQuantizer> {
+        self.pitch_quantizer.clone()
+    }
+
+    fn set_interpolation(&mut self, interpolation: Interpolation) -> &mut Self {
+        self.interpolation = interpolation;
+
+        self
+    }
+
+    fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    fn set_reverse_probability(&mut self, reverse_probability: impl Into<Percentage>) -> &mut Self {
+        self.reverse_probability = reverse_probability.into();
+
+        self
+    }
+
+    fn reverse_probability(&self) -> Percentage {
+        self.reverse_probability
+    }
+
+    fn set_envelope_shape(&mut self, envelope_shape: EnvelopeShape) -> &mut Self {
+        self.envelope_shape = envelope_shape;
+
+        self
+    }
+
+    fn envelope_shape(&self) -> EnvelopeShape {
+        self.envelope_shape
+    }
+
+    fn set_envelope_skew(&mut self, envelope_skew: impl Into<Percentage>) -> &mut Self {
+        self.envelope_skew = envelope_skew.into();
+
+        self
+    }
+
+    fn envelope_skew(&self) -> Percentage {
+        self.envelope_skew
+    }
+
+    fn set_amplitude_min(&mut self, amplitude_min: impl Into<Percentage>) -> &mut Self {
+        self.amplitude_min = amplitude_min.into();
+
+        if self.amplitude_min > self.amplitude_max {
+            // move max to "catch up" to the min
+            self.amplitude_max = self.amplitude_min;
+        }
+
+        self
+    }
+
+    fn amplitude_min(&self) -> Percentage {
+        self.amplitude_min
+    }
+
+    fn set_amplitude_max(&mut self, amplitude_max: impl Into<Percentage>) -> &mut Self {
+        self.amplitude_max = amplitude_max.into();
+
+        if self.amplitude_max < self.amplitude_min {
+            // move min to be below the max
+            self.amplitude_min = self.amplitude_max;
+        }
+
+        self
+    }
+
+    fn amplitude_max(&self) -> Percentage {
+        self.amplitude_max
+    }
+
+    fn set_grain_filter_mode(&mut self, grain_filter_mode: GrainFilterMode) -> &mut Self {
+        self.grain_filter_mode = grain_filter_mode;
+
+        self
+    }
+
+    fn grain_filter_mode(&self) -> GrainFilterMode {
+        self.grain_filter_mode
+    }
+
+    fn set_grain_filter_cutoff_min_hz(&mut self, cutoff_min_hz: f32) -> &mut Self {
+        self.grain_filter_cutoff_min_hz = Self::sanitize_grain_filter_cutoff_hz(cutoff_min_hz);
+
+        if self.grain_filter_cutoff_min_hz > self.grain_filter_cutoff_max_hz {
+            // move max to "catch up" to the min
+            self.grain_filter_cutoff_max_hz = self.grain_filter_cutoff_min_hz;
+        }
+
+        self
+    }
+
+    fn grain_filter_cutoff_min_hz(&self) -> f32 {
+        self.grain_filter_cutoff_min_hz
+    }
+
+    fn set_grain_filter_cutoff_max_hz(&mut self, cutoff_max_hz: f32) -> &mut Self {
+        self.grain_filter_cutoff_max_hz = Self::sanitize_grain_filter_cutoff_hz(cutoff_max_hz);
+
+        if self.grain_filter_cutoff_max_hz < self.grain_filter_cutoff_min_hz {
+            // move min to be below the max
+            self.grain_filter_cutoff_min_hz = self.grain_filter_cutoff_max_hz;
+        }
+
+        self
+    }
+
+    fn grain_filter_cutoff_max_hz(&self) -> f32 {
+        self.grain_filter_cutoff_max_hz
+    }
+
+    fn set_grain_filter_resonance(&mut self, resonance: impl Into<Percentage>) -> &mut Self {
+        self.grain_filter_resonance = resonance.into();
+
+        self
+    }
+
+    fn grain_filter_resonance(&self) -> Percentage {
+        self.grain_filter_resonance
+    }
+
+    fn set_normalize(&mut self, normalize: bool) -> &mut Self {
+        self.normalize = normalize;
+
+        self
+    }
+
+    fn normalize(&self) -> bool {
+        self.normalize
+    }
+
+    fn set_channel_policy(&mut self, channel_policy: ChannelPolicy) -> &mut Self {
+        self.channel_policy = channel_policy;
+
+        self
+    }
+
+    fn channel_policy(&self) -> ChannelPolicy {
+        self.channel_policy
+    }
+
+    fn set_pan_center(&mut self, pan_center: f32) -> &mut Self {
+        self.pan_center
+            .set_target(Self::sanitize_pan_center(pan_center));
+
+        self
+    }
+
+    fn pan_center(&self) -> f32 {
+        self.pan_center.target()
+    }
+
+    fn set_pan_spread(&mut self, pan_spread: impl Into<Percentage>) -> &mut Self {
+        self.pan_spread.set_target(pan_spread.into().get());
+
+        self
+    }
+
+    fn pan_spread(&self) -> Percentage {
+        Percentage::from(self.pan_spread.target())
+    }
+
+    fn set_pan_law(&mut self, pan_law: PanLaw) -> &mut Self {
+        self.pan_law = pan_law;
+        self.update_pan_gains();
+
+        self
+    }
+
+    fn pan_law(&self) -> PanLaw {
+        self.pan_law
+    }
+
+    fn apply_preset(&mut self, preset: &Preset) -> &mut Self {
+        // the play mode is changed first, since changing it resets all voices
+        self.set_play_mode(preset.play_mode)
+            .set_selection_start(preset.selection_start)
+            .set_selection_end(preset.selection_end)
+            .set_grain_len_mode(preset.grain_len_mode)
+            .set_grain_len_range(preset.grain_len_min, preset.grain_len_max)
+            // the max is set first, but either setter moves the other bound out of the way,
+            // so a preset's range is kept as long as its min is no greater than its max
+            .set_grain_len_max_ms(preset.grain_len_max_ms)
+            .set_grain_len_min_ms(preset.grain_len_min_ms)
+            .set_density(preset.density)
+            .set_grains_per_second(preset.grains_per_second)
+            .set_scheduler_mode(preset.scheduler_mode)
+            .set_scheduler_jitter(preset.scheduler_jitter)
+            .set_position_mode(preset.position_mode)
+            .set_scan_speed(preset.scan_speed)
+            .set_spray_ms(preset.spray_ms)
+            .set_pitch_semitones(preset.pitch_semitones)
+            .set_pitch_cents(preset.pitch_cents)
+            .set_pitch_spread(preset.pitch_spread)
+            .set_pitch_quantizer(preset.pitch_quantizer.clone())
+            .set_interpolation(preset.interpolation)
+            .set_reverse_probability(preset.reverse_probability)
+            .set_envelope_shape(preset.envelope_shape)
+            .set_envelope_skew(preset.envelope_skew)
+            .set_amplitude_max(preset.amplitude_max)
+            .set_amplitude_min(preset.amplitude_min)
+            .set_grain_filter_mode(preset.grain_filter_mode)
+            // like the grain lengths in milliseconds, either setter moves the other bound out of the way
+            .set_grain_filter_cutoff_max_hz(preset.grain_filter_cutoff_max_hz)
+            .set_grain_filter_cutoff_min_hz(preset.grain_filter_cutoff_min_hz)
+            .set_grain_filter_resonance(preset.grain_filter_resonance)
+            .set_normalize(preset.normalize)
+            .set_smoothing_ms(preset.smoothing_ms)
+            .set_smoothing_type(preset.smoothing_type)
+            .set_channel_policy(preset.channel_policy)
+            .set_pan_center(preset.pan_center)
+            .set_pan_spread(preset.pan_spread)
+            .set_pan_law(preset.pan_law)
+            .set_root_note(preset.root_note)
+            .set_voice_limit(preset.voice_limit)
+            .set_velocity_sensitivity(preset.velocity_sensitivity);
+
+        // rebuilt through their constructors, so that out-of-range values are clamped
+        let adsr = preset.adsr;
+        self.set_adsr(Adsr::new(
+            adsr.attack_ms,
+            adsr.decay_ms,
+            adsr.sustain,
+            adsr.release_ms,
+        ));
+
+        // LFOs missing from the preset are restored to their defaults
+        for index in 0..ModulationMatrix::NUM_LFOS {
+            let lfo = preset.lfos.get(index).map_or_else(Lfo::default, |lfo| {
+                Lfo::new(lfo.shape(), lfo.rate(), lfo.depth(), lfo.phase_offset())
+            });
+            self.set_lfo(index, lfo);
+        }
+
+        self.modulation_matrix.clear_routings();
+        for routing in &preset.modulation_routings {
+            self.add_modulation_routing(ModulationRouting::new(
+                routing.lfo_index,
+                routing.target,
+                routing.amount,
+            ));
+        }
+
+        self
+    }
+
+    fn preset(&self) -> Preset {
+        Preset {
+            version: Preset::CURRENT_VERSION,
+            selection_start: self.selection_start().get(),
+            selection_end: self.selection_end().get(),
+            grain_len_mode: self.grain_len_mode(),
+            grain_len_min: self.grain_len_min().get(),
+            grain_len_max: self.grain_len_max().get(),
+            grain_len_min_ms: self.grain_len_min_ms(),
+            grain_len_max_ms: self.grain_len_max_ms(),
+            density: self.density().get(),
+            grains_per_second: self.grains_per_second(),
+            scheduler_mode: self.scheduler_mode(),
+            scheduler_jitter: self.scheduler_jitter().get(),
+            position_mode: self.position_mode(),
+            scan_speed: self.scan_speed(),
+            spray_ms: self.spray_ms(),
+            pitch_semitones: self.pitch_semitones(),
+            pitch_cents: self.pitch_cents(),
+            pitch_spread: self.pitch_spread(),
+            interpolation: self.interpolation(),
+            reverse_probability: self.reverse_probability().get(),
+            envelope_shape: self.envelope_shape(),
+            envelope_skew: self.envelope_skew().get(),
+            amplitude_min: self.amplitude_min().get(),
+            amplitude_max: self.amplitude_max().get(),
+            grain_filter_mode: self.grain_filter_mode(),
+            grain_filter_cutoff_min_hz: self.grain_filter_cutoff_min_hz(),
+            grain_filter_cutoff_max_hz: self.grain_filter_cutoff_max_hz(),
+            grain_filter_resonance: self.grain_filter_resonance().get(),
+            normalize: self.normalize(),
+            smoothing_ms: self.smoothing_ms(),
+            smoothing_type: self.smoothing_type(),
+            channel_policy: self.channel_policy(),
+            pan_center: self.pan_center(),
+            pan_spread: self.pan_spread().get(),
+            pan_law: self.pan_law(),
+            play_mode: self.play_mode(),
+            root_note: self.root_note(),
+            voice_limit: self.voice_limit(),
+            velocity_sensitivity: self.velocity_sensitivity().get(),
+            pitch_quantizer: self.pitch_quantizer(),
+            adsr: self.adsr(),
+            lfos: (0..ModulationMatrix::NUM_LFOS)
+                .filter_map(|index| self.lfo(index))
+                .collect(),
+            modulation_routings: self.modulation_routings(),
+        }
+    }
+}
+
+// internal logic to support public GranularSynthesizer interface
+impl GranularSynthesizer {
+    /// Sets both relative grain lengths at once, so that neither is clamped against the other's old value
+    fn set_grain_len_range(
+        &mut self,
+        grain_len_min: impl Into<Percentage>,
+        grain_len_max: impl Into<Percentage>,
+    ) -> &mut Self {
+        self.grain_len_min = Self::GRAIN_LEN_MIN_MIN.into();
+        self.grain_len_max = Self::GRAIN_LEN_MAX_MAX.into();
+        self.set_grain_len_min(grain_len_min)
+            .set_grain_len_max(grain_len_max)
+    }
+
+    fn sanitize_grain_len_min(&self, grain_len_min: impl Into<Percentage>) -> Percentage {
+        grain_len_min
+            .into()
+            // should be smaller than existing max
+            .min((self.grain_len_max - Self::GRAIN_LEN_MIN_DIFFERENCE).into())
+            // should be <= largest possible length
+            .min(Self::GRAIN_LEN_MIN_MAX.into())
+            // should be >= smallest possible length
+            .max(Self::GRAIN_LEN_MIN_MIN.into())
+    }
+
+    fn sanitize_grain_len_max(&self, grain_len_max: impl Into<Percentage>) -> Percentage {
+        grain_len_max
+            .into()
+            // should be bigger than existing min
+            .max((self.grain_len_min + Self::GRAIN_LEN_MIN_DIFFERENCE).into())
+            // should be >= smallest possible length
+            .max(Self::GRAIN_LEN_MAX_MIN.into())
+            // should be <= largest possible length
+            .min(Self::GRAIN_LEN_MAX_MAX.into())
+    }
+
+    fn grain_len_min_in_samples(&self) -> u32 {
+        match self.grain_len_mode {
+            GrainLenMode::Relative => {
+                let selection_len_in_samples = self.selection_len_in_samples() as f32;
+                let grain_len_min_in_samples = selection_len_in_samples * self.grain_len_min;
+                grain_len_min_in_samples as u32
+            }
+            GrainLenMode::Absolute => self.ms_to_samples(self.grain_len_min_ms),
+        }
+    }
+
+    fn grain_len_max_in_samples(&self) -> u32 {
+        match self.grain_len_mode {
+            GrainLenMode::Relative => {
+                let selection_len_in_samples = self.selection_len_in_samples() as f32;
+                let grain_len_max_in_samples = selection_len_in_samples * self.grain_len_max;
+                grain_len_max_in_samples as u32
+            }
+            GrainLenMode::Absolute => self.ms_to_samples(self.grain_len_max_ms),
+        }
+    }
+
+    fn ms_to_samples(&self, ms: f32) -> u32 {
+        (ms / 1000.0 * self.sample_rate as f32) as u32
+    }
+
+    /// Takes 1 grain that was previously finished off of the free list
+    /// and refreshes it with a new range of buffer indexes.
+    fn refresh_grain(&mut self) {
+        // get start and end of selection
+        let selection_start_index = self.selection_start_in_samples();
+        let selection_end_index = self.selection_end_in_samples();
+        let selection_len_in_samples = self.selection_len_in_samples();
+
+        // grain length modulation only affects new grains, so that playing grains are never cut short
+        let grain_len_modulation = self.grain_len_modulation();
+        let grain_len_max_samples =
+            (self.grain_len_max_in_samples() as f32 * grain_len_modulation) as u32;
+        let grain_len_min_samples =
+            (self.grain_len_min_in_samples() as f32 * grain_len_modulation) as u32;
+
+        // get largest possible grain length:
+        // the smaller value between (selection_end - selection_start) & grain_len_max]
+        let largest_possible_grain_len = selection_len_in_samples.min(grain_len_max_samples);
+
+        // get smallest possible grain length:
+        // the smaller value between (selection_end - selection_start) & grain_len_min
+        let smallest_possible_grain_len = selection_len_in_samples.min(grain_len_min_samples);
+
+        // if nothing is selected, there's no use in refreshing grains with empty data
+        let selection_is_empty = selection_start_index >= selection_end_index;
+        let grain_len_range_is_small =
+            largest_possible_grain_len - smallest_possible_grain_len <= 20;
+
+        if selection_is_empty {
+            return;
+        }
+
+        // only as many grains as `density` allows can play at once
+        if self.active_grains.len() >= self.num_channels_for_frame() {
+            return;
+        }
+
+        // the grain is only removed from the free list once it's certain it will be used
+        let grain_index = match self.free_grains.last() {
+            Some(&grain_index) => grain_index,
+            None => return,
+        };
+
+        // when playing notes, grains can only be started for notes that are sounding
+        let voice = match self.play_mode {
+            PlayMode::Drone => None,
+            PlayMode::Notes => match self.next_sounding_voice() {
+                Some(voice) => Some(voice),
+                None => return,
+            },
+        };
+
+        let mut playback_rate = self.new_grain_playback_rate();
+        if let Some(voice) = voice {
+            let semitones = self.voices[voice].note as f64 - self.root_note as f64;
+            playback_rate *= 2.0_f64.powf(semitones / 12.0);
+        }
+
+        // get random length (in output frames)
+        let mut grain_len = if grain_len_range_is_small {
+            // there are fewer errors that can happen with larger grains
+            // (less divide by 0 errors, etc.)
+            largest_possible_grain_len
+        } else {
+            self.rng
+                .gen_range(smallest_possible_grain_len..=largest_possible_grain_len)
+        };
+
+        // transposed grains read through more (or less) of the buffer than their length,
+        // so shorten the grain if it would otherwise read past the end of the selection
+        let mut grain_span = Grain::buffer_span(grain_len as usize, playback_rate) as u32;
+        if grain_span > selection_len_in_samples {
+            grain_len = (selection_len_in_samples as f64 / playback_rate) as u32;
+            grain_span = Grain::buffer_span(grain_len as usize, playback_rate) as u32;
+        }
+
+        if grain_len == 0 {
+            return;
+        }
+
+        let largest_start_index = selection_end_index - grain_span;
+
+        // if the largest possible start index and the actual start index are very close,
+        // then just use the start index (prevents silence when min & max are both at 1.0)
+        let start_index_range_is_close = (largest_start_index - selection_start_index) < 20;
+
+        let grain_start_index = if start_index_range_is_close {
+            selection_start_index
+        } else {
+            match self.position_mode {
+                // get random index inside selection
+                PositionMode::Random => self
+                    .rng
+                    .gen_range(selection_start_index..=largest_start_index),
+                // get random index near the playhead (that still fits inside the selection)
+                PositionMode::Scan => self
+                    .new_grain_scan_position()
+                    .clamp(selection_start_index, largest_start_index),
+            }
+        };
+
+        let direction = if self.rng.gen_bool(self.reverse_probability.get() as f64) {
+            GrainDirection::Reverse
+        } else {
+            GrainDirection::Forward
+        };
+
+        let amplitude = self.new_grain_amplitude();
+        let filter = self.new_grain_filter();
+        let pan = self.new_grain_pan();
+        let (pan_channel, pan_gains) = self.pan_law.output_gains(pan, self.pan_num_channels);
+        let source_channel =
+            self.channel_policy
+                .source_channel(self.buffer.num_channels(), pan, &mut self.rng);
+
+        self.free_grains.pop();
+        self.active_grains.push(grain_index);
+        self.grains[grain_index] = Grain {
+            envelope_shape: self.envelope_shape,
+            envelope_skew: self.envelope_skew.get(),
+            pan,
+            pan_channel,
+            pan_gains,
+            source_channel,
+            amplitude,
+            filter,
+            voice,
+            len_modulation: grain_len_modulation,
+            ..Grain::new(
+                grain_start_index as usize,
+                grain_len as usize,
+                playback_rate,
+                direction,
+            )
+        };
+    }
+
+    /// Picks a random amplitude for a new grain between `amplitude_min` and `amplitude_max`
+    fn new_grain_amplitude(&mut self) -> f32 {
+        let amplitude_min = self.amplitude_min.get();
+        let amplitude_max = self.amplitude_max.get();
+        if amplitude_max > amplitude_min {
+            self.rng.gen_range(amplitude_min..=amplitude_max)
+        } else {
+            amplitude_max
+        }
+    }
+
+    /// Creates a filter for a new grain (if grain filtering is on),
+    /// with a random cutoff between `grain_filter_cutoff_min_hz` and `grain_filter_cutoff_max_hz`
+    fn new_grain_filter(&mut self) -> Option<StateVariableFilter> {
+        if self.grain_filter_mode == GrainFilterMode::Off {
+            return None;
+        }
+
+        // picked on a logarithmic scale, so that low cutoffs are as likely as high ones
+        let cutoff_min_log = self.grain_filter_cutoff_min_hz.ln();
+        let cutoff_max_log = self.grain_filter_cutoff_max_hz.ln();
+        let cutoff_hz = if cutoff_max_log > cutoff_min_log {
+            self.rng.gen_range(cutoff_min_log..=cutoff_max_log).exp()
+        } else {
+            self.grain_filter_cutoff_max_hz
+        };
+
+        Some(StateVariableFilter::new(
+            self.grain_filter_mode,
+            cutoff_hz,
+            self.grain_filter_resonance.get(),
+            self.sample_rate,
+        ))
+    }
+
+    /// Picks a buffer frame within `spray_ms` of the playhead for a new grain to start from
+    fn new_grain_scan_position(&mut self) -> u32 {
+        let spray_in_samples = self.spray_ms / 1000.0 * self.buffer.sample_rate() as f32;
+        let spray = if spray_in_samples > 0.0 {
+            self.rng.gen_range(-spray_in_samples..=spray_in_samples)
+        } else {
+            0.0
+        };
+
+        (self.playhead.position() + spray as f64).max(0.0) as u32
+    }
+
+    /// Picks a pan position for a new grain, somewhere within `pan_spread` of the `pan_center`
+    fn new_grain_pan(&mut self) -> f32 {
+        let pan_spread = self.pan_spread.current();
+        let spread = if pan_spread > 0.0 {
+            self.rng.gen_range(-pan_spread..=pan_spread)
+        } else {
+            0.0
+        };
+
+        let modulation = self.modulation_matrix.offset(ModulationTarget::Pan);
+        (self.pan_center.current() + spread + modulation).clamp(Self::PAN_MIN, Self::PAN_MAX)
+    }
+
+    /// Recalculates every grain's output channel gains if the number of output channels has changed
+    fn set_pan_num_channels(&mut self, num_channels: usize) {
+        if num_channels != self.pan_num_channels {
+            self.pan_num_channels = num_channels;
+            self.update_pan_gains();
+        }
+    }
+
+    /// Recalculates every grain's output channel gains from its pan position
+    fn update_pan_gains(&mut self) {
+        for grain in &mut self.grains {
+            let (pan_channel, pan_gains) =
+                self.pan_law.output_gains(grain.pan, self.pan_num_channels);
+            grain.pan_channel = pan_channel;
+            grain.pan_gains = pan_gains;
+        }
+    }
+
+    /// Pans every channel of the most recently rendered frame into the output channels,
+    /// calling `add_to_output` with each output channel index and the value to add to it.
+    fn pan_frame(&self, mut add_to_output: impl FnMut(usize, f32)) {
+        let scale_divisor = if self.normalize {
+            self.normalization_divisor
+        } else {
+            // the more grains that are summed into each output channel, the quieter each grain should be
+            // (this keeps output levels in line with those produced by `mixdown`)
+            (self.num_channels_for_frame() as f32 / self.pan_num_channels as f32)
+                .cbrt()
+                .max(0.01)
+        };
+
+        // only playing grains can produce any output
+        for &grain_index in &self.active_grains {
+            let grain = &self.grains[grain_index];
+            let value = self.frame_samples[grain_index] / scale_divisor;
+            add_to_output(grain.pan_channel, value * grain.pan_gains[0]);
+            add_to_output(grain.pan_channel + 1, value * grain.pan_gains[1]);
+        }
+    }
+
+    /// Picks a playback rate for a new grain based on the current pitch settings
+    fn new_grain_playback_rate(&mut self) -> f64 {
+        let spread = if self.pitch_spread > 0.0 {
+            self.rng.gen_range(-self.pitch_spread..=self.pitch_spread)
+        } else {
+            0.0
+        };
+        let modulation = self.modulation_matrix.offset(ModulationTarget::Pitch)
+            * Self::MODULATION_PITCH_SEMITONES;
+        let mut transposition = spread + modulation;
+        if let Some(pitch_quantizer) = &self.pitch_quantizer {
+            transposition = pitch_quantizer.quantize(transposition);
+        }
+        let semitones = self.pitch_semitones + self.pitch_cents / 100.0 + transposition;
+
+        2.0_f64.powf(semitones as f64 / 12.0)
+    }
+
+    /// Scales the lengths of new grains by the current grain length modulation
+    fn grain_len_modulation(&self) -> f32 {
+        2.0_f32.powf(self.modulation_matrix.offset(ModulationTarget::GrainLen))
+    }
+
+    fn selection_start_in_samples(&self) -> u32 {
+        let selection_start = (self.selection_start.current()
+            + self
+                .modulation_matrix
+                .offset(ModulationTarget::SelectionStart))
+        .clamp(0.0, 1.0);
+        ((self.buffer.num_frames() as f32 * selection_start) as u32)
+            .min(self.buffer.num_frames() as u32)
+    }
+
+    fn selection_end_in_samples(&self) -> u32 {
+        let selection_end = (self.selection_end.current()
+            + self
+                .modulation_matrix
+                .offset(ModulationTarget::SelectionEnd))
+        .clamp(0.0, 1.0);
+        ((self.buffer.num_frames() as f32 * selection_end) as u32)
+            .min(self.buffer.num_frames() as u32)
+    }
+
+    fn selection_len_in_samples(&self) -> u32 {
+        // modulation can move the selection start past the selection end
+        self.selection_end_in_samples()
+            .saturating_sub(self.selection_start_in_samples())
+    }
+
+    /// Prevent long grains from lingering when max length and/or selection has changed
+    ///
+    /// Checks a single playing grain (taking turns between all playing grains) and releases it
+    /// if it exceeds the current selection length or maximum grain length (scaled by the same
+    /// grain length modulation that the grain was started with).
+    ///
+    /// If more grains are playing than `density` currently allows, that grain is released regardless,
+    /// so that lowering the density thins out grains one onset at a time.
+    ///
+    /// Released grains fade out over `GRAIN_RELEASE_MS` instead of being cut off.
+    fn filter_long_grain(&mut self) {
+        if self.active_grains.is_empty() {
+            return;
+        }
+
+        let grain_len_max_in_samples = self.grain_len_max_in_samples() as f32;
+        let selection_len_in_samples = self.selection_len_in_samples() as usize;
+        let release_len = self.ms_to_samples(Self::GRAIN_RELEASE_MS) as usize;
+
+        // grains that are already fading out don't count against `density`
+        let num_unreleased_grains = self
+            .active_grains
+            .iter()
+            .filter(|&&grain_index| !self.grains[grain_index].is_released())
+            .count();
+        let too_many_grains = num_unreleased_grains > self.num_channels_for_frame();
+
+        self.long_grain_cursor = (self.long_grain_cursor + 1) % self.active_grains.len();
+        let grain = &mut self.grains[self.active_grains[self.long_grain_cursor]];
+        let remaining_grain_samples = grain.remaining_samples();
+        let grain_len_max_for_grain = (grain_len_max_in_samples * grain.len_modulation) as usize;
+
+        if too_many_grains
+            || remaining_grain_samples > grain_len_max_for_grain
+            || remaining_grain_samples > selection_len_in_samples
+        {
+            grain.release(release_len);
+        }
+    }
+
+    /// Fills in the sample data for each channel that has a playing grain
+    /// (filtered buffer sample * envelope sample * grain amplitude), based on the current state of that grain.
+    ///
+    /// Grains that have finished are moved back onto the free list, and their channel is silenced.
+    fn fill_frame_samples(&mut self) {
+        // the combined power of every grain's (envelope * amplitude) in this frame
+        let mut grain_power = 0.0;
+
+        // gain modulation is left out of `grain_power`, so that normalization doesn't compensate for it
+        let gain_modulation =
+            (1.0 + self.modulation_matrix.offset(ModulationTarget::Gain)).max(0.0);
+
+        let mut i = 0;
+        while i < self.active_grains.len() {
+            let grain_index = self.active_grains[i];
+            let grain = &mut self.grains[grain_index];
+            let voice_gain = grain.voice.map_or(1.0, |voice| self.voices[voice].gain());
+
+            // grains are only removed from the active list once their final sample has been output
+            if grain.finished {
+                self.frame_samples[grain_index] = 0.0;
+                self.active_grains.swap_remove(i);
+                self.free_grains.push(grain_index);
+                continue;
+            }
+
+            // each buffer sample and envelope sample must be coordinated/aligned to prevent
+            // audio clipping and/or unexpected audio results
+            let envelope_value = grain.envelope_value() * grain.amplitude;
+            grain_power += envelope_value * envelope_value;
+            let mut sample_value = self.interpolator.read(
+                self.buffer.channel(grain.source_channel),
+                grain.position,
+                grain.playback_rate,
+                self.interpolation,
+            );
+            if let Some(filter) = &mut grain.filter {
+                sample_value = filter.process(sample_value);
+            }
+            self.frame_samples[grain_index] =
+                sample_value * envelope_value * gain_modulation * voice_gain;
+
+            grain.next_frame();
+            i += 1;
+        }
+
+        if self.normalize {
+            self.update_normalization_divisor(grain_power);
+        }
+    }
+
+    /// Moves the normalization divisor toward the level of the grains in the current frame.
+    ///
+    /// Grains are mostly uncorrelated, so their summed level grows with the square root of their
+    /// combined power, rather than with the number of grains. Quiet frames are never boosted.
+    fn update_normalization_divisor(&mut self, grain_power: f32) {
+        let target_divisor = (grain_power / self.pan_num_channels as f32).sqrt().max(1.0);
+        self.normalization_divisor +=
+            (target_divisor - self.normalization_divisor) * self.normalization_coefficient;
+    }
+
+    /// How much of the distance to its target the normalization divisor should move every frame,
+    /// so that it settles in roughly `NORMALIZATION_TIME_MS`
+    fn normalization_coefficient(sample_rate: u32) -> f32 {
+        let time_in_samples = Self::NORMALIZATION_TIME_MS / 1000.0 * sample_rate.max(1) as f32;
+        1.0 - (-1.0 / time_in_samples).exp()
+    }
+
+    /// this represents the number of channels actually in use
+    fn num_channels_for_frame(&self) -> usize {
+        let density = (self.density.current()
+            + self.modulation_matrix.offset(ModulationTarget::Density))
+        .clamp(0.0, 1.0);
+        (self.max_num_channels as f32 * density) as usize
+    }
+
+    /// Advances all grains by a single frame and stores the resulting channel values
+    /// in `frame_samples`
+    fn render_frame(&mut self) {
+        for smoothed_value in self.smoothed_values_mut() {
+            smoothed_value.next_value();
+        }
+        self.playhead.set_speed(self.scan_speed.current());
+        self.modulation_matrix.tick(self.sample_rate, &mut self.rng);
+        if self.play_mode == PlayMode::Notes {
+            self.advance_voices();
+        }
+
+        if self.position_mode == PositionMode::Scan {
+            self.advance_playhead();
+        }
+
+        // buy only filtering/refreshing grains at an interval, it blends one sound into the other
+        // decrease rate of refreshes to blend sounds together
+        let num_onsets = self.scheduler.tick(self.sample_rate, &mut self.rng);
+        for _ in 0..num_onsets {
+            self.filter_long_grain();
+            self.refresh_grain();
+        }
+
+        self.fill_frame_samples();
+    }
+
+    /// Advances every sounding voice's envelope by one frame.
+    ///
+    /// Grains that belong to voices that have gone silent are finished, so their grains can be reused.
+    fn advance_voices(&mut self) {
+        for voice_index in 0..self.voices.len() {
+            let voice = &mut self.voices[voice_index];
+            if voice.is_idle() {
+                continue;
+            }
+
+            voice.next_frame(&self.adsr, self.sample_rate);
+            if voice.is_idle() {
+                self.finish_voice_grains(|grain_voice| grain_voice == voice_index);
+            }
+        }
+    }
+
+    /// Marks every playing grain that belongs to a voice matching `predicate` as finished
+    fn finish_voice_grains(&mut self, predicate: impl Fn(usize) -> bool) {
+        for &grain_index in &self.active_grains {
+            let grain = &mut self.grains[grain_index];
+            if matches!(grain.voice, Some(voice) if predicate(voice)) {
+                grain.finished = true;
+            }
+        }
+    }
+
+    /// Fades out every playing grain that belongs to the voice at `voice_index` over `GRAIN_RELEASE_MS`
+    fn release_voice_grains(&mut self, voice_index: usize) {
+        let release_len = self.ms_to_samples(Self::GRAIN_RELEASE_MS) as usize;
+        for &grain_index in &self.active_grains {
+            let grain = &mut self.grains[grain_index];
+            if grain.voice == Some(voice_index) {
+                grain.release(release_len);
+            }
+        }
+    }
+
+    /// Picks the next sounding voice (taking turns between all sounding voices) for a new grain
+    fn next_sounding_voice(&mut self) -> Option<usize> {
+        for _ in 0..self.voice_limit {
+            self.voice_cursor = (self.voice_cursor + 1) % self.voice_limit;
+            if !self.voices[self.voice_cursor].is_idle() {
+                return Some(self.voice_cursor);
+            }
+        }
+
+        None
+    }
+
+    /// Creates a smoothed parameter that uses the default smoothing settings
+    fn new_smoothed_value(value: f32) -> SmoothedValue {
+        SmoothedValue::new(
+            value,
+            Self::DEFAULT_SMOOTHING_MS,
+            SmoothingType::default(),
+            Self::DEFAULT_SAMPLE_RATE,
+        )
+    }
+
+    /// Every continuous parameter that ramps toward new values
+    fn smoothed_values_mut(&mut self) -> [&mut SmoothedValue; 6] {
+        [
+            &mut self.density,
+            &mut self.selection_start,
+            &mut self.selection_end,
+            &mut self.pan_center,
+            &mut self.pan_spread,
+            &mut self.scan_speed,
+        ]
+    }
+
+    /// Moves the playhead forward by one output frame, looping within the current selection
+    fn advance_playhead(&mut self) {
+        // at a scan speed of 1.0, the playhead should move at the buffer's original speed,
+        // even if it was recorded at a different sample rate than the output
+        let buffer_frames_per_frame = self.buffer.sample_rate() as f64 / self.sample_rate as f64;
+
+        self.playhead.advance(
+            buffer_frames_per_frame,
+            self.selection_start_in_samples() as f64,
+            self.selection_end_in_samples() as f64,
+        );
+    }
+
+    /// The channel values of the most recently rendered frame
+    /// (1 array element = 1 grain = 1 channel of audio)
+    fn frame(&self) -> &[f32] {
+        &self.frame_samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lfo::LfoShape;
+    use crate::midi::MidiMessage;
+
+    /// A second of audio whose samples all differ, so that each grain's start position is audible
+    fn varied_buffer() -> Arc<SourceBuffer> {
+        let samples = (0..44100).map(|i| (i as f32 * 0.01).sin()).collect();
+        Arc::new(SourceBuffer::from_mono(samples, 44100))
+    }
+
+    fn render(synth: &mut GranularSynthesizer, num_frames: usize) -> Vec<f32> {
+        let mut interleaved = vec![0.0; num_frames * 2];
+        synth.process_block(&mut interleaved, 2);
+        interleaved
+    }
+
+    /// Every grain should be on exactly one of the free or active lists
+    fn assert_grains_partitioned(synth: &GranularSynthesizer) {
+        let mut indexes: Vec<_> = synth
+            .free_grains
+            .iter()
+            .chain(&synth.active_grains)
+            .copied()
+            .collect();
+        indexes.sort_unstable();
+        assert_eq!(indexes, (0..synth.grains.len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn grains_move_between_free_and_active_lists() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth.set_buffer(varied_buffer());
+        assert_grains_partitioned(&synth);
+
+        render(&mut synth, 100);
+        assert!(!synth.active_grains.is_empty());
+        assert_grains_partitioned(&synth);
+
+        // long enough for the first grains to finish and be reused
+        render(&mut synth, 44100);
+        assert_grains_partitioned(&synth);
+    }
+
+    #[test]
+    fn changing_the_number_of_channels_keeps_the_lists_in_sync() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth.set_buffer(varied_buffer());
+        render(&mut synth, 1000);
+
+        synth.set_max_number_of_channels(10);
+        assert_eq!(synth.grains.len(), 10);
+        assert_grains_partitioned(&synth);
+        render(&mut synth, 1000);
+
+        synth.set_max_number_of_channels(500);
+        assert_eq!(synth.grains.len(), 500);
+        assert_grains_partitioned(&synth);
+        render(&mut synth, 1000);
+        assert_grains_partitioned(&synth);
+    }
+
+    #[test]
+    fn absolute_grain_lengths_ignore_the_selection() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth
+            .set_buffer(varied_buffer())
+            .set_grain_len_mode(GrainLenMode::Absolute)
+            .set_grain_len_min_ms(50.0)
+            .set_grain_len_max_ms(200.0);
+
+        assert_eq!(synth.grain_len_min_in_samples(), 2205);
+        assert_eq!(synth.grain_len_max_in_samples(), 8820);
+
+        synth.set_selection_end(0.5);
+        render(&mut synth, 44100);
+        assert_eq!(synth.grain_len_min_in_samples(), 2205);
+        assert_eq!(synth.grain_len_max_in_samples(), 8820);
+    }
+
+    #[test]
+    fn millisecond_grain_lengths_stay_ordered_and_in_range() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth
+            .set_grain_len_min_ms(500.0)
+            .set_grain_len_max_ms(100.0);
+        assert_eq!(synth.grain_len_min_ms(), 100.0);
+
+        synth.set_grain_len_min_ms(300.0);
+        assert_eq!(synth.grain_len_max_ms(), 300.0);
+
+        synth.set_grain_len_min_ms(0.0).set_grain_len_max_ms(1e6);
+        assert_eq!(
+            synth.grain_len_min_ms(),
+            GranularSynthesizer::GRAIN_LEN_MS_MIN
+        );
+        assert_eq!(
+            synth.grain_len_max_ms(),
+            GranularSynthesizer::GRAIN_LEN_MS_MAX
+        );
+    }
+
+    /// The notes of every voice that hasn't fully faded out
+    fn sounding_notes(synth: &GranularSynthesizer) -> Vec<u8> {
+        let mut notes: Vec<_> = synth
+            .voices
+            .iter()
+            .filter(|voice| !voice.is_idle())
+            .map(|voice| voice.note)
+            .collect();
+        notes.sort_unstable();
+        notes
+    }
+
+    #[test]
+    fn notes_take_over_the_oldest_voice_once_all_are_used() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth
+            .set_play_mode(PlayMode::Notes)
+            .set_voice_limit(2)
+            .note_on(60, 100)
+            .note_on(64, 100)
+            .note_on(67, 100);
+        assert_eq!(sounding_notes(&synth), [64, 67]);
+
+        // released voices are taken over before held ones
+        synth.note_off(67).note_on(72, 100);
+        assert_eq!(sounding_notes(&synth), [64, 72]);
+
+        // retriggering a sounding note reuses its voice
+        synth.note_on(64, 50);
+        assert_eq!(sounding_notes(&synth), [64, 72]);
+    }
+
+    #[test]
+    fn midi_messages_start_and_release_notes() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth
+            .set_play_mode(PlayMode::Notes)
+            .set_adsr(Adsr::new(0.0, 0.0, 1.0, 0.0))
+            .handle_midi(&[0x90, 60, 127])
+            .handle_midi(&[0x91, 62, 127]);
+        assert_eq!(sounding_notes(&synth), [60, 62]);
+
+        synth.handle_midi(&[0x90, 60, 0]);
+        render(&mut synth, 10);
+        assert_eq!(sounding_notes(&synth), [62]);
+
+        synth.handle_midi(&[0xB0, MidiMessage::ALL_NOTES_OFF, 0]);
+        render(&mut synth, 10);
+        assert!(sounding_notes(&synth).is_empty());
+    }
+
+    #[test]
+    fn notes_mode_is_silent_without_notes() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth
+            .set_buffer(varied_buffer())
+            .set_play_mode(PlayMode::Notes);
+        assert!(render(&mut synth, 4410).iter().all(|&sample| sample == 0.0));
+
+        synth.note_on(synth.root_note(), 127);
+        assert!(render(&mut synth, 4410).iter().any(|&sample| sample != 0.0));
+    }
+
+    #[test]
+    fn grain_amplitudes_stay_within_their_range() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth.set_amplitude_min(0.9).set_amplitude_max(0.3);
+        assert_eq!(synth.amplitude_min().get(), 0.3);
+
+        synth.set_amplitude_max(0.6);
+        for _ in 0..100 {
+            let amplitude = synth.new_grain_amplitude();
+            assert!((0.3..=0.6).contains(&amplitude));
+        }
+    }
+
+    #[test]
+    fn normalization_evens_out_the_level_across_grain_rates() {
+        let rms = |normalize: bool, grains_per_second: f32| {
+            let mut synth = GranularSynthesizer::new_with_seed(0);
+            synth
+                .set_buffer(varied_buffer())
+                .set_max_number_of_channels(500)
+                .set_density(1.0)
+                .set_grains_per_second(grains_per_second)
+                .set_normalize(normalize);
+            // skip past the density ramp and let the grain cloud fill in
+            render(&mut synth, 44100);
+            let samples = render(&mut synth, 44100);
+            (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32)
+                .sqrt()
+        };
+
+        // 10 times as many grains are much louder, unless the output is normalized
+        let unnormalized_ratio = rms(false, 2000.0) / rms(false, 200.0);
+        let normalized_ratio = rms(true, 2000.0) / rms(true, 200.0);
+        assert!(unnormalized_ratio > 2.0, "{}", unnormalized_ratio);
+        assert!(normalized_ratio < 1.5, "{}", normalized_ratio);
+    }
+
+    #[test]
+    fn same_seed_renders_identical_output() {
+        let mut a = GranularSynthesizer::new_with_seed(7);
+        let mut b = GranularSynthesizer::new_with_seed(7);
+        a.set_buffer(varied_buffer());
+        b.set_buffer(varied_buffer());
+
+        assert_eq!(a.seed(), 7);
+        assert_eq!(render(&mut a, 4410), render(&mut b, 4410));
+    }
+
+    #[test]
+    fn different_seeds_render_different_output() {
+        let mut a = GranularSynthesizer::new_with_seed(1);
+        let mut b = GranularSynthesizer::new_with_seed(2);
+        a.set_buffer(varied_buffer());
+        b.set_buffer(varied_buffer());
+
+        assert_ne!(render(&mut a, 4410), render(&mut b, 4410));
+    }
+
+    #[test]
+    fn reseeding_matches_a_newly_seeded_synthesizer() {
+        let mut fresh = GranularSynthesizer::new_with_seed(3);
+        fresh.set_buffer(varied_buffer());
+        let expected = render(&mut fresh, 4410);
+
+        let mut reseeded = GranularSynthesizer::new_with_seed(99);
+        reseeded.set_buffer(varied_buffer());
+        render(&mut reseeded, 1000);
+        reseeded.reseed(3);
+
+        assert_eq!(reseeded.seed(), 3);
+        assert_eq!(render(&mut reseeded, 4410), expected);
+    }
+
+    #[test]
+    fn interleaved_and_planar_blocks_render_the_same_output() {
+        let mut interleaved_synth = GranularSynthesizer::new_with_seed(0);
+        let mut planar_synth = GranularSynthesizer::new_with_seed(0);
+        interleaved_synth.set_buffer(varied_buffer());
+        planar_synth.set_buffer(varied_buffer());
+
+        let interleaved = render(&mut interleaved_synth, 4410);
+        let mut left = vec![0.0; 4410];
+        let mut right = vec![0.0; 4410];
+        planar_synth.process_block_planar(&mut [&mut left, &mut right]);
+
+        let (interleaved_left, interleaved_right): (Vec<f32>, Vec<f32>) = interleaved
+            .chunks(2)
+            .map(|frame| (frame[0], frame[1]))
+            .unzip();
+        assert_eq!(interleaved_left, left);
+        assert_eq!(interleaved_right, right);
+    }
+
+    #[test]
+    fn block_size_does_not_change_the_output() {
+        let mut whole_synth = GranularSynthesizer::new_with_seed(0);
+        whole_synth.set_buffer(varied_buffer());
+        let whole = render(&mut whole_synth, 1000);
+
+        let mut split_synth = GranularSynthesizer::new_with_seed(0);
+        split_synth.set_buffer(varied_buffer());
+        let mut split = render(&mut split_synth, 1);
+        split.extend(render(&mut split_synth, 333));
+        split.extend(render(&mut split_synth, 666));
+
+        assert_eq!(split, whole);
+    }
+
+    #[test]
+    fn grain_filters_are_only_created_when_enabled() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        assert_eq!(synth.new_grain_filter(), None);
+
+        synth
+            .set_grain_filter_mode(GrainFilterMode::BandPass)
+            .set_grain_filter_cutoff_min_hz(500.0)
+            .set_grain_filter_cutoff_max_hz(500.0);
+        let filter = synth.new_grain_filter().unwrap();
+        assert_eq!(filter.mode(), GrainFilterMode::BandPass);
+        assert_eq!(
+            filter,
+            StateVariableFilter::new(
+                GrainFilterMode::BandPass,
+                500.0,
+                synth.grain_filter_resonance().get(),
+                synth.sample_rate()
+            )
+        );
+    }
+
+    #[test]
+    fn nan_reverse_probability_plays_grains_forwards() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth.set_buffer(varied_buffer());
+        synth.set_reverse_probability(f32::NAN);
+
+        assert_eq!(synth.reverse_probability().get(), 0.0);
+        assert!(render(&mut synth, 4410)
+            .iter()
+            .all(|sample| sample.is_finite()));
+    }
+
+    #[test]
+    fn grain_length_modulation_does_not_cut_grains_short() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth
+            .set_buffer(varied_buffer())
+            .set_grain_len_mode(GrainLenMode::Absolute)
+            .set_grain_len_min_ms(100.0)
+            .set_grain_len_max_ms(100.0)
+            // doubles the length of every new grain
+            .set_lfo(0, Lfo::new(LfoShape::Square, Lfo::RATE_MIN, 1.0, 0.0))
+            .add_modulation_routing(ModulationRouting::new(0, ModulationTarget::GrainLen, 1.0));
+
+        for _ in 0..100 {
+            render(&mut synth, 64);
+            assert!(synth
+                .active_grains
+                .iter()
+                .all(|&grain_index| !synth.grains[grain_index].is_released()));
+        }
+        assert!(synth
+            .active_grains
+            .iter()
+            .any(|&grain_index| synth.grains[grain_index].current_frame > 4410));
+    }
+
+    #[test]
+    fn lowering_the_density_releases_grains() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth
+            .set_buffer(varied_buffer())
+            .set_max_number_of_channels(50)
+            .set_grains_per_second(2000.0);
+        render(&mut synth, 4410);
+        let num_grains = synth.active_grains.len();
+
+        synth.set_smoothing_ms(0.0).set_density(0.0);
+        render(&mut synth, 256);
+        let released_grains: Vec<_> = synth
+            .active_grains
+            .iter()
+            .map(|&grain_index| synth.grains[grain_index])
+            .filter(|grain| grain.is_released())
+            .collect();
+        assert!(!released_grains.is_empty());
+        assert!(released_grains.iter().all(|grain| grain.release_len
+            == synth.ms_to_samples(GranularSynthesizer::GRAIN_RELEASE_MS) as usize));
+
+        render(&mut synth, 4410);
+        assert!(synth.active_grains.len() < num_grains);
     }
 
-    fn increment_refresh_counter(&mut self) {
-        self.refresh_counter = self.refresh_counter.wrapping_add(1);
+    #[test]
+    fn taking_over_a_voice_releases_the_previous_notes_grains() {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth
+            .set_buffer(varied_buffer())
+            .set_max_number_of_channels(20)
+            .set_grains_per_second(500.0)
+            .set_play_mode(PlayMode::Notes)
+            .set_voice_limit(1)
+            .note_on(60, 100);
+        render(&mut synth, 4410);
+        assert!(!synth.active_grains.is_empty());
+
+        // an octave above the root note
+        synth.note_on(72, 100);
+        let release_len = synth.ms_to_samples(GranularSynthesizer::GRAIN_RELEASE_MS) as usize;
+        assert!(synth
+            .active_grains
+            .iter()
+            .all(|&grain_index| synth.grains[grain_index].remaining_samples() <= release_len));
+
+        render(&mut synth, 4410);
+        assert!(!synth.active_grains.is_empty());
+        assert!(synth
+            .active_grains
+            .iter()
+            .all(|&grain_index| synth.grains[grain_index].playback_rate == 2.0));
     }
 }
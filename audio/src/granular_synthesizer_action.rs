@@ -1,6 +1,13 @@
 use std::sync::Arc;
 
-use crate::{grain::Grain, percentage::Percentage};
+use crate::{
+    adsr::Adsr, channel_policy::ChannelPolicy, envelope_shape::EnvelopeShape, grain::Grain,
+    grain_filter::GrainFilterMode, grain_len_mode::GrainLenMode, interpolation::Interpolation,
+    lfo::Lfo, midi::MidiMessage, modulation_matrix::ModulationRouting, note_voice::PlayMode,
+    pan_law::PanLaw, percentage::Percentage, pitch_quantizer::PitchQuantizer,
+    playhead::PositionMode, preset::Preset, scheduler::SchedulerMode,
+    smoothed_value::SmoothingType, source_buffer::SourceBuffer,
+};
 
 /// Public interface to the GranularSynesizer.
 ///
@@ -13,6 +20,10 @@ pub trait GranularSynthesizerAction {
 
     const DEFAULT_NUM_CHANNELS: u32 = 2;
 
+    /// The number of output channels that grains are panned across,
+    /// until a block is processed with a different number of channels
+    const DEFAULT_NUM_OUTPUT_CHANNELS: usize = 2;
+
     const DEFAULT_DENSITY: f32 = 1.0;
 
     const GRAIN_LEN_MIN_DIFFERENCE: f32 = 0.05;
@@ -27,9 +38,19 @@ pub trait GranularSynthesizerAction {
     /// the largest possible length of grain, given as a percentage of the currently selected audio
     const GRAIN_LEN_MAX_MAX: f32 = 1.0;
 
+    /// the smallest possible length of grain (in milliseconds) when using `GrainLenMode::Absolute`
+    const GRAIN_LEN_MS_MIN: f32 = 5.0;
+
+    /// the largest possible length of grain (in milliseconds) when using `GrainLenMode::Absolute`
+    const GRAIN_LEN_MS_MAX: f32 = 2000.0;
+
+    const DEFAULT_GRAIN_LEN_MIN_MS: f32 = 20.0;
+
+    const DEFAULT_GRAIN_LEN_MAX_MS: f32 = 100.0;
+
     const DEFAULT_SAMPLE_RATE: u32 = 44100;
 
-    /// This is the sample interval at which grains are filtered / refreshed.
+    /// This is the sample interval (at `DEFAULT_SAMPLE_RATE`) at which grains are filtered / refreshed.
     /// Using a prime number leads to the least periodic overlap in grains.
     const DEFAULT_REFRESH_INTERVAL: u32 = 271;
 
@@ -37,9 +58,125 @@ pub trait GranularSynthesizerAction {
 
     const REFRESH_INTERVAL_MAX: u32 = 1009;
 
-    /// Creates a new GranularSynthesizer instance
+    const GRAINS_PER_SECOND_MIN: f32 = 1.0;
+
+    /// Equivalent to `REFRESH_INTERVAL_MIN`
+    const GRAINS_PER_SECOND_MAX: f32 =
+        Self::DEFAULT_SAMPLE_RATE as f32 / Self::REFRESH_INTERVAL_MIN as f32;
+
+    /// Equivalent to `DEFAULT_REFRESH_INTERVAL`
+    const DEFAULT_GRAINS_PER_SECOND: f32 =
+        Self::DEFAULT_SAMPLE_RATE as f32 / Self::DEFAULT_REFRESH_INTERVAL as f32;
+
+    /// By default, synchronous grains start at perfectly regular intervals
+    const DEFAULT_SCHEDULER_JITTER: f32 = 0.0;
+
+    /// Scan speeds are multiples of the original speed (negative values scan backwards)
+    const SCAN_SPEED_MIN: f32 = -4.0;
+
+    const SCAN_SPEED_MAX: f32 = 4.0;
+
+    const DEFAULT_SCAN_SPEED: f32 = 1.0;
+
+    /// Range (in milliseconds) around the playhead that grains can randomly start from
+    const SPRAY_MS_MIN: f32 = 0.0;
+
+    const SPRAY_MS_MAX: f32 = 1000.0;
+
+    const DEFAULT_SPRAY_MS: f32 = 20.0;
+
+    /// Transposition range (in semitones) for all new grains
+    const PITCH_SEMITONES_MIN: f32 = -24.0;
+
+    const PITCH_SEMITONES_MAX: f32 = 24.0;
+
+    /// Fine-tuning range (in cents) for all new grains
+    const PITCH_CENTS_MIN: f32 = -100.0;
+
+    const PITCH_CENTS_MAX: f32 = 100.0;
+
+    /// Range (in semitones) that each new grain's pitch can randomly deviate by
+    const PITCH_SPREAD_MIN: f32 = 0.0;
+
+    const PITCH_SPREAD_MAX: f32 = 24.0;
+
+    /// By default, all grains play forward through the buffer
+    const DEFAULT_REVERSE_PROBABILITY: f32 = 0.0;
+
+    /// An envelope skew of 0.5 places each grain's peak amplitude in its center
+    const DEFAULT_ENVELOPE_SKEW: f32 = 0.5;
+
+    /// By default, every grain plays at full amplitude
+    const DEFAULT_AMPLITUDE_MIN: f32 = 1.0;
+
+    const DEFAULT_AMPLITUDE_MAX: f32 = 1.0;
+
+    /// The range of cutoffs (in Hz) that new grains' filters can be given
+    const GRAIN_FILTER_CUTOFF_MIN_HZ: f32 = 20.0;
+
+    const GRAIN_FILTER_CUTOFF_MAX_HZ: f32 = 20000.0;
+
+    const DEFAULT_GRAIN_FILTER_CUTOFF_MIN_HZ: f32 = 200.0;
+
+    const DEFAULT_GRAIN_FILTER_CUTOFF_MAX_HZ: f32 = 5000.0;
+
+    const DEFAULT_GRAIN_FILTER_RESONANCE: f32 = 0.2;
+
+    /// How long (in milliseconds) continuous parameters take to ramp to new values
+    const SMOOTHING_MS_MIN: f32 = 0.0;
+
+    const SMOOTHING_MS_MAX: f32 = 1000.0;
+
+    const DEFAULT_SMOOTHING_MS: f32 = 20.0;
+
+    /// The most notes that can ever sound at once
+    const MAX_VOICES: usize = 16;
+
+    const DEFAULT_VOICE_LIMIT: usize = 8;
+
+    /// Notes are transposed relative to this MIDI note (middle C), which plays the buffer at its original pitch
+    const DEFAULT_ROOT_NOTE: u8 = 60;
+
+    /// By default, softer notes are quieter
+    const DEFAULT_VELOCITY_SENSITIVITY: f32 = 1.0;
+
+    /// How far (in semitones) new grains are transposed by a full-scale pitch modulation
+    const MODULATION_PITCH_SEMITONES: f32 = 12.0;
+
+    /// How quickly (in milliseconds) normalization adapts to changes in the number of sounding grains
+    const NORMALIZATION_TIME_MS: f32 = 100.0;
+
+    /// How long (in milliseconds) grains take to fade out when they have to stop early
+    /// (e.g. when `density` or the maximum grain length is lowered)
+    const GRAIN_RELEASE_MS: f32 = 5.0;
+
+    /// Pan positions range from the first output channel (-1.0) to the last (1.0)
+    const PAN_MIN: f32 = -1.0;
+
+    const PAN_MAX: f32 = 1.0;
+
+    const DEFAULT_PAN_CENTER: f32 = 0.0;
+
+    /// By default, grains are spread across the full width of the output channels
+    const DEFAULT_PAN_SPREAD: f32 = 1.0;
+
+    /// Creates a new GranularSynthesizer instance, seeded with a random seed
     fn new() -> Self;
 
+    /// Creates a new GranularSynthesizer instance, whose random number generator
+    /// is seeded with `seed`.
+    ///
+    /// Given the same seed, buffer, and parameters, the synthesizer produces identical output.
+    fn new_with_seed(seed: u64) -> Self;
+
+    /// Re-seeds the random number generator and resets all playing grains,
+    /// so that the output from this point forward is identical to that of
+    /// a new synthesizer created with `new_with_seed(seed)` (given the same buffer and parameters).
+    fn reseed(&mut self, seed: u64) -> &mut Self;
+
+    /// The seed that was most recently used to seed the random number generator
+    fn seed(&self) -> u64;
+
     fn set_selection_start(&mut self, start: impl Into<Percentage>) -> &mut Self;
 
     fn selection_start(&self) -> Percentage;
@@ -64,43 +201,400 @@ pub trait GranularSynthesizerAction {
             .min(Self::REFRESH_INTERVAL_MAX)
     }
 
+    /// The average interval between grain onsets, in samples at `DEFAULT_SAMPLE_RATE`.
+    ///
+    /// This is an alternate representation of `grains_per_second`.
     fn refresh_interval(&self) -> u32;
 
+    /// Sets the average interval between grain onsets, in samples at `DEFAULT_SAMPLE_RATE`
+    /// (the interval is scaled to match the actual sample rate, so timing is the same at any sample rate).
+    ///
+    /// This is an alternate way to set `grains_per_second`.
     fn set_refresh_interval(&mut self, refresh_interval: u32) -> &mut Self;
 
+    fn sanitize_grains_per_second(grains_per_second: f32) -> f32 {
+        grains_per_second
+            .max(Self::GRAINS_PER_SECOND_MIN)
+            .min(Self::GRAINS_PER_SECOND_MAX)
+    }
+
+    /// The average number of new grains that are started every second.
+    ///
+    /// Every time a grain is started, one grain that is too long for the
+    /// current selection / grain length settings is also marked as finished.
+    fn set_grains_per_second(&mut self, grains_per_second: f32) -> &mut Self;
+
+    fn grains_per_second(&self) -> f32;
+
+    /// Determines whether grains start at regular or random intervals
+    fn set_scheduler_mode(&mut self, scheduler_mode: SchedulerMode) -> &mut Self;
+
+    fn scheduler_mode(&self) -> SchedulerMode;
+
+    /// How far (0.0 -> 1.0, as a percentage of the interval) each synchronous grain onset
+    /// can randomly deviate from the regular interval
+    fn set_scheduler_jitter(&mut self, scheduler_jitter: impl Into<Percentage>) -> &mut Self;
+
+    fn scheduler_jitter(&self) -> Percentage;
+
+    fn sanitize_scan_speed(scan_speed: f32) -> f32 {
+        scan_speed
+            .max(Self::SCAN_SPEED_MIN)
+            .min(Self::SCAN_SPEED_MAX)
+    }
+
+    fn sanitize_spray_ms(spray_ms: f32) -> f32 {
+        spray_ms.max(Self::SPRAY_MS_MIN).min(Self::SPRAY_MS_MAX)
+    }
+
+    /// Determines whether new grains start anywhere in the selection,
+    /// or around a playhead that scans through the selection (looping at its edges)
+    fn set_position_mode(&mut self, position_mode: PositionMode) -> &mut Self;
+
+    fn position_mode(&self) -> PositionMode;
+
+    /// How fast the playhead moves through the buffer when using `PositionMode::Scan`.
+    ///
+    /// 1.0 plays through the buffer at its original speed, 0.5 stretches it to twice as long,
+    /// 0.0 freezes the playhead, and negative values scan backwards.
+    fn set_scan_speed(&mut self, scan_speed: f32) -> &mut Self;
+
+    fn scan_speed(&self) -> f32;
+
+    /// How far (in milliseconds) before or after the playhead new grains can randomly start
+    fn set_spray_ms(&mut self, spray_ms: f32) -> &mut Self;
+
+    fn spray_ms(&self) -> f32;
+
+    fn sanitize_smoothing_ms(smoothing_ms: f32) -> f32 {
+        smoothing_ms
+            .max(Self::SMOOTHING_MS_MIN)
+            .min(Self::SMOOTHING_MS_MAX)
+    }
+
+    /// How long (in milliseconds) continuous parameters (density, selection, pan and scan speed)
+    /// take to ramp to new values, which prevents clicks and zipper noise when they change.
+    ///
+    /// Getters always return the value being ramped toward.
+    fn set_smoothing_ms(&mut self, smoothing_ms: f32) -> &mut Self;
+
+    fn smoothing_ms(&self) -> f32;
+
+    /// The curve that continuous parameters follow when ramping to new values
+    fn set_smoothing_type(&mut self, smoothing_type: SmoothingType) -> &mut Self;
+
+    fn smoothing_type(&self) -> SmoothingType;
+
+    /// Copies the settings (shape, rate, depth, phase offset) of `lfo` into the synthesizer's LFO at `index`.
+    ///
+    /// The LFO keeps running from its current point in its cycle.
+    fn set_lfo(&mut self, index: usize, lfo: Lfo) -> &mut Self;
+
+    /// Returns a copy of the synthesizer's LFO at `index`, if it exists
+    fn lfo(&self, index: usize) -> Option<Lfo>;
+
+    /// Routes one of the synthesizer's LFOs to a parameter.
+    ///
+    /// Modulation is evaluated on the audio thread for every frame, on top of the parameter's own value.
+    fn add_modulation_routing(&mut self, routing: ModulationRouting) -> &mut Self;
+
+    fn remove_modulation_routing(&mut self, index: usize) -> &mut Self;
+
+    /// A copy of all current routings (this allocates, so it shouldn't be called from the audio thread)
+    fn modulation_routings(&self) -> Vec<ModulationRouting>;
+
+    /// Determines whether grains play continuously (drone) or only while notes are sounding
+    fn set_play_mode(&mut self, play_mode: PlayMode) -> &mut Self;
+
+    fn play_mode(&self) -> PlayMode;
+
+    /// Starts a note with a MIDI note number and velocity (1 -> 127).
+    ///
+    /// When playing notes, every new grain is assigned to one of the sounding notes,
+    /// transposed relative to the root note and shaped by that note's ADSR envelope.
+    /// If `voice_limit` notes are already sounding, the oldest one is taken over,
+    /// and the grains that were playing its previous note fade out.
+    fn note_on(&mut self, note: u8, velocity: u8) -> &mut Self;
+
+    /// Releases every sounding voice started by `note`
+    fn note_off(&mut self, note: u8) -> &mut Self;
+
+    /// Releases every sounding voice
+    fn all_notes_off(&mut self) -> &mut Self;
+
+    /// Responds to a single raw MIDI message (note on / off and the "all notes off" channel modes).
+    ///
+    /// Messages on any MIDI channel are accepted, and unsupported messages are ignored.
+    fn handle_midi(&mut self, bytes: &[u8]) -> &mut Self {
+        match MidiMessage::parse(bytes) {
+            Some(MidiMessage::NoteOn { note, velocity, .. }) => self.note_on(note, velocity),
+            Some(MidiMessage::NoteOff { note, .. }) => self.note_off(note),
+            Some(MidiMessage::ControlChange { controller, .. })
+                if controller == MidiMessage::ALL_NOTES_OFF
+                    || controller == MidiMessage::ALL_SOUND_OFF =>
+            {
+                self.all_notes_off()
+            }
+            _ => self,
+        }
+    }
+
+    /// The MIDI note that plays the buffer at its original pitch
+    fn set_root_note(&mut self, root_note: u8) -> &mut Self;
+
+    fn root_note(&self) -> u8;
+
+    /// The most notes (1 -> `MAX_VOICES`) that can sound at once
+    fn set_voice_limit(&mut self, voice_limit: usize) -> &mut Self;
+
+    fn voice_limit(&self) -> usize;
+
+    /// The amplitude envelope applied to every note
+    fn set_adsr(&mut self, adsr: Adsr) -> &mut Self;
+
+    fn adsr(&self) -> Adsr;
+
+    /// How much (0.0 -> 1.0) a note's velocity affects its amplitude.
+    /// At 0.0, every note plays at full amplitude.
+    fn set_velocity_sensitivity(
+        &mut self,
+        velocity_sensitivity: impl Into<Percentage>,
+    ) -> &mut Self;
+
+    fn velocity_sensitivity(&self) -> Percentage;
+
+    /// Moves the playhead to a position in the buffer (0.0 -> 1.0).
+    ///
+    /// If the position is outside of the selection, the playhead wraps back into it on the next frame.
+    fn set_playhead(&mut self, playhead: impl Into<Percentage>) -> &mut Self;
+
+    /// The playhead's current position in the buffer (0.0 -> 1.0)
+    fn playhead(&self) -> Percentage;
+
     /// Replace the internal buffer reference with a different one.
     ///
     /// Any existing / currently playing grains that extend past the new buffer
     /// will be replaced with new ones on the next call to `next_frame`.
     ///
+    /// Any existing / currently playing grains that read from a channel the new buffer
+    /// doesn't have will also be replaced.
+    ///
     /// Any existing / current playing grains that are compatible with new buffer
     /// length will keep their internal state unchanged and will sample from the
     /// new buffer on the next frame.
-    fn set_buffer(&mut self, buffer: Arc<Vec<f32>>) -> &mut Self;
+    ///
+    /// The buffer is played as-is, so it should already be at the synthesizer's sample rate
+    /// (see `SourceBuffer::resampled`).
+    fn set_buffer(&mut self, buffer: Arc<SourceBuffer>) -> &mut Self;
 
     /// Returns a full audio frame (1 array element = 1 audio channel value),
     /// where each channel gets its own, indepedent value
     /// based on the progression of that audio channel's grain.
+    ///
+    /// This allocates a new `Vec` for every frame. On the audio thread, prefer `process_block`.
     fn next_frame(&mut self) -> Vec<f32>;
 
-    /// This should be set BEFORE calling `set_grain_len_min` or `set_grain_len_max`
+    /// Renders as many frames as fit into `out`, with every grain panned into `channels`
+    /// interleaved output channels (i.e. `out.len() / channels` frames).
+    ///
+    /// Does not allocate.
+    fn process_block(&mut self, out: &mut [f32], channels: usize);
+
+    /// Renders as many frames as fit into the shortest of the `out` channels,
+    /// with every grain panned into `out.len()` non-interleaved (planar) output channels.
+    ///
+    /// Does not allocate.
+    fn process_block_planar(&mut self, out: &mut [&mut [f32]]);
+
+    /// Copies the per-grain channel values of the most recently rendered frame into `frame_out`
+    /// (before they were panned), resizing `frame_out` to fit.
+    ///
+    /// `frame_out` is only reallocated if its capacity is too small.
+    fn copy_last_frame(&self, frame_out: &mut Vec<f32>);
+
+    /// The sample rate of the surrounding context.
+    ///
+    /// Absolute grain lengths and grain onset timing are converted to frames using this sample rate.
     fn set_sample_rate(&mut self, sample_rate: u32) -> &mut Self;
 
+    fn sample_rate(&self) -> u32;
+
     /// Produces an unitialized grain for filling the initial array of Grains
     ///
     /// Once it is time to actually produce an audio sample from the buffer,
     /// each grain will be initialized with a randaom start/end index, etc.
     fn new_grain() -> Grain {
-        Grain {
-            current_frame: 0,
-            end_frame: 0,
-            finished: true,
-            len: 0,
-            start_frame: 0,
-        }
+        Grain::default()
     }
 
     fn grain_len_min(&self) -> Percentage;
 
     fn grain_len_max(&self) -> Percentage;
+
+    fn sanitize_grain_len_ms(grain_len_ms: f32) -> f32 {
+        grain_len_ms
+            .max(Self::GRAIN_LEN_MS_MIN)
+            .min(Self::GRAIN_LEN_MS_MAX)
+    }
+
+    /// Determines whether new grains' lengths come from `grain_len_min` / `grain_len_max`
+    /// (a percentage of the selection) or `grain_len_min_ms` / `grain_len_max_ms`.
+    ///
+    /// In either mode, grains are never longer than the current selection.
+    fn set_grain_len_mode(&mut self, grain_len_mode: GrainLenMode) -> &mut Self;
+
+    fn grain_len_mode(&self) -> GrainLenMode;
+
+    /// The shortest length (in milliseconds) of new grains when using `GrainLenMode::Absolute`
+    fn set_grain_len_min_ms(&mut self, grain_len_min_ms: f32) -> &mut Self;
+
+    fn grain_len_min_ms(&self) -> f32;
+
+    /// The longest length (in milliseconds) of new grains when using `GrainLenMode::Absolute`
+    fn set_grain_len_max_ms(&mut self, grain_len_max_ms: f32) -> &mut Self;
+
+    fn grain_len_max_ms(&self) -> f32;
+
+    fn sanitize_pitch_semitones(semitones: f32) -> f32 {
+        semitones
+            .max(Self::PITCH_SEMITONES_MIN)
+            .min(Self::PITCH_SEMITONES_MAX)
+    }
+
+    fn sanitize_pitch_cents(cents: f32) -> f32 {
+        cents.max(Self::PITCH_CENTS_MIN).min(Self::PITCH_CENTS_MAX)
+    }
+
+    fn sanitize_pitch_spread(spread: f32) -> f32 {
+        spread
+            .max(Self::PITCH_SPREAD_MIN)
+            .min(Self::PITCH_SPREAD_MAX)
+    }
+
+    /// Transposes all new grains by a number of semitones
+    fn set_pitch_semitones(&mut self, semitones: f32) -> &mut Self;
+
+    fn pitch_semitones(&self) -> f32;
+
+    /// Fine-tunes all new grains by a number of cents (on top of `pitch_semitones`)
+    fn set_pitch_cents(&mut self, cents: f32) -> &mut Self;
+
+    fn pitch_cents(&self) -> f32;
+
+    /// Each new grain is randomly transposed by up to +/- `spread` semitones
+    fn set_pitch_spread(&mut self, spread: f32) -> &mut Self;
+
+    fn pitch_spread(&self) -> f32;
+
+    /// Snaps the transposition of every new grain (the pitch spread and pitch modulation)
+    /// to the closest pitch in a scale, chord or tuning, relative to `pitch_semitones`.
+    ///
+    /// `None` lets new grains be transposed continuously.
+    fn set_pitch_quantizer(&mut self, pitch_quantizer: Option<PitchQuantizer>) -> &mut Self;
+
+    fn pitch_quantizer(&self) -> Option<PitchQuantizer>;
+
+    /// Determines how sample values are read from between whole buffer frames
+    /// when grains are transposed
+    fn set_interpolation(&mut self, interpolation: Interpolation) -> &mut Self;
+
+    fn interpolation(&self) -> Interpolation;
+
+    /// The chance (0.0 -> 1.0) that each new grain will play backwards through the buffer
+    fn set_reverse_probability(&mut self, reverse_probability: impl Into<Percentage>) -> &mut Self;
+
+    fn reverse_probability(&self) -> Percentage;
+
+    /// Changes the amplitude window used for grains.
+    ///
+    /// Grains that are already playing keep the shape they were created with.
+    fn set_envelope_shape(&mut self, envelope_shape: EnvelopeShape) -> &mut Self;
+
+    fn envelope_shape(&self) -> EnvelopeShape;
+
+    /// Moves the peak of each new grain's envelope earlier (< 0.5) or later (> 0.5)
+    fn set_envelope_skew(&mut self, envelope_skew: impl Into<Percentage>) -> &mut Self;
+
+    fn envelope_skew(&self) -> Percentage;
+
+    /// The quietest amplitude (0.0 -> 1.0) that new grains can be randomly assigned
+    fn set_amplitude_min(&mut self, amplitude_min: impl Into<Percentage>) -> &mut Self;
+
+    fn amplitude_min(&self) -> Percentage;
+
+    /// The loudest amplitude (0.0 -> 1.0) that new grains can be randomly assigned
+    fn set_amplitude_max(&mut self, amplitude_max: impl Into<Percentage>) -> &mut Self;
+
+    fn amplitude_max(&self) -> Percentage;
+
+    /// Runs each new grain through a low-pass, high-pass, or band-pass filter (or none).
+    ///
+    /// Grains that are already playing keep the filter they were created with.
+    fn set_grain_filter_mode(&mut self, grain_filter_mode: GrainFilterMode) -> &mut Self;
+
+    fn grain_filter_mode(&self) -> GrainFilterMode;
+
+    fn sanitize_grain_filter_cutoff_hz(cutoff_hz: f32) -> f32 {
+        cutoff_hz
+            .max(Self::GRAIN_FILTER_CUTOFF_MIN_HZ)
+            .min(Self::GRAIN_FILTER_CUTOFF_MAX_HZ)
+    }
+
+    /// The lowest cutoff (in Hz) that new grains' filters can be randomly assigned.
+    ///
+    /// Cutoffs are picked on a logarithmic scale, so that every octave in the range is equally likely.
+    fn set_grain_filter_cutoff_min_hz(&mut self, cutoff_min_hz: f32) -> &mut Self;
+
+    fn grain_filter_cutoff_min_hz(&self) -> f32;
+
+    /// The highest cutoff (in Hz) that new grains' filters can be randomly assigned
+    fn set_grain_filter_cutoff_max_hz(&mut self, cutoff_max_hz: f32) -> &mut Self;
+
+    fn grain_filter_cutoff_max_hz(&self) -> f32;
+
+    /// How sharply (0.0 -> 1.0) new grains' filters emphasize their cutoff
+    fn set_grain_filter_resonance(&mut self, resonance: impl Into<Percentage>) -> &mut Self;
+
+    fn grain_filter_resonance(&self) -> Percentage;
+
+    /// When enabled, the output level is smoothly compensated for the number (and envelope overlap)
+    /// of grains that are actually sounding, so that changing `density` or the number of channels
+    /// doesn't require adjusting the gain.
+    ///
+    /// When disabled, a fixed divisor based on the number of channels is used instead.
+    fn set_normalize(&mut self, normalize: bool) -> &mut Self;
+
+    fn normalize(&self) -> bool;
+
+    /// Determines which channel of a multi-channel buffer each new grain reads from
+    fn set_channel_policy(&mut self, channel_policy: ChannelPolicy) -> &mut Self;
+
+    fn channel_policy(&self) -> ChannelPolicy;
+
+    fn sanitize_pan_center(pan_center: f32) -> f32 {
+        pan_center.max(Self::PAN_MIN).min(Self::PAN_MAX)
+    }
+
+    /// The pan position (-1.0 -> 1.0) that new grains are centered around
+    fn set_pan_center(&mut self, pan_center: f32) -> &mut Self;
+
+    fn pan_center(&self) -> f32;
+
+    /// How far (0.0 -> 1.0) new grains' pan positions can randomly deviate from the pan center
+    fn set_pan_spread(&mut self, pan_spread: impl Into<Percentage>) -> &mut Self;
+
+    fn pan_spread(&self) -> Percentage;
+
+    /// Determines how each grain's amplitude is split between output channels
+    fn set_pan_law(&mut self, pan_law: PanLaw) -> &mut Self;
+
+    fn pan_law(&self) -> PanLaw;
+
+    /// Replaces every setting stored in `preset` at once.
+    ///
+    /// The buffer, sample rate, seed, and any sounding notes are left untouched.
+    fn apply_preset(&mut self, preset: &Preset) -> &mut Self;
+
+    /// Captures the synthesizer's current settings, so that they can be saved and re-applied later
+    fn preset(&self) -> Preset;
 }
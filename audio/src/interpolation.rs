@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// Strategy used to read a sample value that falls between two whole buffer frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    /// Straight line between the two closest frames. This is the cheapest option.
+    #[default]
+    Linear,
+    /// 4-point, 3rd-order Hermite spline.
+    /// Noticeably smoother than linear interpolation at a small extra cost.
+    CubicHermite,
+    /// Blackman-windowed sinc, reading `SINC_HALF_WIDTH` frames on either side of the position.
+    ///
+    /// When transposing up, the sinc kernel is stretched (up to `SINC_MAX_HALF_WIDTH` frames)
+    /// to band-limit the read, which keeps aliasing down, but this is by far the most expensive option.
+    WindowedSinc,
+}
+
+/// Number of frames read on either side of the current position for `WindowedSinc`
+/// (at playback rates of 1.0 and below), which is also the kernel's number of zero crossings
+pub const SINC_HALF_WIDTH: usize = 4;
+
+/// The most frames read on either side of the current position for `WindowedSinc`.
+///
+/// The kernel widens with the playback rate, so that it keeps all of its zero crossings as its cutoff lowers.
+/// Above a playback rate of `SINC_MAX_HALF_WIDTH / SINC_HALF_WIDTH`, it's capped to this width to limit
+/// the cost of each read, and has fewer zero crossings instead.
+pub const SINC_MAX_HALF_WIDTH: usize = 32;
+
+/// Number of table entries per unit of distance from the kernel's center
+const SINC_TABLE_RESOLUTION: usize = 512;
+
+/// Number of window table entries from the kernel's center to its edge
+const WINDOW_TABLE_RESOLUTION: usize = SINC_HALF_WIDTH * SINC_TABLE_RESOLUTION;
+
+/// Reads fractional positions out of a buffer of sample data.
+///
+/// Owns the lookup tables for the windowed sinc kernel, so that
+/// no trigonometric functions have to be evaluated on the audio thread.
+#[derive(Clone, Debug)]
+pub struct Interpolator {
+    /// sin(πx) / πx, sampled from 0 -> `SINC_HALF_WIDTH + 1`
+    sinc_table: Vec<f32>,
+    /// Blackman window, sampled from the center of the kernel (0.0) to its edge (1.0)
+    window_table: Vec<f32>,
+}
+
+impl Default for Interpolator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpolator {
+    pub fn new() -> Self {
+        // a widened kernel can reach up to one zero crossing further than `SINC_HALF_WIDTH`,
+        // plus one extra entry so that linear lookups at the very edge stay in bounds
+        let sinc_table_len = (SINC_HALF_WIDTH + 1) * SINC_TABLE_RESOLUTION + 2;
+
+        let sinc_table = (0..sinc_table_len)
+            .map(|i| {
+                let x = i as f32 / SINC_TABLE_RESOLUTION as f32;
+                if x == 0.0 {
+                    1.0
+                } else {
+                    (PI * x).sin() / (PI * x)
+                }
+            })
+            .collect();
+
+        let window_table = (0..WINDOW_TABLE_RESOLUTION + 2)
+            .map(|i| {
+                let phase = PI * (i as f32 / WINDOW_TABLE_RESOLUTION as f32).min(1.0);
+                (0.42 + 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos()).max(0.0)
+            })
+            .collect();
+
+        Self {
+            sinc_table,
+            window_table,
+        }
+    }
+
+    /// Returns the sample value at a fractional `position` inside the `buffer`.
+    ///
+    /// Any frames that would be read from outside of the buffer are clamped to its first/last frame.
+    ///
+    /// `playback_rate` is only used by `Interpolation::WindowedSinc` to band-limit the read
+    /// when the buffer is being read faster than real-time.
+    pub fn read(
+        &self,
+        buffer: &[f32],
+        position: f64,
+        playback_rate: f64,
+        interpolation: Interpolation,
+    ) -> f32 {
+        if buffer.is_empty() {
+            return 0.0;
+        }
+
+        let index = position.floor();
+        let fraction = (position - index) as f32;
+        let index = index as isize;
+        let sample = |offset: isize| -> f32 {
+            let i = (index + offset).max(0).min(buffer.len() as isize - 1);
+            buffer[i as usize]
+        };
+
+        match interpolation {
+            Interpolation::Linear => {
+                let x0 = sample(0);
+                let x1 = sample(1);
+                x0 + (x1 - x0) * fraction
+            }
+            Interpolation::CubicHermite => {
+                let x_m1 = sample(-1);
+                let x0 = sample(0);
+                let x1 = sample(1);
+                let x2 = sample(2);
+
+                let c1 = 0.5 * (x1 - x_m1);
+                let c2 = x_m1 - 2.5 * x0 + 2.0 * x1 - 0.5 * x2;
+                let c3 = 0.5 * (x2 - x_m1) + 1.5 * (x0 - x1);
+
+                ((c3 * fraction + c2) * fraction + c1) * fraction + x0
+            }
+            Interpolation::WindowedSinc => {
+                // lower the kernel's cutoff when reading faster than real-time
+                let cutoff = (1.0 / playback_rate.abs().max(f64::EPSILON)).min(1.0) as f32;
+                // and widen the kernel to match, so that it doesn't lose zero crossings
+                let half_width = ((SINC_HALF_WIDTH as f32 / cutoff).ceil() as usize)
+                    .min(SINC_MAX_HALF_WIDTH) as isize;
+
+                let mut sum = 0.0;
+                for offset in (1 - half_width)..=half_width {
+                    let distance = (offset as f32 - fraction).abs();
+                    let kernel = cutoff
+                        * Self::lookup(&self.sinc_table, SINC_TABLE_RESOLUTION, cutoff * distance)
+                        * Self::lookup(
+                            &self.window_table,
+                            WINDOW_TABLE_RESOLUTION,
+                            distance / half_width as f32,
+                        );
+                    sum += kernel * sample(offset);
+                }
+                sum
+            }
+        }
+    }
+
+    /// Linearly interpolates between the two closest table entries for a distance `x` (>= 0.0),
+    /// where `resolution` is the number of table entries per unit of distance
+    fn lookup(table: &[f32], resolution: usize, x: f32) -> f32 {
+        let table_position = x * resolution as f32;
+        let i = table_position as usize;
+        if i + 1 >= table.len() {
+            return 0.0;
+        }
+        let fraction = table_position - i as f32;
+        table[i] + (table[i + 1] - table[i]) * fraction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Interpolation; 3] = [
+        Interpolation::Linear,
+        Interpolation::CubicHermite,
+        Interpolation::WindowedSinc,
+    ];
+
+    fn ramp() -> Vec<f32> {
+        (0..64).map(|i| i as f32 / 64.0).collect()
+    }
+
+    #[test]
+    fn whole_positions_read_the_frame_itself() {
+        let interpolator = Interpolator::new();
+        let buffer: Vec<f32> = (0..64).map(|i| (i as f32 * 0.7).sin()).collect();
+        for interpolation in ALL {
+            for i in 8..56 {
+                let value = interpolator.read(&buffer, i as f64, 1.0, interpolation);
+                assert!((value - buffer[i]).abs() < 1e-3, "{:?}", interpolation);
+            }
+        }
+    }
+
+    #[test]
+    fn fractional_positions_follow_a_ramp() {
+        let interpolator = Interpolator::new();
+        let buffer = ramp();
+        for interpolation in ALL {
+            let value = interpolator.read(&buffer, 20.25, 1.0, interpolation);
+            assert!((value - 20.25 / 64.0).abs() < 1e-3, "{:?}", interpolation);
+        }
+    }
+
+    #[test]
+    fn positions_outside_the_buffer_are_clamped() {
+        let interpolator = Interpolator::new();
+        let buffer = ramp();
+        for interpolation in ALL {
+            assert_eq!(interpolator.read(&buffer, -10.0, 1.0, interpolation), 0.0);
+            assert_eq!(interpolator.read(&[], 1.5, 1.0, interpolation), 0.0);
+        }
+        let last = interpolator.read(&buffer, 100.0, 1.0, Interpolation::Linear);
+        assert_eq!(last, buffer[63]);
+    }
+
+    #[test]
+    fn widened_sinc_keeps_unity_gain_when_transposing_up() {
+        let interpolator = Interpolator::new();
+        let buffer = vec![0.5; 256];
+        for playback_rate in [1.0, 2.0, 3.5, 8.0] {
+            let value =
+                interpolator.read(&buffer, 128.3, playback_rate, Interpolation::WindowedSinc);
+            assert!(
+                (value - 0.5).abs() < 0.01,
+                "rate {}: {}",
+                playback_rate,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn widened_sinc_filters_out_content_above_the_lowered_cutoff() {
+        let interpolator = Interpolator::new();
+        // a tone at half of the nyquist frequency, which would alias when read at 4x
+        let buffer: Vec<f32> = (0..256)
+            .map(|i| (i as f32 * std::f32::consts::FRAC_PI_2).sin())
+            .collect();
+        for i in 0..16 {
+            let position = 100.0 + i as f64 * 0.37;
+            let value = interpolator.read(&buffer, position, 4.0, Interpolation::WindowedSinc);
+            assert!(value.abs() < 0.01, "{}", value);
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// The waveform that an `Lfo` follows over each cycle
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LfoShape {
+    #[default]
+    Sine,
+    Triangle,
+    Square,
+    /// Jumps to a new random value at the start of every cycle
+    SampleAndHold,
+    /// Glides smoothly from one random value to the next over every cycle
+    SmoothRandom,
+}
+
+impl LfoShape {
+    pub const NAMES: [&'static str; 5] = [
+        "sine",
+        "triangle",
+        "square",
+        "sample & hold",
+        "smooth random",
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sine" => Some(LfoShape::Sine),
+            "triangle" => Some(LfoShape::Triangle),
+            "square" => Some(LfoShape::Square),
+            "sample & hold" => Some(LfoShape::SampleAndHold),
+            "smooth random" => Some(LfoShape::SmoothRandom),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LfoShape::Sine => "sine",
+            LfoShape::Triangle => "triangle",
+            LfoShape::Square => "square",
+            LfoShape::SampleAndHold => "sample & hold",
+            LfoShape::SmoothRandom => "smooth random",
+        }
+    }
+}
+
+/// A low frequency oscillator, which produces a slowly-changing value (-depth -> depth)
+/// that can be used to modulate synthesizer parameters.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lfo {
+    shape: LfoShape,
+
+    /// Cycles per second
+    rate: f32,
+
+    /// The peak value of the oscillator (0.0 -> 1.0)
+    depth: f32,
+
+    /// How far (0.0 -> 1.0, as a fraction of a cycle) the waveform is shifted.
+    /// Has no effect on the random shapes.
+    phase_offset: f32,
+
+    /// How far (0.0 -> 1.0) the oscillator currently is through its cycle
+    #[serde(skip)]
+    phase: f64,
+
+    /// The random value at the start of the current cycle (used by `SmoothRandom`)
+    #[serde(skip)]
+    random_previous: f32,
+
+    /// The random value for the current cycle (held by `SampleAndHold`,
+    /// and glided toward by `SmoothRandom`)
+    #[serde(skip)]
+    random_next: f32,
+}
+
+impl Lfo {
+    pub const RATE_MIN: f32 = 0.01;
+
+    pub const RATE_MAX: f32 = 20.0;
+
+    pub const DEFAULT_RATE: f32 = 1.0;
+
+    pub fn new(shape: LfoShape, rate: f32, depth: f32, phase_offset: f32) -> Self {
+        let mut lfo = Self::default();
+        lfo.set_shape(shape)
+            .set_rate(rate)
+            .set_depth(depth)
+            .set_phase_offset(phase_offset);
+        lfo
+    }
+
+    pub fn shape(&self) -> LfoShape {
+        self.shape
+    }
+
+    pub fn set_shape(&mut self, shape: LfoShape) -> &mut Self {
+        self.shape = shape;
+
+        self
+    }
+
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    pub fn set_rate(&mut self, rate: f32) -> &mut Self {
+        self.rate = rate.clamp(Self::RATE_MIN, Self::RATE_MAX);
+
+        self
+    }
+
+    pub fn depth(&self) -> f32 {
+        self.depth
+    }
+
+    pub fn set_depth(&mut self, depth: f32) -> &mut Self {
+        self.depth = depth.clamp(0.0, 1.0);
+
+        self
+    }
+
+    pub fn phase_offset(&self) -> f32 {
+        self.phase_offset
+    }
+
+    pub fn set_phase_offset(&mut self, phase_offset: f32) -> &mut Self {
+        self.phase_offset = phase_offset.rem_euclid(1.0);
+
+        self
+    }
+
+    /// Copies the shape, rate, depth, and phase offset of `other`,
+    /// without restarting the current cycle
+    pub fn set_settings_from(&mut self, other: &Lfo) -> &mut Self {
+        self.shape = other.shape;
+        self.rate = other.rate;
+        self.depth = other.depth;
+        self.phase_offset = other.phase_offset;
+
+        self
+    }
+
+    /// Restarts the oscillator from the beginning of its cycle
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+        self.random_previous = 0.0;
+        self.random_next = 0.0;
+    }
+
+    /// The oscillator's value (-depth -> depth) at the current point in its cycle
+    pub fn value(&self) -> f32 {
+        let phase = (self.phase as f32 + self.phase_offset).fract();
+
+        let value = match self.shape {
+            LfoShape::Sine => (2.0 * PI * phase).sin(),
+            // starts at 0.0 and rises first, just like the sine
+            LfoShape::Triangle => 1.0 - 4.0 * ((phase + 0.25).fract() - 0.5).abs(),
+            LfoShape::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            LfoShape::SampleAndHold => self.random_next,
+            LfoShape::SmoothRandom => {
+                // cosine interpolation, so that there are no corners between cycles
+                let t = (1.0 - (PI * self.phase as f32).cos()) / 2.0;
+                self.random_previous + (self.random_next - self.random_previous) * t
+            }
+        };
+
+        value * self.depth
+    }
+
+    /// Returns the oscillator's current value and advances it by one frame
+    ///
+    /// New random values are only drawn from `rng` at the start of each cycle.
+    pub fn next_value(&mut self, sample_rate: u32, rng: &mut impl Rng) -> f32 {
+        let value = self.value();
+
+        self.phase += self.rate as f64 / sample_rate.max(1) as f64;
+        if self.phase >= 1.0 {
+            self.phase = self.phase.fract();
+            self.random_previous = self.random_next;
+            self.random_next = rng.gen_range(-1.0..=1.0);
+        }
+
+        value
+    }
+}
+
+impl Default for Lfo {
+    fn default() -> Self {
+        Self {
+            shape: LfoShape::default(),
+            rate: Self::DEFAULT_RATE,
+            depth: 1.0,
+            phase_offset: 0.0,
+            phase: 0.0,
+            random_previous: 0.0,
+            random_next: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// At a rate of 1.0, each cycle lasts exactly 4 frames
+    const SAMPLE_RATE: u32 = 4;
+
+    fn run(lfo: &mut Lfo, num_frames: usize) -> Vec<f32> {
+        let mut rng = StdRng::seed_from_u64(0);
+        (0..num_frames)
+            .map(|_| lfo.next_value(SAMPLE_RATE, &mut rng))
+            .collect()
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, b) in actual.iter().zip(expected) {
+            assert!(
+                (a - b).abs() < 1e-5,
+                "expected {:?}, got {:?}",
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn shape_names_round_trip() {
+        for name in LfoShape::NAMES {
+            assert_eq!(LfoShape::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(LfoShape::from_name("saw"), None);
+    }
+
+    #[test]
+    fn periodic_shapes_follow_their_waveforms() {
+        for (shape, expected) in [
+            (LfoShape::Sine, [0.0, 1.0, 0.0, -1.0]),
+            (LfoShape::Triangle, [0.0, 1.0, 0.0, -1.0]),
+            (LfoShape::Square, [1.0, 1.0, -1.0, -1.0]),
+        ] {
+            let mut lfo = Lfo::new(shape, 1.0, 1.0, 0.0);
+            assert_close(&run(&mut lfo, 4), &expected);
+            assert_close(&run(&mut lfo, 4), &expected);
+        }
+    }
+
+    #[test]
+    fn depth_and_phase_offset_shape_the_output() {
+        let mut lfo = Lfo::new(LfoShape::Sine, 1.0, 0.5, 0.25);
+        assert_close(&run(&mut lfo, 4), &[0.5, 0.0, -0.5, 0.0]);
+    }
+
+    #[test]
+    fn settings_are_clamped() {
+        let lfo = Lfo::new(LfoShape::Sine, 100.0, 2.0, 1.25);
+        assert_eq!(lfo.rate(), Lfo::RATE_MAX);
+        assert_eq!(lfo.depth(), 1.0);
+        assert_eq!(lfo.phase_offset(), 0.25);
+        assert_eq!(
+            Lfo::new(LfoShape::Sine, 0.0, -1.0, 0.0).rate(),
+            Lfo::RATE_MIN
+        );
+    }
+
+    #[test]
+    fn sample_and_hold_holds_each_value_for_a_cycle() {
+        let mut lfo = Lfo::new(LfoShape::SampleAndHold, 1.0, 1.0, 0.0);
+        let values = run(&mut lfo, 12);
+        for cycle in values.chunks(4) {
+            assert!(cycle.iter().all(|&value| value == cycle[0]));
+            assert!((-1.0..=1.0).contains(&cycle[0]));
+        }
+        assert_ne!(values[4], values[8]);
+    }
+
+    #[test]
+    fn smooth_random_glides_between_values() {
+        let mut lfo = Lfo::new(LfoShape::SmoothRandom, 1.0, 1.0, 0.0);
+        let values = run(&mut lfo, 400);
+        assert!(values.iter().all(|value| (-1.0..=1.0).contains(value)));
+        // each cycle starts where the previous one was headed
+        let mut sample_and_hold = Lfo::new(LfoShape::SampleAndHold, 1.0, 1.0, 0.0);
+        let held = run(&mut sample_and_hold, 400);
+        for cycle in 1..100 {
+            assert!((values[cycle * 4] - held[cycle * 4 - 1]).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn reset_restarts_the_cycle() {
+        let mut lfo = Lfo::new(LfoShape::Sine, 1.0, 1.0, 0.0);
+        run(&mut lfo, 3);
+        lfo.reset();
+        assert_close(&run(&mut lfo, 2), &[0.0, 1.0]);
+    }
+}
@@ -1,8 +1,33 @@
+pub mod adsr;
+pub mod channel_policy;
+pub mod delay;
+pub mod effect;
+pub mod effect_chain;
+pub mod envelope_shape;
 pub mod grain;
+pub mod grain_filter;
+pub mod grain_len_mode;
 pub mod granular_synthesizer;
 pub mod granular_synthesizer_action;
+pub mod interpolation;
+pub mod lfo;
+pub mod low_pass_filter;
 pub mod max;
+pub mod midi;
 pub mod min;
 pub mod mixdown;
+pub mod modulation_matrix;
+pub mod note_division;
+pub mod note_voice;
+pub mod offline_render;
+pub mod pan_law;
 pub mod percentage;
+pub mod pitch_quantizer;
+pub mod playhead;
+pub mod preset;
+pub mod resample;
+pub mod reverb;
+pub mod scheduler;
+pub mod smoothed_value;
+pub mod source_buffer;
 pub mod utils;
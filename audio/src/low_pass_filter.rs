@@ -0,0 +1,155 @@
+use crate::effect::{Effect, EffectParameter};
+use std::f32::consts::PI;
+
+/// A gentle (6 dB / octave) one-pole low-pass filter, for taming the brightness of dense grain clouds
+#[derive(Clone, Debug)]
+pub struct LowPassFilter {
+    cutoff_hz: f32,
+
+    sample_rate: u32,
+
+    /// How much of the previous output is kept for each new sample (derived from `cutoff_hz`)
+    coefficient: f32,
+
+    /// The most recent output of each channel
+    previous: Vec<f32>,
+}
+
+impl LowPassFilter {
+    pub const CUTOFF_HZ: EffectParameter = EffectParameter {
+        name: "cutoff (Hz)",
+        min: 20.0,
+        max: 20000.0,
+        default: 2000.0,
+        logarithmic: true,
+        options: &[],
+    };
+
+    const PARAMETERS: [EffectParameter; 1] = [Self::CUTOFF_HZ];
+
+    pub fn new(sample_rate: u32) -> Self {
+        let mut filter = Self {
+            cutoff_hz: Self::CUTOFF_HZ.default,
+            sample_rate,
+            coefficient: 0.0,
+            previous: Vec::new(),
+        };
+        filter.update_coefficient();
+        filter
+    }
+
+    pub fn cutoff_hz(&self) -> f32 {
+        self.cutoff_hz
+    }
+
+    pub fn set_cutoff_hz(&mut self, cutoff_hz: f32) -> &mut Self {
+        self.cutoff_hz = Self::CUTOFF_HZ.sanitize(cutoff_hz);
+        self.update_coefficient();
+
+        self
+    }
+
+    fn update_coefficient(&mut self) {
+        // cutoffs above the nyquist frequency leave the signal (nearly) untouched
+        let cutoff_hz = self.cutoff_hz.min(self.sample_rate as f32 * 0.49);
+        self.coefficient = (-2.0 * PI * cutoff_hz / self.sample_rate.max(1) as f32).exp();
+    }
+}
+
+impl Effect for LowPassFilter {
+    fn name(&self) -> &'static str {
+        "low-pass"
+    }
+
+    fn parameters(&self) -> &'static [EffectParameter] {
+        &Self::PARAMETERS
+    }
+
+    fn parameter(&self, index: usize) -> Option<f32> {
+        match index {
+            0 => Some(self.cutoff_hz),
+            _ => None,
+        }
+    }
+
+    fn set_parameter(&mut self, index: usize, value: f32) {
+        if index == 0 {
+            self.set_cutoff_hz(value);
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+        self.update_coefficient();
+    }
+
+    fn reset(&mut self) {
+        self.previous.fill(0.0);
+    }
+
+    fn process_block(&mut self, block: &mut [f32], num_channels: usize) {
+        if num_channels == 0 {
+            return;
+        }
+        if self.previous.len() < num_channels {
+            self.previous.resize(num_channels, 0.0);
+        }
+
+        let coefficient = self.coefficient;
+        for frame in block.chunks_mut(num_channels) {
+            for (sample, previous) in frame.iter_mut().zip(self.previous.iter_mut()) {
+                *previous = *sample * (1.0 - coefficient) + *previous * coefficient;
+                *sample = *previous;
+            }
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Effect> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The peak level of the last half of a processed signal (once the filter has settled)
+    fn settled_peak(filter: &mut LowPassFilter, mut block: Vec<f32>) -> f32 {
+        filter.process_block(&mut block, 1);
+        block[block.len() / 2..]
+            .iter()
+            .fold(0.0, |peak, sample| sample.abs().max(peak))
+    }
+
+    #[test]
+    fn passes_low_frequencies_and_attenuates_high_ones() {
+        let mut filter = LowPassFilter::new(44100);
+        filter.set_cutoff_hz(500.0);
+        assert!((settled_peak(&mut filter, vec![1.0; 4410]) - 1.0).abs() < 1e-3);
+
+        filter.reset();
+        let nyquist = (0..4410)
+            .map(|i| if i % 2 == 0 { 1.0 } else { -1.0 })
+            .collect();
+        assert!(settled_peak(&mut filter, nyquist) < 0.05);
+    }
+
+    #[test]
+    fn channels_are_filtered_independently() {
+        let mut filter = LowPassFilter::new(44100);
+        let mut block = vec![1.0, 0.0, 1.0, 0.0];
+        filter.process_block(&mut block, 2);
+        assert!(block[0] > 0.0 && block[2] > block[0]);
+        assert_eq!([block[1], block[3]], [0.0, 0.0]);
+    }
+
+    #[test]
+    fn reset_clears_the_filter_state() {
+        let mut filter = LowPassFilter::new(44100);
+        filter.process_block(&mut [1.0; 100], 1);
+        filter.reset();
+        let mut silence = [0.0; 10];
+        filter.process_block(&mut silence, 1);
+        assert_eq!(silence, [0.0; 10]);
+    }
+}
@@ -0,0 +1,130 @@
+/// A MIDI channel voice message that the synthesizer can respond to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MidiMessage {
+    NoteOn {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    NoteOff {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+}
+
+impl MidiMessage {
+    /// Controller number of the "All Sound Off" channel mode message
+    pub const ALL_SOUND_OFF: u8 = 120;
+
+    /// Controller number of the "All Notes Off" channel mode message
+    pub const ALL_NOTES_OFF: u8 = 123;
+
+    /// Parses a single raw MIDI message (e.g. from Web MIDI, a native MIDI port, or a `.mid` file track).
+    ///
+    /// A note-on with a velocity of 0 is treated as a note-off, as the MIDI spec requires.
+    /// Any message the synthesizer doesn't respond to (including system messages) returns `None`.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let (&status, data) = bytes.split_first()?;
+        let channel = status & 0x0F;
+
+        // data bytes never have their high bit set
+        let data_byte = |i: usize| data.get(i).copied().filter(|byte| byte & 0x80 == 0);
+
+        match status & 0xF0 {
+            0x80 => Some(MidiMessage::NoteOff {
+                channel,
+                note: data_byte(0)?,
+                velocity: data_byte(1)?,
+            }),
+            0x90 => {
+                let note = data_byte(0)?;
+                let velocity = data_byte(1)?;
+                if velocity == 0 {
+                    Some(MidiMessage::NoteOff {
+                        channel,
+                        note,
+                        velocity,
+                    })
+                } else {
+                    Some(MidiMessage::NoteOn {
+                        channel,
+                        note,
+                        velocity,
+                    })
+                }
+            }
+            0xB0 => Some(MidiMessage::ControlChange {
+                channel,
+                controller: data_byte(0)?,
+                value: data_byte(1)?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_note_messages() {
+        assert_eq!(
+            MidiMessage::parse(&[0x93, 60, 100]),
+            Some(MidiMessage::NoteOn {
+                channel: 3,
+                note: 60,
+                velocity: 100
+            })
+        );
+        assert_eq!(
+            MidiMessage::parse(&[0x80, 60, 64]),
+            Some(MidiMessage::NoteOff {
+                channel: 0,
+                note: 60,
+                velocity: 64
+            })
+        );
+    }
+
+    #[test]
+    fn note_on_without_velocity_is_a_note_off() {
+        assert_eq!(
+            MidiMessage::parse(&[0x9F, 72, 0]),
+            Some(MidiMessage::NoteOff {
+                channel: 15,
+                note: 72,
+                velocity: 0
+            })
+        );
+    }
+
+    #[test]
+    fn parses_control_changes() {
+        assert_eq!(
+            MidiMessage::parse(&[0xB0, MidiMessage::ALL_NOTES_OFF, 0]),
+            Some(MidiMessage::ControlChange {
+                channel: 0,
+                controller: MidiMessage::ALL_NOTES_OFF,
+                value: 0
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_unsupported_and_malformed_messages() {
+        // empty, pitch bend, system real-time clock
+        assert_eq!(MidiMessage::parse(&[]), None);
+        assert_eq!(MidiMessage::parse(&[0xE0, 0, 64]), None);
+        assert_eq!(MidiMessage::parse(&[0xF8]), None);
+        // truncated, and a status byte where a data byte should be
+        assert_eq!(MidiMessage::parse(&[0x90, 60]), None);
+        assert_eq!(MidiMessage::parse(&[0x90, 60, 0x90]), None);
+    }
+}
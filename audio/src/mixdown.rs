@@ -1,24 +1,45 @@
 /// Mixes a multichannel frame down to a dfferent number of output channels
+///
+/// This allocates a new `Vec` for every frame. On the audio thread, prefer `mixdown_into`.
 pub fn mixdown(samples_in: &[f32], num_channels_out: u32) -> Vec<f32> {
+    let mut samples_out = vec![0.0; num_channels_out as usize];
+    mixdown_into(samples_in, &mut samples_out);
+    samples_out
+}
+
+/// Mixes a multichannel frame down into the caller-provided `samples_out`,
+/// where each element of `samples_out` is one output channel.
+///
+/// Does not allocate.
+pub fn mixdown_into(samples_in: &[f32], samples_out: &mut [f32]) {
+    let num_channels_out = samples_out.len();
+    for (sample_out_i, sample_out) in samples_out.iter_mut().enumerate() {
+        *sample_out = mixdown_channel(samples_in, sample_out_i, num_channels_out);
+    }
+}
+
+/// Calculates the mixed down value of a single output channel (`sample_out_i`)
+/// out of `num_channels_out` total output channels.
+///
+/// Does not allocate.
+pub fn mixdown_channel(samples_in: &[f32], sample_out_i: usize, num_channels_out: usize) -> f32 {
     if num_channels_out == 0 {
-        return Vec::new();
+        return 0.0;
     }
 
-    let mut samples_out = vec![0.0; num_channels_out as usize];
+    let sample_out_index_progress = sample_out_i as f32 / num_channels_out as f32;
+    let mut sample_out = 0.0;
 
     for (sample_in_i, sample_in) in samples_in.iter().enumerate() {
-        for (sample_out_i, sample_out) in samples_out.iter_mut().enumerate() {
-            let sample_in_index_progress = sample_in_i as f32 / samples_in.len() as f32;
-            let sample_out_index_progress = sample_out_i as f32 / num_channels_out as f32;
-
-            // how "far away" the input channel is to the output channel (as a percentage)
-            let index_difference = (sample_out_index_progress - sample_in_index_progress).abs();
-            // multiplier makes input channels that are "closest" to the ouput channel louder
-            let amplitude_multiplier = 1.0 - index_difference;
-
-            let value_to_add = sample_in * amplitude_multiplier;
-            *sample_out += value_to_add;
-        }
+        let sample_in_index_progress = sample_in_i as f32 / samples_in.len() as f32;
+
+        // how "far away" the input channel is to the output channel (as a percentage)
+        let index_difference = (sample_out_index_progress - sample_in_index_progress).abs();
+        // multiplier makes input channels that are "closest" to the ouput channel louder
+        let amplitude_multiplier = 1.0 - index_difference;
+
+        let value_to_add = sample_in * amplitude_multiplier;
+        sample_out += value_to_add;
     }
 
     // hacky solution for now (@todo: research multi-channel mixdown algorithms):
@@ -30,9 +51,32 @@ pub fn mixdown(samples_in: &[f32], num_channels_out: u32) -> Vec<f32> {
         .cbrt()
         // do not allow dividing by zero (or anything close)
         .max(0.01);
-    for sample in &mut samples_out {
-        *sample /= scale_divisor;
+
+    sample_out / scale_divisor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixdown_into_matches_mixdown() {
+        let samples_in = [0.5, -0.25, 1.0, 0.0, 0.75];
+        let mut samples_out = [1.0; 2];
+        mixdown_into(&samples_in, &mut samples_out);
+        assert_eq!(samples_out.to_vec(), mixdown(&samples_in, 2));
     }
 
-    samples_out
+    #[test]
+    fn matching_channel_counts_pass_through() {
+        assert_eq!(mixdown(&[0.5], 1), [0.5]);
+        assert_eq!(mixdown(&[1.0, 0.0], 2), [1.0, 0.5]);
+    }
+
+    #[test]
+    fn empty_outputs_are_silent() {
+        assert!(mixdown(&[1.0, 1.0], 0).is_empty());
+        assert_eq!(mixdown_channel(&[1.0, 1.0], 0, 0), 0.0);
+        assert_eq!(mixdown(&[], 2), [0.0, 0.0]);
+    }
 }
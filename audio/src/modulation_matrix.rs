@@ -0,0 +1,271 @@
+use crate::lfo::Lfo;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// A synthesizer parameter that can be modulated
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModulationTarget {
+    /// Offsets the selection start (as a percentage of the buffer)
+    #[default]
+    SelectionStart,
+    /// Offsets the selection end (as a percentage of the buffer)
+    SelectionEnd,
+    /// Offsets the density
+    Density,
+    /// Scales the length of new grains (up to twice or half as long)
+    GrainLen,
+    /// Transposes new grains (up to an octave up or down)
+    Pitch,
+    /// Offsets the pan position of new grains
+    Pan,
+    /// Offsets the amplitude of all grains (1.0 = twice as loud, -1.0 = silent)
+    Gain,
+}
+
+impl ModulationTarget {
+    pub const COUNT: usize = 7;
+
+    pub const NAMES: [&'static str; Self::COUNT] = [
+        "selection start",
+        "selection end",
+        "density",
+        "grain length",
+        "pitch",
+        "pan",
+        "gain",
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "selection start" => Some(ModulationTarget::SelectionStart),
+            "selection end" => Some(ModulationTarget::SelectionEnd),
+            "density" => Some(ModulationTarget::Density),
+            "grain length" => Some(ModulationTarget::GrainLen),
+            "pitch" => Some(ModulationTarget::Pitch),
+            "pan" => Some(ModulationTarget::Pan),
+            "gain" => Some(ModulationTarget::Gain),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        Self::NAMES[self.index()]
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            ModulationTarget::SelectionStart => 0,
+            ModulationTarget::SelectionEnd => 1,
+            ModulationTarget::Density => 2,
+            ModulationTarget::GrainLen => 3,
+            ModulationTarget::Pitch => 4,
+            ModulationTarget::Pan => 5,
+            ModulationTarget::Gain => 6,
+        }
+    }
+}
+
+/// Connects one LFO to one synthesizer parameter
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModulationRouting {
+    /// Which of the matrix's LFOs is the source of the modulation
+    pub lfo_index: usize,
+
+    pub target: ModulationTarget,
+
+    /// How much (-1.0 -> 1.0) of the LFO's value is applied to the target
+    pub amount: f32,
+}
+
+impl ModulationRouting {
+    pub fn new(lfo_index: usize, target: ModulationTarget, amount: f32) -> Self {
+        Self {
+            lfo_index,
+            target,
+            amount: amount.clamp(-1.0, 1.0),
+        }
+    }
+}
+
+/// A fixed set of LFOs, along with a list of routings from those LFOs to synthesizer parameters.
+///
+/// Call `tick` once per frame to advance every LFO and recalculate the offset for every target.
+/// Routings are stored in preallocated memory, so ticking never allocates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModulationMatrix {
+    lfos: [Lfo; Self::NUM_LFOS],
+
+    routings: Vec<ModulationRouting>,
+
+    /// The summed modulation for each target, as of the most recent frame
+    offsets: [f32; ModulationTarget::COUNT],
+}
+
+impl ModulationMatrix {
+    pub const NUM_LFOS: usize = 4;
+
+    pub const MAX_ROUTINGS: usize = 16;
+
+    pub fn new() -> Self {
+        Self {
+            lfos: Default::default(),
+            routings: Vec::with_capacity(Self::MAX_ROUTINGS),
+            offsets: [0.0; ModulationTarget::COUNT],
+        }
+    }
+
+    pub fn lfo(&self, index: usize) -> Option<&Lfo> {
+        self.lfos.get(index)
+    }
+
+    /// Copies the settings of `lfo` into the LFO at `index` (without restarting its cycle)
+    pub fn set_lfo(&mut self, index: usize, lfo: &Lfo) -> &mut Self {
+        if let Some(existing_lfo) = self.lfos.get_mut(index) {
+            existing_lfo.set_settings_from(lfo);
+        }
+
+        self
+    }
+
+    pub fn routings(&self) -> &[ModulationRouting] {
+        &self.routings
+    }
+
+    /// Adds a new routing, unless it refers to an LFO that doesn't exist
+    /// or `MAX_ROUTINGS` routings already exist
+    pub fn add_routing(&mut self, routing: ModulationRouting) -> &mut Self {
+        if routing.lfo_index < Self::NUM_LFOS && self.routings.len() < Self::MAX_ROUTINGS {
+            self.routings.push(routing);
+        }
+
+        self
+    }
+
+    pub fn remove_routing(&mut self, index: usize) -> &mut Self {
+        if index < self.routings.len() {
+            self.routings.remove(index);
+        }
+
+        self
+    }
+
+    pub fn clear_routings(&mut self) -> &mut Self {
+        self.routings.clear();
+
+        self
+    }
+
+    /// Restarts every LFO and clears all current modulation (routings are kept)
+    pub fn reset(&mut self) {
+        for lfo in &mut self.lfos {
+            lfo.reset();
+        }
+        self.offsets = [0.0; ModulationTarget::COUNT];
+    }
+
+    /// Advances every LFO by one frame and sums each routing into its target's offset
+    pub fn tick(&mut self, sample_rate: u32, rng: &mut impl Rng) {
+        // no need to do any work if nothing is being modulated
+        if self.routings.is_empty() {
+            self.offsets = [0.0; ModulationTarget::COUNT];
+            return;
+        }
+
+        let mut lfo_values = [0.0; Self::NUM_LFOS];
+        for (lfo, lfo_value) in self.lfos.iter_mut().zip(lfo_values.iter_mut()) {
+            *lfo_value = lfo.next_value(sample_rate, rng);
+        }
+
+        self.offsets = [0.0; ModulationTarget::COUNT];
+        for routing in &self.routings {
+            self.offsets[routing.target.index()] += lfo_values[routing.lfo_index] * routing.amount;
+        }
+    }
+
+    /// The total modulation applied to `target` as of the most recent frame
+    pub fn offset(&self, target: ModulationTarget) -> f32 {
+        self.offsets[target.index()]
+    }
+}
+
+impl Default for ModulationMatrix {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lfo::LfoShape;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// At a rate of 1.0, each LFO cycle lasts exactly 4 frames
+    const SAMPLE_RATE: u32 = 4;
+
+    #[test]
+    fn target_names_round_trip() {
+        for name in ModulationTarget::NAMES {
+            assert_eq!(ModulationTarget::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(ModulationTarget::from_name("volume"), None);
+    }
+
+    #[test]
+    fn routings_are_summed_into_their_targets() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let square = Lfo::new(LfoShape::Square, 1.0, 1.0, 0.0);
+        let mut matrix = ModulationMatrix::new();
+        matrix
+            .set_lfo(0, &square)
+            .set_lfo(1, &square)
+            .add_routing(ModulationRouting::new(0, ModulationTarget::Pitch, 0.5))
+            .add_routing(ModulationRouting::new(1, ModulationTarget::Pitch, 0.25))
+            .add_routing(ModulationRouting::new(1, ModulationTarget::Pan, -2.0));
+
+        matrix.tick(SAMPLE_RATE, &mut rng);
+        assert_eq!(matrix.offset(ModulationTarget::Pitch), 0.75);
+        assert_eq!(matrix.offset(ModulationTarget::Pan), -1.0);
+        assert_eq!(matrix.offset(ModulationTarget::Gain), 0.0);
+
+        matrix.tick(SAMPLE_RATE, &mut rng);
+        matrix.tick(SAMPLE_RATE, &mut rng);
+        assert_eq!(matrix.offset(ModulationTarget::Pitch), -0.75);
+    }
+
+    #[test]
+    fn invalid_and_excess_routings_are_ignored() {
+        let mut matrix = ModulationMatrix::new();
+        matrix.add_routing(ModulationRouting::new(
+            ModulationMatrix::NUM_LFOS,
+            ModulationTarget::Pan,
+            1.0,
+        ));
+        assert!(matrix.routings().is_empty());
+
+        for _ in 0..ModulationMatrix::MAX_ROUTINGS + 1 {
+            matrix.add_routing(ModulationRouting::new(0, ModulationTarget::Pan, 1.0));
+        }
+        assert_eq!(matrix.routings().len(), ModulationMatrix::MAX_ROUTINGS);
+
+        matrix.remove_routing(0).remove_routing(100);
+        assert_eq!(matrix.routings().len(), ModulationMatrix::MAX_ROUTINGS - 1);
+        matrix.clear_routings();
+        assert!(matrix.routings().is_empty());
+    }
+
+    #[test]
+    fn removing_every_routing_clears_the_offsets() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut matrix = ModulationMatrix::new();
+        matrix
+            .set_lfo(0, &Lfo::new(LfoShape::Square, 1.0, 1.0, 0.0))
+            .add_routing(ModulationRouting::new(0, ModulationTarget::Density, 1.0));
+        matrix.tick(SAMPLE_RATE, &mut rng);
+        assert_eq!(matrix.offset(ModulationTarget::Density), 1.0);
+
+        matrix.clear_routings().tick(SAMPLE_RATE, &mut rng);
+        assert_eq!(matrix.offset(ModulationTarget::Density), 0.0);
+    }
+}
@@ -0,0 +1,108 @@
+/// A note length relative to the beat, used to sync time-based effects to a tempo
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum NoteDivision {
+    Whole,
+    Half,
+    DottedQuarter,
+    #[default]
+    Quarter,
+    QuarterTriplet,
+    DottedEighth,
+    Eighth,
+    EighthTriplet,
+    Sixteenth,
+    SixteenthTriplet,
+    ThirtySecond,
+}
+
+impl NoteDivision {
+    pub const NAMES: [&'static str; 11] = [
+        "1/1",
+        "1/2",
+        "1/4 dotted",
+        "1/4",
+        "1/4 triplet",
+        "1/8 dotted",
+        "1/8",
+        "1/8 triplet",
+        "1/16",
+        "1/16 triplet",
+        "1/32",
+    ];
+
+    /// Every division, in the same order as `NAMES`
+    pub const ALL: [NoteDivision; 11] = [
+        NoteDivision::Whole,
+        NoteDivision::Half,
+        NoteDivision::DottedQuarter,
+        NoteDivision::Quarter,
+        NoteDivision::QuarterTriplet,
+        NoteDivision::DottedEighth,
+        NoteDivision::Eighth,
+        NoteDivision::EighthTriplet,
+        NoteDivision::Sixteenth,
+        NoteDivision::SixteenthTriplet,
+        NoteDivision::ThirtySecond,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .position(|&n| n == name)
+            .map(|i| Self::ALL[i])
+    }
+
+    pub fn name(&self) -> &'static str {
+        Self::NAMES[self.index()]
+    }
+
+    /// The position of this division in `ALL` / `NAMES`
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|d| d == self).unwrap_or_default()
+    }
+
+    /// The length of the note in beats (a quarter note is one beat)
+    pub fn beats(&self) -> f32 {
+        match self {
+            NoteDivision::Whole => 4.0,
+            NoteDivision::Half => 2.0,
+            NoteDivision::DottedQuarter => 1.5,
+            NoteDivision::Quarter => 1.0,
+            NoteDivision::QuarterTriplet => 2.0 / 3.0,
+            NoteDivision::DottedEighth => 0.75,
+            NoteDivision::Eighth => 0.5,
+            NoteDivision::EighthTriplet => 1.0 / 3.0,
+            NoteDivision::Sixteenth => 0.25,
+            NoteDivision::SixteenthTriplet => 1.0 / 6.0,
+            NoteDivision::ThirtySecond => 0.125,
+        }
+    }
+
+    /// The length of the note in milliseconds at a tempo of `bpm` beats per minute
+    pub fn ms(&self, bpm: f32) -> f32 {
+        self.beats() * 60_000.0 / bpm.max(f32::EPSILON)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for (i, name) in NoteDivision::NAMES.iter().enumerate() {
+            let division = NoteDivision::from_name(name).unwrap();
+            assert_eq!(division.name(), *name);
+            assert_eq!(division.index(), i);
+        }
+        assert_eq!(NoteDivision::from_name("1/64"), None);
+    }
+
+    #[test]
+    fn converts_to_milliseconds_at_a_tempo() {
+        assert_eq!(NoteDivision::Quarter.ms(120.0), 500.0);
+        assert_eq!(NoteDivision::DottedEighth.ms(120.0), 375.0);
+        assert_eq!(NoteDivision::Whole.ms(60.0), 4000.0);
+        assert!((NoteDivision::EighthTriplet.ms(100.0) - 200.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::adsr::{Adsr, AdsrEnvelope};
+use serde::{Deserialize, Serialize};
+
+/// Determines whether the synthesizer drones continuously or is played with notes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayMode {
+    /// Grains play continuously, regardless of any notes
+    #[default]
+    Drone,
+    /// Grains only play while notes are sounding, transposed relative to the root note
+    Notes,
+}
+
+impl PlayMode {
+    pub const NAMES: [&'static str; 2] = ["drone", "notes"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "drone" => Some(PlayMode::Drone),
+            "notes" => Some(PlayMode::Notes),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlayMode::Drone => "drone",
+            PlayMode::Notes => "notes",
+        }
+    }
+}
+
+/// A single sounding note, which new grains can be assigned to
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct NoteVoice {
+    /// The MIDI note number that started this voice
+    pub note: u8,
+
+    /// The amplitude (0.0 -> 1.0) that the note's velocity maps to
+    pub velocity_gain: f32,
+
+    /// Whether the note is still held down
+    pub held: bool,
+
+    /// Increases every time a voice is started, so that the oldest voice can be stolen
+    pub started_at: u64,
+
+    pub envelope: AdsrEnvelope,
+}
+
+impl NoteVoice {
+    pub fn is_idle(&self) -> bool {
+        self.envelope.is_idle()
+    }
+
+    /// The voice's current amplitude (envelope * velocity)
+    pub fn gain(&self) -> f32 {
+        self.envelope.value() * self.velocity_gain
+    }
+
+    /// Advances the voice's envelope by one frame
+    pub fn next_frame(&mut self, adsr: &Adsr, sample_rate: u32) {
+        self.envelope.next_value(adsr, sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn play_mode_names_round_trip() {
+        for name in PlayMode::NAMES {
+            assert_eq!(PlayMode::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(PlayMode::from_name("mono"), None);
+    }
+
+    #[test]
+    fn gain_follows_the_envelope_and_velocity() {
+        let adsr = Adsr::new(0.0, 0.0, 1.0, 0.0);
+        let mut voice = NoteVoice {
+            velocity_gain: 0.5,
+            ..NoteVoice::default()
+        };
+        assert!(voice.is_idle());
+        assert_eq!(voice.gain(), 0.0);
+
+        voice.envelope.note_on();
+        voice.next_frame(&adsr, 44100);
+        assert_eq!(voice.gain(), 0.5);
+        assert!(!voice.is_idle());
+    }
+}
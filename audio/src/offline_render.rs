@@ -0,0 +1,164 @@
+use crate::{effect_chain::EffectChain, granular_synthesizer_action::GranularSynthesizerAction};
+
+/// How much audio an offline render should produce
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RenderLength {
+    /// A number of frames at the synthesizer's sample rate
+    Frames(usize),
+    /// A duration in seconds, converted to frames using the synthesizer's sample rate
+    Seconds(f32),
+}
+
+impl RenderLength {
+    /// The number of frames this length corresponds to at `sample_rate`
+    pub fn num_frames(&self, sample_rate: u32) -> usize {
+        match *self {
+            RenderLength::Frames(num_frames) => num_frames,
+            RenderLength::Seconds(seconds) => (seconds.max(0.0) * sample_rate as f32) as usize,
+        }
+    }
+}
+
+/// Number of frames rendered per call to `process_block`,
+/// so that block-rate work is spread the same way it would be in a real-time audio callback
+pub const RENDER_BLOCK_FRAMES: usize = 512;
+
+/// Runs a synthesizer faster than real-time, returning interleaved output with `num_channels` channels.
+///
+/// The synthesizer is advanced by the rendered length, so render a clone to leave the original untouched.
+pub fn render(
+    synthesizer: &mut impl GranularSynthesizerAction,
+    length: RenderLength,
+    num_channels: usize,
+) -> Vec<f32> {
+    let num_frames = length.num_frames(synthesizer.sample_rate());
+    let mut out = vec![0.0; num_frames * num_channels];
+    render_into(synthesizer, &mut out, num_channels);
+
+    out
+}
+
+/// Fills `out` with interleaved output with `num_channels` channels.
+///
+/// This can be called repeatedly with consecutive slices of a larger buffer to render it in pieces.
+pub fn render_into(
+    synthesizer: &mut impl GranularSynthesizerAction,
+    out: &mut [f32],
+    num_channels: usize,
+) {
+    if num_channels == 0 {
+        return;
+    }
+
+    for block in out.chunks_mut(RENDER_BLOCK_FRAMES * num_channels) {
+        synthesizer.process_block(block, num_channels);
+    }
+}
+
+/// Like `render`, but runs the output through `effect_chain` (e.g. a reverb),
+/// exactly as it would be processed in a real-time audio callback.
+///
+/// The effect chain keeps any tail it's holding onto, so render a clone to leave the original untouched.
+pub fn render_with_effects(
+    synthesizer: &mut impl GranularSynthesizerAction,
+    effect_chain: &mut EffectChain,
+    length: RenderLength,
+    num_channels: usize,
+) -> Vec<f32> {
+    let num_frames = length.num_frames(synthesizer.sample_rate());
+    let mut out = vec![0.0; num_frames * num_channels];
+    render_into_with_effects(synthesizer, effect_chain, &mut out, num_channels);
+
+    out
+}
+
+/// Like `render_into`, but runs each block through `effect_chain` after it has been synthesized
+pub fn render_into_with_effects(
+    synthesizer: &mut impl GranularSynthesizerAction,
+    effect_chain: &mut EffectChain,
+    out: &mut [f32],
+    num_channels: usize,
+) {
+    if num_channels == 0 {
+        return;
+    }
+
+    for block in out.chunks_mut(RENDER_BLOCK_FRAMES * num_channels) {
+        synthesizer.process_block(block, num_channels);
+        effect_chain.process_block(block, num_channels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        granular_synthesizer::GranularSynthesizer, low_pass_filter::LowPassFilter,
+        source_buffer::SourceBuffer,
+    };
+    use std::sync::Arc;
+
+    fn synthesizer() -> GranularSynthesizer {
+        let samples = (0..44100).map(|i| (i as f32 * 0.01).sin()).collect();
+        let mut synthesizer = GranularSynthesizer::new_with_seed(0);
+        synthesizer.set_buffer(Arc::new(SourceBuffer::from_mono(samples, 44100)));
+        synthesizer
+    }
+
+    #[test]
+    fn lengths_convert_to_frames() {
+        assert_eq!(RenderLength::Frames(100).num_frames(48000), 100);
+        assert_eq!(RenderLength::Seconds(0.5).num_frames(48000), 24000);
+        assert_eq!(RenderLength::Seconds(-1.0).num_frames(48000), 0);
+    }
+
+    #[test]
+    fn renders_the_requested_length() {
+        let mut synthesizer = synthesizer();
+        let out = render(&mut synthesizer, RenderLength::Seconds(0.1), 2);
+        assert_eq!(out.len(), 4410 * 2);
+        assert!(out.iter().any(|&sample| sample != 0.0));
+
+        assert!(render(&mut synthesizer, RenderLength::Frames(10), 0).is_empty());
+    }
+
+    #[test]
+    fn rendering_in_pieces_matches_rendering_at_once() {
+        let whole = render(&mut synthesizer(), RenderLength::Frames(4096), 2);
+
+        let mut synthesizer = synthesizer();
+        let mut pieces = vec![0.0; whole.len()];
+        let (first, second) = pieces.split_at_mut(RENDER_BLOCK_FRAMES * 3 * 2);
+        render_into(&mut synthesizer, first, 2);
+        render_into(&mut synthesizer, second, 2);
+
+        assert_eq!(pieces, whole);
+    }
+
+    #[test]
+    fn effects_are_applied_to_every_block() {
+        let dry = render(&mut synthesizer(), RenderLength::Frames(4096), 2);
+
+        let mut empty_chain = EffectChain::new(44100);
+        let unprocessed = render_with_effects(
+            &mut synthesizer(),
+            &mut empty_chain,
+            RenderLength::Frames(4096),
+            2,
+        );
+        assert_eq!(unprocessed, dry);
+
+        let mut effect_chain = EffectChain::new(44100);
+        let mut filter = LowPassFilter::new(44100);
+        filter.set_cutoff_hz(200.0);
+        effect_chain.add_effect(Box::new(filter));
+        let filtered = render_with_effects(
+            &mut synthesizer(),
+            &mut effect_chain,
+            RenderLength::Frames(4096),
+            2,
+        );
+        assert_eq!(filtered.len(), dry.len());
+        assert_ne!(filtered, dry);
+    }
+}
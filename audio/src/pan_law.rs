@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use std::f32::consts::FRAC_PI_2;
+
+/// Determines how a grain's amplitude is split between the two output channels it is panned between
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanLaw {
+    /// Constant power: a centered grain plays at -3dB in both channels
+    #[default]
+    EqualPower,
+    /// Compromise between equal power and linear: a centered grain plays at -4.5dB in both channels
+    MinusFourPointFiveDb,
+    /// Constant amplitude: a centered grain plays at -6dB in both channels
+    Linear,
+}
+
+impl PanLaw {
+    pub const NAMES: [&'static str; 3] = ["equal power", "-4.5 dB", "linear"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "equal power" => Some(PanLaw::EqualPower),
+            "-4.5 dB" => Some(PanLaw::MinusFourPointFiveDb),
+            "linear" => Some(PanLaw::Linear),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PanLaw::EqualPower => "equal power",
+            PanLaw::MinusFourPointFiveDb => "-4.5 dB",
+            PanLaw::Linear => "linear",
+        }
+    }
+
+    /// Returns the [left, right] gains for a `pan` position (-1.0 = left, 1.0 = right)
+    pub fn gains(&self, pan: f32) -> [f32; 2] {
+        let x = (pan.clamp(-1.0, 1.0) + 1.0) / 2.0;
+
+        match self {
+            PanLaw::EqualPower => [(x * FRAC_PI_2).cos(), (x * FRAC_PI_2).sin()],
+            // `max` guards against `cos` dipping just below 0.0 when panned hard right
+            PanLaw::MinusFourPointFiveDb => [
+                ((1.0 - x) * (x * FRAC_PI_2).cos()).max(0.0).sqrt(),
+                (x * (x * FRAC_PI_2).sin()).max(0.0).sqrt(),
+            ],
+            PanLaw::Linear => [1.0 - x, x],
+        }
+    }
+
+    /// Spreads a `pan` position (-1.0 -> 1.0) across `num_channels` output channels,
+    /// where -1.0 is the first channel and 1.0 is the last.
+    ///
+    /// Returns the first of the two adjacent output channels that the position falls between,
+    /// along with the gains for that channel and the one after it.
+    /// A single output channel always gets the full signal.
+    pub fn output_gains(&self, pan: f32, num_channels: usize) -> (usize, [f32; 2]) {
+        if num_channels <= 1 {
+            return (0, [1.0, 0.0]);
+        }
+
+        let position = (pan.clamp(-1.0, 1.0) + 1.0) / 2.0 * (num_channels - 1) as f32;
+        // the last channel pair is used for a grain panned all the way to the end
+        let first_channel = (position as usize).min(num_channels - 2);
+        let pair_position = position - first_channel as f32;
+
+        (first_channel, self.gains(pair_position * 2.0 - 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_db(gain: f32) -> f32 {
+        20.0 * gain.log10()
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for name in PanLaw::NAMES {
+            assert_eq!(PanLaw::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(PanLaw::from_name("-3 dB"), None);
+    }
+
+    #[test]
+    fn centered_grains_are_attenuated_by_the_law() {
+        for (law, expected_db) in [
+            (PanLaw::EqualPower, -3.01),
+            (PanLaw::MinusFourPointFiveDb, -4.5),
+            (PanLaw::Linear, -6.02),
+        ] {
+            let [left, right] = law.gains(0.0);
+            assert!((left - right).abs() < 1e-6);
+            assert!((to_db(left) - expected_db).abs() < 0.05, "{:?}", law);
+        }
+    }
+
+    #[test]
+    fn hard_panned_grains_play_in_one_channel() {
+        for law in [
+            PanLaw::EqualPower,
+            PanLaw::MinusFourPointFiveDb,
+            PanLaw::Linear,
+        ] {
+            let [left, right] = law.gains(-1.0);
+            assert!((left - 1.0).abs() < 1e-6 && right.abs() < 1e-6);
+            let [left, right] = law.gains(1.0);
+            assert!(left.abs() < 1e-3 && (right - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn equal_power_keeps_power_constant() {
+        for i in 0..=10 {
+            let [left, right] = PanLaw::EqualPower.gains(i as f32 / 5.0 - 1.0);
+            assert!((left * left + right * right - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn output_gains_spread_across_channels() {
+        let law = PanLaw::Linear;
+        assert_eq!(law.output_gains(0.3, 1), (0, [1.0, 0.0]));
+        assert_eq!(law.output_gains(-1.0, 2), (0, [1.0, 0.0]));
+        assert_eq!(law.output_gains(1.0, 2), (0, [0.0, 1.0]));
+
+        // with 5 channels, each adjacent pair covers a quarter of the pan range
+        assert_eq!(law.output_gains(-0.5, 5), (1, [1.0, 0.0]));
+        assert_eq!(law.output_gains(-0.25, 5), (1, [0.5, 0.5]));
+        assert_eq!(law.output_gains(1.0, 5), (3, [0.0, 1.0]));
+    }
+}
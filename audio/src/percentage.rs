@@ -7,15 +7,19 @@ pub struct Percentage(f32);
 /// Only accepts values ranging from 0.0 -> 1.0.
 ///
 /// If a value outside of these bounds is received, it will be truncated to match the range.
+/// Values that aren't finite (such as `NaN`) are treated as 0.0.
 impl Percentage {
     pub const PERCENTAGE_MAX: f32 = 1.0;
 
     pub const PERCENTAGE_MIN: f32 = 0.0;
 
     pub fn sanitize_percentage(percentage: f32) -> f32 {
-        percentage
-            .max(Self::PERCENTAGE_MIN)
-            .min(Self::PERCENTAGE_MAX)
+        // `clamp` passes `NaN` through, which would then poison every calculation that uses it
+        if !percentage.is_finite() {
+            return Self::PERCENTAGE_MIN;
+        }
+
+        percentage.clamp(Self::PERCENTAGE_MIN, Self::PERCENTAGE_MAX)
     }
 
     pub fn new(percentage: f32) -> Self {
@@ -136,3 +140,23 @@ impl From<f32> for Percentage {
         Self::new(percentage)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        assert_eq!(Percentage::new(-0.5).get(), 0.0);
+        assert_eq!(Percentage::new(0.25).get(), 0.25);
+        assert_eq!(Percentage::new(1.5).get(), 1.0);
+    }
+
+    #[test]
+    fn non_finite_values_become_zero() {
+        for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert_eq!(Percentage::new(value).get(), 0.0);
+            assert_eq!(Percentage::new(0.5).set(value).get(), 0.0);
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Built-in pitch sets that grains can be quantized to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Scale {
+    #[default]
+    Major,
+    /// Natural minor
+    Minor,
+    /// Major pentatonic
+    Pentatonic,
+    WholeTone,
+}
+
+impl Scale {
+    pub const NAMES: [&'static str; 4] = ["major", "minor", "pentatonic", "whole tone"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "major" => Some(Scale::Major),
+            "minor" => Some(Scale::Minor),
+            "pentatonic" => Some(Scale::Pentatonic),
+            "whole tone" => Some(Scale::WholeTone),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scale::Major => "major",
+            Scale::Minor => "minor",
+            Scale::Pentatonic => "pentatonic",
+            Scale::WholeTone => "whole tone",
+        }
+    }
+
+    /// The scale's degrees, in semitones above its root
+    pub fn semitones(&self) -> &'static [f32] {
+        match self {
+            Scale::Major => &[0.0, 2.0, 4.0, 5.0, 7.0, 9.0, 11.0],
+            Scale::Minor => &[0.0, 2.0, 3.0, 5.0, 7.0, 8.0, 10.0],
+            Scale::Pentatonic => &[0.0, 2.0, 4.0, 7.0, 9.0],
+            Scale::WholeTone => &[0.0, 2.0, 4.0, 6.0, 8.0, 10.0],
+        }
+    }
+}
+
+/// Reasons that a Scala (`.scl`) tuning file can fail to load
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScalaError {
+    /// The file ended before the number of notes was given
+    MissingNoteCount,
+    /// The number of notes wasn't a positive whole number
+    InvalidNoteCount(String),
+    /// A pitch wasn't a valid cents value or ratio
+    InvalidPitch(String),
+    /// The file had fewer pitches than its note count
+    MissingPitches { expected: usize, found: usize },
+    /// The last pitch of the tuning (the interval it repeats at) wasn't above the root
+    InvalidPeriod,
+}
+
+impl fmt::Display for ScalaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScalaError::MissingNoteCount => write!(f, "missing the number of notes"),
+            ScalaError::InvalidNoteCount(line) => write!(f, "invalid number of notes: {}", line),
+            ScalaError::InvalidPitch(line) => write!(f, "invalid pitch: {}", line),
+            ScalaError::MissingPitches { expected, found } => {
+                write!(f, "expected {} pitches, but found {}", expected, found)
+            }
+            ScalaError::InvalidPeriod => write!(f, "the tuning must repeat above its root"),
+        }
+    }
+}
+
+impl std::error::Error for ScalaError {}
+
+/// Restricts transpositions to a set of pitches that repeats every `period` semitones
+/// (usually an octave).
+///
+/// Pitches are measured in (possibly fractional) semitones above the set's root,
+/// so microtonal tunings can be represented as well as ordinary scales and chords.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "PitchQuantizerData")]
+pub struct PitchQuantizer {
+    name: String,
+
+    /// Sorted and deduplicated, all within 0 -> `period`
+    pitches: Vec<f32>,
+
+    /// The interval (in semitones) that `pitches` repeat at
+    period: f32,
+}
+
+/// The serialized form of a `PitchQuantizer`, which is validated before it's used
+#[derive(Deserialize)]
+struct PitchQuantizerData {
+    name: String,
+    pitches: Vec<f32>,
+    period: f32,
+}
+
+impl TryFrom<PitchQuantizerData> for PitchQuantizer {
+    type Error = &'static str;
+
+    fn try_from(data: PitchQuantizerData) -> Result<Self, Self::Error> {
+        if !data.period.is_finite() || data.period <= 0.0 {
+            return Err("pitch sets must repeat at an interval above 0");
+        }
+
+        Self::new(data.name, &data.pitches, data.period).ok_or("pitch sets must not be empty")
+    }
+}
+
+impl PitchQuantizer {
+    /// Pitch sets that aren't otherwise given a period repeat every octave
+    pub const OCTAVE: f32 = 12.0;
+
+    /// Creates a pitch set from a list of semitones above the root (e.g. `[0, 7, 12, 19]`).
+    ///
+    /// The set repeats every octave, so pitches that are an octave apart are interchangeable.
+    /// Returns `None` if there aren't any pitches.
+    pub fn from_semitones(name: impl Into<String>, semitones: &[f32]) -> Option<Self> {
+        Self::new(name, semitones, Self::OCTAVE)
+    }
+
+    pub fn from_scale(scale: Scale) -> Self {
+        Self::from_semitones(scale.name(), scale.semitones())
+            .expect("Built-in scales should not be empty")
+    }
+
+    /// Parses a tuning from the contents of a Scala (`.scl`) file.
+    ///
+    /// The tuning's description is used as its name, and its last pitch
+    /// (usually 2/1) is used as the interval that the tuning repeats at.
+    pub fn from_scala(contents: &str) -> Result<Self, ScalaError> {
+        let mut lines = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('!'));
+
+        // the description is allowed to be empty, but must be present
+        let description = lines.next().ok_or(ScalaError::MissingNoteCount)?;
+        let note_count_line = lines.next().ok_or(ScalaError::MissingNoteCount)?;
+        let note_count = note_count_line
+            .split_whitespace()
+            .next()
+            .and_then(|count| count.parse::<usize>().ok())
+            .filter(|&count| count > 0)
+            .ok_or_else(|| ScalaError::InvalidNoteCount(note_count_line.to_string()))?;
+
+        // scala files list every pitch except the root (which is implicitly 1/1)
+        let mut pitches = vec![0.0];
+        for line in lines.filter(|line| !line.is_empty()).take(note_count) {
+            pitches.push(Self::parse_scala_pitch(line)?);
+        }
+
+        if pitches.len() - 1 < note_count {
+            return Err(ScalaError::MissingPitches {
+                expected: note_count,
+                found: pitches.len() - 1,
+            });
+        }
+
+        let period = pitches.pop().unwrap();
+        if period <= 0.0 {
+            return Err(ScalaError::InvalidPeriod);
+        }
+
+        let name = if description.is_empty() {
+            "scala"
+        } else {
+            description
+        };
+
+        Ok(Self::new(name, &pitches, period).expect("Tuning should include its root"))
+    }
+
+    /// Converts a single Scala pitch (cents if it contains a `.`, otherwise a ratio) to semitones
+    fn parse_scala_pitch(line: &str) -> Result<f32, ScalaError> {
+        let invalid_pitch = || ScalaError::InvalidPitch(line.to_string());
+        // anything after the pitch value is a comment
+        let value = line.split_whitespace().next().ok_or_else(invalid_pitch)?;
+
+        let semitones = if value.contains('.') {
+            value.parse::<f32>().map_err(|_| invalid_pitch())? / 100.0
+        } else {
+            let (numerator, denominator) = value.split_once('/').unwrap_or((value, "1"));
+            let numerator = numerator.parse::<f32>().map_err(|_| invalid_pitch())?;
+            let denominator = denominator.parse::<f32>().map_err(|_| invalid_pitch())?;
+            if numerator <= 0.0 || denominator <= 0.0 {
+                return Err(invalid_pitch());
+            }
+
+            Self::OCTAVE * (numerator / denominator).log2()
+        };
+
+        if semitones.is_finite() {
+            Ok(semitones)
+        } else {
+            Err(invalid_pitch())
+        }
+    }
+
+    fn new(name: impl Into<String>, semitones: &[f32], period: f32) -> Option<Self> {
+        let mut pitches: Vec<f32> = semitones
+            .iter()
+            .filter(|pitch| pitch.is_finite())
+            .map(|pitch| pitch.rem_euclid(period))
+            .collect();
+        pitches.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        pitches.dedup_by(|a, b| (*a - *b).abs() < f32::EPSILON);
+
+        if pitches.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            name: name.into(),
+            pitches,
+            period,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The pitches (in semitones above the root) within a single period
+    pub fn pitches(&self) -> &[f32] {
+        &self.pitches
+    }
+
+    /// The interval (in semitones) that the pitch set repeats at
+    pub fn period(&self) -> f32 {
+        self.period
+    }
+
+    /// Snaps a transposition (in semitones) to the closest pitch in the set
+    pub fn quantize(&self, semitones: f32) -> f32 {
+        let period_start = (semitones / self.period).floor() * self.period;
+        let within_period = semitones - period_start;
+
+        // the closest pitch may be the last one from the period below or the first from the one above
+        let below = self.pitches[self.pitches.len() - 1] - self.period;
+        let above = self.pitches[0] + self.period;
+
+        let closest = self
+            .pitches
+            .iter()
+            .copied()
+            .chain([below, above])
+            .min_by(|a, b| {
+                (a - within_period)
+                    .abs()
+                    .partial_cmp(&(b - within_period).abs())
+                    .unwrap()
+            })
+            .unwrap();
+
+        period_start + closest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn scale_names_round_trip() {
+        for name in Scale::NAMES {
+            assert_eq!(Scale::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(Scale::from_name("lydian"), None);
+    }
+
+    #[test]
+    fn quantizes_to_the_closest_scale_degree() {
+        let major = PitchQuantizer::from_scale(Scale::Major);
+        assert_eq!(major.quantize(0.0), 0.0);
+        assert_eq!(major.quantize(0.9), 0.0);
+        assert_eq!(major.quantize(1.1), 2.0);
+        assert_eq!(major.quantize(6.4), 7.0);
+    }
+
+    #[test]
+    fn quantizes_across_periods() {
+        let major = PitchQuantizer::from_scale(Scale::Major);
+        assert_eq!(major.quantize(14.2), 14.0);
+        assert_eq!(major.quantize(-0.9), -1.0);
+        assert_eq!(major.quantize(-12.4), -12.0);
+        // closer to the next octave's root than to the 7th degree
+        assert_eq!(major.quantize(11.8), 12.0);
+    }
+
+    #[test]
+    fn pitches_are_folded_into_a_single_period() {
+        let quantizer = PitchQuantizer::from_semitones("fifths", &[0.0, 7.0, 12.0, 19.0]).unwrap();
+        assert_eq!(quantizer.pitches(), [0.0, 7.0]);
+        assert_eq!(quantizer.period(), PitchQuantizer::OCTAVE);
+        assert_eq!(quantizer.quantize(20.0), 19.0);
+    }
+
+    #[test]
+    fn empty_pitch_sets_are_rejected() {
+        assert_eq!(PitchQuantizer::from_semitones("empty", &[]), None);
+        assert_eq!(PitchQuantizer::from_semitones("nan", &[f32::NAN]), None);
+    }
+
+    #[test]
+    fn parses_scala_cents_and_ratios() {
+        let contents = "! meantone.scl\n\
+                        !\n\
+                        Quarter-comma meantone\n\
+                        \x20 3\n\
+                        !\n\
+                        386.3137 ! major third\n\
+                        3/2\n\
+                        2/1\n";
+        let quantizer = PitchQuantizer::from_scala(contents).unwrap();
+        assert_eq!(quantizer.name(), "Quarter-comma meantone");
+        assert_close(quantizer.period(), 12.0);
+        assert_eq!(quantizer.pitches().len(), 3);
+        assert_close(quantizer.pitches()[1], 3.863137);
+        assert_close(quantizer.pitches()[2], 7.01955);
+    }
+
+    #[test]
+    fn scala_tunings_repeat_at_their_last_pitch() {
+        // Bohlen-Pierce repeats at a tritave (3/1) rather than an octave
+        let quantizer = PitchQuantizer::from_scala("bp\n2\n950.0\n3/1\n").unwrap();
+        let tritave = 12.0 * 3.0_f32.log2();
+        assert_close(quantizer.period(), tritave);
+        assert_close(quantizer.quantize(tritave + 0.1), tritave);
+    }
+
+    #[test]
+    fn invalid_scala_files_are_rejected() {
+        assert_eq!(
+            PitchQuantizer::from_scala(""),
+            Err(ScalaError::MissingNoteCount)
+        );
+        assert_eq!(
+            PitchQuantizer::from_scala("name\nmany\n"),
+            Err(ScalaError::InvalidNoteCount("many".to_string()))
+        );
+        assert_eq!(
+            PitchQuantizer::from_scala("name\n2\n3/2\n"),
+            Err(ScalaError::MissingPitches {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            PitchQuantizer::from_scala("name\n1\n-3/2\n"),
+            Err(ScalaError::InvalidPitch("-3/2".to_string()))
+        );
+        assert_eq!(
+            PitchQuantizer::from_scala("name\n1\n-100.0\n"),
+            Err(ScalaError::InvalidPeriod)
+        );
+    }
+
+    #[test]
+    fn deserializing_validates_the_pitch_set() {
+        let json = r#"{"name":"fifths","pitches":[0.0,7.0,19.0],"period":12.0}"#;
+        let quantizer: PitchQuantizer = serde_json::from_str(json).unwrap();
+        assert_eq!(quantizer.pitches(), [0.0, 7.0]);
+
+        let empty = r#"{"name":"empty","pitches":[],"period":12.0}"#;
+        assert!(serde_json::from_str::<PitchQuantizer>(empty).is_err());
+        let no_period = r#"{"name":"fifths","pitches":[0.0],"period":0.0}"#;
+        assert!(serde_json::from_str::<PitchQuantizer>(no_period).is_err());
+    }
+}
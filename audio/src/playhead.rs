@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+/// Determines where in the selection new grains start reading from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionMode {
+    /// Grains start anywhere inside the selection, chosen uniformly at random
+    #[default]
+    Random,
+    /// Grains start around a playhead that moves through the selection over time
+    /// (i.e. granular time-stretching)
+    Scan,
+}
+
+impl PositionMode {
+    pub const NAMES: [&'static str; 2] = ["random", "scan"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "random" => Some(PositionMode::Random),
+            "scan" => Some(PositionMode::Scan),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PositionMode::Random => "random",
+            PositionMode::Scan => "scan",
+        }
+    }
+}
+
+/// A position in the buffer that moves at a constant speed,
+/// looping around within a range of the buffer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Playhead {
+    /// The current position, in buffer frames
+    position: f64,
+
+    /// How many buffer frames the playhead moves per frame of (real-time) playback.
+    ///
+    /// 1.0 is the original speed, fractional values stretch the sound out,
+    /// 0.0 freezes the playhead in place, and negative values move backwards.
+    speed: f32,
+}
+
+impl Playhead {
+    pub fn new(speed: f32) -> Self {
+        Self {
+            position: 0.0,
+            speed,
+        }
+    }
+
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: f64) -> &mut Self {
+        self.position = position;
+
+        self
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f32) -> &mut Self {
+        self.speed = speed;
+
+        self
+    }
+
+    /// Moves the playhead by `speed * frames` buffer frames, wrapping around
+    /// so that it always stays within `loop_start..loop_end`.
+    pub fn advance(&mut self, frames: f64, loop_start: f64, loop_end: f64) {
+        if loop_end <= loop_start {
+            self.position = loop_start;
+            return;
+        }
+
+        self.position += self.speed as f64 * frames;
+
+        if self.position < loop_start || self.position >= loop_end {
+            let loop_len = loop_end - loop_start;
+            self.position = loop_start + (self.position - loop_start).rem_euclid(loop_len);
+        }
+    }
+}
+
+impl Default for Playhead {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_mode_names_round_trip() {
+        for name in PositionMode::NAMES {
+            assert_eq!(PositionMode::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(PositionMode::from_name("fixed"), None);
+    }
+
+    #[test]
+    fn advances_by_speed() {
+        let mut playhead = Playhead::new(0.5);
+        playhead.set_position(10.0);
+        playhead.advance(4.0, 0.0, 100.0);
+        assert_eq!(playhead.position(), 12.0);
+
+        playhead.set_speed(0.0);
+        playhead.advance(4.0, 0.0, 100.0);
+        assert_eq!(playhead.position(), 12.0);
+    }
+
+    #[test]
+    fn wraps_around_within_the_loop() {
+        let mut playhead = Playhead::default();
+        playhead.set_position(95.0);
+        playhead.advance(10.0, 50.0, 100.0);
+        assert_eq!(playhead.position(), 55.0);
+
+        playhead.set_speed(-1.0);
+        playhead.advance(10.0, 50.0, 100.0);
+        assert_eq!(playhead.position(), 95.0);
+    }
+
+    #[test]
+    fn jumps_into_a_loop_that_moved() {
+        let mut playhead = Playhead::default();
+        playhead.set_position(10.0);
+        playhead.advance(1.0, 50.0, 100.0);
+        assert!((50.0..100.0).contains(&playhead.position()));
+    }
+
+    #[test]
+    fn empty_loops_hold_the_playhead_at_their_start() {
+        let mut playhead = Playhead::default();
+        playhead.set_position(10.0);
+        playhead.advance(1.0, 30.0, 30.0);
+        assert_eq!(playhead.position(), 30.0);
+    }
+}
@@ -0,0 +1,409 @@
+use crate::{
+    adsr::Adsr,
+    channel_policy::ChannelPolicy,
+    envelope_shape::EnvelopeShape,
+    grain_filter::GrainFilterMode,
+    grain_len_mode::GrainLenMode,
+    granular_synthesizer::GranularSynthesizer,
+    granular_synthesizer_action::GranularSynthesizerAction,
+    interpolation::Interpolation,
+    lfo::Lfo,
+    modulation_matrix::{ModulationMatrix, ModulationRouting},
+    note_voice::PlayMode,
+    pan_law::PanLaw,
+    pitch_quantizer::PitchQuantizer,
+    playhead::PositionMode,
+    scheduler::SchedulerMode,
+    smoothed_value::SmoothingType,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Reasons that a preset can fail to load or save
+#[derive(Debug)]
+pub enum PresetError {
+    Json(serde_json::Error),
+    TomlDe(toml::de::Error),
+    TomlSer(toml::ser::Error),
+    /// The preset was saved by a newer version of the synthesizer
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::Json(e) => write!(f, "invalid JSON preset: {}", e),
+            PresetError::TomlDe(e) => write!(f, "invalid TOML preset: {}", e),
+            PresetError::TomlSer(e) => write!(f, "failed to write TOML preset: {}", e),
+            PresetError::UnsupportedVersion(version) => write!(
+                f,
+                "preset version {} is newer than the latest supported version ({})",
+                version,
+                Preset::CURRENT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PresetError {}
+
+/// A snapshot of every user-facing setting of a `GranularSynthesizer`.
+///
+/// Runtime state (the buffer, sample rate, seed, sounding grains and notes) isn't included,
+/// so a preset can be applied to any source audio.
+///
+/// Any setting missing from a serialized preset takes its default value,
+/// which lets presets saved before a setting existed keep loading.
+/// Percentages are stored as plain numbers from 0.0 -> 1.0.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preset {
+    /// The preset format that this preset was saved with
+    pub version: u32,
+
+    pub selection_start: f32,
+    pub selection_end: f32,
+
+    pub grain_len_mode: GrainLenMode,
+    pub grain_len_min: f32,
+    pub grain_len_max: f32,
+    pub grain_len_min_ms: f32,
+    pub grain_len_max_ms: f32,
+
+    pub density: f32,
+    /// How often new grains are scheduled. Stored as a rate rather than a refresh interval,
+    /// since the interval (in whole samples) can't represent every rate exactly.
+    pub grains_per_second: f32,
+    pub scheduler_mode: SchedulerMode,
+    pub scheduler_jitter: f32,
+
+    pub position_mode: PositionMode,
+    pub scan_speed: f32,
+    pub spray_ms: f32,
+
+    pub pitch_semitones: f32,
+    pub pitch_cents: f32,
+    pub pitch_spread: f32,
+    pub interpolation: Interpolation,
+
+    pub reverse_probability: f32,
+    pub envelope_skew: f32,
+    pub amplitude_min: f32,
+    pub amplitude_max: f32,
+    pub normalize: bool,
+
+    pub grain_filter_mode: GrainFilterMode,
+    pub grain_filter_cutoff_min_hz: f32,
+    pub grain_filter_cutoff_max_hz: f32,
+    pub grain_filter_resonance: f32,
+
+    pub smoothing_ms: f32,
+    pub smoothing_type: SmoothingType,
+
+    pub pan_center: f32,
+    pub pan_spread: f32,
+    pub pan_law: PanLaw,
+
+    pub play_mode: PlayMode,
+    pub root_note: u8,
+    pub voice_limit: usize,
+    pub velocity_sensitivity: f32,
+
+    // nested settings are kept last, since TOML requires tables to follow plain values
+    pub envelope_shape: EnvelopeShape,
+    pub channel_policy: ChannelPolicy,
+    pub pitch_quantizer: Option<PitchQuantizer>,
+    pub adsr: Adsr,
+    pub lfos: Vec<Lfo>,
+    // an empty list would be written as a plain value after the `lfos` tables, which TOML doesn't allow
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub modulation_routings: Vec<ModulationRouting>,
+}
+
+impl Preset {
+    /// Bumped whenever the meaning of an existing setting changes.
+    /// Adding a new setting doesn't require a new version, since missing settings use their defaults.
+    pub const CURRENT_VERSION: u32 = 1;
+
+    pub fn to_json(&self) -> Result<String, PresetError> {
+        serde_json::to_string_pretty(self).map_err(PresetError::Json)
+    }
+
+    /// Like `to_json`, but only includes the settings that differ from their defaults
+    /// (along with the version), for when size matters more than readability (e.g. in URLs)
+    pub fn to_compact_json(&self) -> Result<String, PresetError> {
+        let mut value = serde_json::to_value(self).map_err(PresetError::Json)?;
+        let default_value = serde_json::to_value(Self::default()).map_err(PresetError::Json)?;
+
+        if let (Some(settings), Some(default_settings)) =
+            (value.as_object_mut(), default_value.as_object())
+        {
+            settings.retain(|name, setting| {
+                name == "version" || default_settings.get(name) != Some(setting)
+            });
+        }
+
+        shorten_floats(&mut value);
+        serde_json::to_string(&value).map_err(PresetError::Json)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, PresetError> {
+        serde_json::from_str::<Self>(json)
+            .map_err(PresetError::Json)?
+            .check_version()
+    }
+
+    pub fn to_toml(&self) -> Result<String, PresetError> {
+        toml::to_string_pretty(self).map_err(PresetError::TomlSer)
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, PresetError> {
+        toml::from_str::<Self>(toml)
+            .map_err(PresetError::TomlDe)?
+            .check_version()
+    }
+
+    /// Only presets from a newer version are rejected. Older presets are loaded as they are,
+    /// since there is no migration between versions (yet).
+    fn check_version(self) -> Result<Self, PresetError> {
+        if self.version > Self::CURRENT_VERSION {
+            return Err(PresetError::UnsupportedVersion(self.version));
+        }
+
+        Ok(self)
+    }
+}
+
+/// Every float in a preset is an `f32`, which gets widened to an `f64` (e.g. 0.4 -> 0.4000000059604645)
+/// when converted to a `serde_json::Value`. This rewrites each one as the shortest `f64` that
+/// converts back to the same `f32`.
+fn shorten_floats(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Number(number) if number.is_f64() => {
+            let shortened = number
+                .as_f64()
+                .and_then(|float| (float as f32).to_string().parse::<f64>().ok())
+                .and_then(serde_json::Number::from_f64);
+            if let Some(shortened) = shortened {
+                *number = shortened;
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(shorten_floats),
+        serde_json::Value::Object(values) => values.values_mut().for_each(shorten_floats),
+        _ => {}
+    }
+}
+
+impl Default for Preset {
+    /// The settings of a newly created `GranularSynthesizer`
+    fn default() -> Self {
+        Self {
+            version: Self::CURRENT_VERSION,
+            selection_start: 0.0,
+            selection_end: 1.0,
+            grain_len_mode: GrainLenMode::default(),
+            grain_len_min: GranularSynthesizer::GRAIN_LEN_MIN_MIN,
+            grain_len_max: GranularSynthesizer::GRAIN_LEN_MAX_MIN,
+            grain_len_min_ms: GranularSynthesizer::DEFAULT_GRAIN_LEN_MIN_MS,
+            grain_len_max_ms: GranularSynthesizer::DEFAULT_GRAIN_LEN_MAX_MS,
+            density: GranularSynthesizer::DEFAULT_DENSITY,
+            grains_per_second: GranularSynthesizer::DEFAULT_GRAINS_PER_SECOND,
+            scheduler_mode: SchedulerMode::default(),
+            scheduler_jitter: GranularSynthesizer::DEFAULT_SCHEDULER_JITTER,
+            position_mode: PositionMode::default(),
+            scan_speed: GranularSynthesizer::DEFAULT_SCAN_SPEED,
+            spray_ms: GranularSynthesizer::DEFAULT_SPRAY_MS,
+            pitch_semitones: 0.0,
+            pitch_cents: 0.0,
+            pitch_spread: 0.0,
+            interpolation: Interpolation::default(),
+            reverse_probability: GranularSynthesizer::DEFAULT_REVERSE_PROBABILITY,
+            envelope_skew: GranularSynthesizer::DEFAULT_ENVELOPE_SKEW,
+            amplitude_min: GranularSynthesizer::DEFAULT_AMPLITUDE_MIN,
+            amplitude_max: GranularSynthesizer::DEFAULT_AMPLITUDE_MAX,
+            normalize: false,
+            grain_filter_mode: GrainFilterMode::default(),
+            grain_filter_cutoff_min_hz: GranularSynthesizer::DEFAULT_GRAIN_FILTER_CUTOFF_MIN_HZ,
+            grain_filter_cutoff_max_hz: GranularSynthesizer::DEFAULT_GRAIN_FILTER_CUTOFF_MAX_HZ,
+            grain_filter_resonance: GranularSynthesizer::DEFAULT_GRAIN_FILTER_RESONANCE,
+            smoothing_ms: GranularSynthesizer::DEFAULT_SMOOTHING_MS,
+            smoothing_type: SmoothingType::default(),
+            pan_center: GranularSynthesizer::DEFAULT_PAN_CENTER,
+            pan_spread: GranularSynthesizer::DEFAULT_PAN_SPREAD,
+            pan_law: PanLaw::default(),
+            play_mode: PlayMode::default(),
+            root_note: GranularSynthesizer::DEFAULT_ROOT_NOTE,
+            voice_limit: GranularSynthesizer::DEFAULT_VOICE_LIMIT,
+            velocity_sensitivity: GranularSynthesizer::DEFAULT_VELOCITY_SENSITIVITY,
+            envelope_shape: EnvelopeShape::default(),
+            channel_policy: ChannelPolicy::default(),
+            pitch_quantizer: None,
+            adsr: Adsr::default(),
+            lfos: vec![Lfo::default(); ModulationMatrix::NUM_LFOS],
+            modulation_routings: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lfo::LfoShape, modulation_matrix::ModulationTarget, pitch_quantizer::Scale};
+
+    /// A preset with most settings changed from their defaults
+    fn custom_preset() -> Preset {
+        let mut synth = GranularSynthesizer::new_with_seed(0);
+        synth
+            .set_grain_len_mode(GrainLenMode::Absolute)
+            .set_grain_len_max(0.6)
+            .set_grain_len_min(0.2)
+            .set_grain_len_max_ms(300.0)
+            .set_grain_len_min_ms(40.0)
+            .set_density(0.5)
+            .set_scheduler_mode(SchedulerMode::Asynchronous)
+            .set_scheduler_jitter(0.25)
+            .set_spray_ms(100.0)
+            .set_pitch_semitones(7.0)
+            .set_pitch_quantizer(Some(PitchQuantizer::from_scale(Scale::Minor)))
+            .set_reverse_probability(0.5)
+            .set_amplitude_max(0.9)
+            .set_amplitude_min(0.4)
+            .set_normalize(true)
+            .set_grain_filter_mode(GrainFilterMode::BandPass)
+            .set_grain_filter_cutoff_max_hz(2000.0)
+            .set_grain_filter_cutoff_min_hz(500.0)
+            .set_grain_filter_resonance(0.7)
+            .set_pan_spread(0.5)
+            .set_pan_law(PanLaw::Linear)
+            .set_adsr(Adsr::new(10.0, 200.0, 0.5, 400.0))
+            .set_lfo(1, Lfo::new(LfoShape::Square, 2.0, 0.5, 0.25))
+            .add_modulation_routing(ModulationRouting::new(1, ModulationTarget::Density, 0.5));
+
+        synth.preset()
+    }
+
+    #[test]
+    fn default_matches_a_new_synthesizer() {
+        assert_eq!(
+            Preset::default(),
+            GranularSynthesizer::new_with_seed(0).preset()
+        );
+    }
+
+    #[test]
+    fn round_trips_through_json_and_toml() {
+        for preset in [Preset::default(), custom_preset()] {
+            assert_eq!(
+                Preset::from_json(&preset.to_json().unwrap()).unwrap(),
+                preset
+            );
+            assert_eq!(
+                Preset::from_toml(&preset.to_toml().unwrap()).unwrap(),
+                preset
+            );
+        }
+    }
+
+    #[test]
+    fn compact_json_only_includes_changed_settings() {
+        let default_json = Preset::default().to_compact_json().unwrap();
+        assert_eq!(
+            default_json,
+            format!(r#"{{"version":{}}}"#, Preset::CURRENT_VERSION)
+        );
+
+        let preset = Preset {
+            density: 0.4,
+            ..Preset::default()
+        };
+        let json = preset.to_compact_json().unwrap();
+        // floats are written as the shortest value that reads back as the same `f32`
+        assert!(json.contains(r#""density":0.4"#), "{}", json);
+        assert_eq!(Preset::from_json(&json).unwrap(), preset);
+    }
+
+    #[test]
+    fn compact_json_round_trips() {
+        let preset = custom_preset();
+        let json = preset.to_compact_json().unwrap();
+        assert!(json.len() < preset.to_json().unwrap().len());
+        assert_eq!(Preset::from_json(&json).unwrap(), preset);
+    }
+
+    #[test]
+    fn round_trips_every_envelope_shape() {
+        for name in EnvelopeShape::NAMES {
+            let preset = Preset {
+                envelope_shape: EnvelopeShape::from_name(name).unwrap(),
+                ..custom_preset()
+            };
+            assert_eq!(
+                Preset::from_json(&preset.to_json().unwrap()).unwrap(),
+                preset
+            );
+            assert_eq!(
+                Preset::from_toml(&preset.to_toml().unwrap()).unwrap(),
+                preset
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips_every_channel_policy() {
+        for channel_policy in [
+            ChannelPolicy::Fixed(3),
+            ChannelPolicy::Random,
+            ChannelPolicy::FollowPan,
+        ] {
+            let preset = Preset {
+                channel_policy,
+                ..custom_preset()
+            };
+            assert_eq!(
+                Preset::from_json(&preset.to_json().unwrap()).unwrap(),
+                preset
+            );
+            assert_eq!(
+                Preset::from_toml(&preset.to_toml().unwrap()).unwrap(),
+                preset
+            );
+        }
+    }
+
+    #[test]
+    fn missing_settings_use_their_defaults() {
+        let preset = Preset::from_toml("density = 0.25").unwrap();
+        assert_eq!(preset.density, 0.25);
+        assert_eq!(
+            preset,
+            Preset {
+                density: 0.25,
+                ..Preset::default()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let toml = format!("version = {}", Preset::CURRENT_VERSION + 1);
+        assert!(matches!(
+            Preset::from_toml(&toml),
+            Err(PresetError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn applying_a_preset_restores_its_settings() {
+        let preset = custom_preset();
+        let mut synth = GranularSynthesizer::new_with_seed(1);
+        // bounds that would constrain the preset's, if they were set one at a time
+        synth
+            .set_grain_len_min(0.8)
+            .set_grain_len_min_ms(1000.0)
+            .set_amplitude_min(1.0)
+            .set_grain_filter_cutoff_min_hz(10000.0);
+
+        synth.apply_preset(&preset);
+        assert_eq!(synth.preset(), preset);
+    }
+}
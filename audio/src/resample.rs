@@ -0,0 +1,222 @@
+use std::f64::consts::PI;
+
+/// Strategy used to convert audio from one sample rate to another
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ResampleQuality {
+    /// Straight line between the two closest input samples.
+    /// Very cheap, but dulls high frequencies and lets aliasing through when downsampling.
+    Fast,
+    /// Band-limited, Blackman-windowed sinc with `SINC_ZERO_CROSSINGS` zero crossings on either side.
+    ///
+    /// When downsampling, the kernel's cutoff is lowered to the new Nyquist frequency,
+    /// so that content that can't be represented at the new sample rate is filtered out.
+    #[default]
+    High,
+}
+
+impl ResampleQuality {
+    pub const NAMES: [&'static str; 2] = ["fast", "high"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fast" => Some(ResampleQuality::Fast),
+            "high" => Some(ResampleQuality::High),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResampleQuality::Fast => "fast",
+            ResampleQuality::High => "high",
+        }
+    }
+}
+
+/// Number of zero crossings of the sinc kernel on either side of its center (at full bandwidth)
+pub const SINC_ZERO_CROSSINGS: usize = 16;
+
+/// Number of kernel table entries per zero crossing
+const SINC_TABLE_RESOLUTION: usize = 512;
+
+/// Portion of the (lower) Nyquist frequency that is kept when resampling with `ResampleQuality::High`.
+/// Leaves room for the kernel's transition band, so that it doesn't alias.
+const SINC_CUTOFF: f64 = 0.95;
+
+/// Converts a single channel of samples from `from_sample_rate` to `to_sample_rate`.
+///
+/// Samples outside of the input are treated as silence (for `ResampleQuality::High`)
+/// or as the closest sample (for `ResampleQuality::Fast`).
+pub fn resample(
+    samples: &[f32],
+    from_sample_rate: u32,
+    to_sample_rate: u32,
+    quality: ResampleQuality,
+) -> Vec<f32> {
+    if from_sample_rate == to_sample_rate
+        || from_sample_rate == 0
+        || to_sample_rate == 0
+        || samples.is_empty()
+    {
+        return samples.to_vec();
+    }
+
+    let num_output_samples =
+        (samples.len() as u64 * to_sample_rate as u64 / from_sample_rate as u64) as usize;
+    // how far to move through the input for every output sample
+    let step = from_sample_rate as f64 / to_sample_rate as f64;
+
+    match quality {
+        ResampleQuality::Fast => (0..num_output_samples)
+            .map(|i| {
+                let position = i as f64 * step;
+                let index = position.floor() as usize;
+                let fraction = (position - index as f64) as f32;
+                let x0 = samples[index.min(samples.len() - 1)];
+                let x1 = samples[(index + 1).min(samples.len() - 1)];
+                x0 + (x1 - x0) * fraction
+            })
+            .collect(),
+        ResampleQuality::High => {
+            let kernel = SincKernel::new();
+            // lower the cutoff to the output's Nyquist frequency when downsampling
+            let cutoff = SINC_CUTOFF * (1.0 / step).min(1.0);
+            // the kernel widens (in input samples) as its cutoff lowers
+            let half_width = (SINC_ZERO_CROSSINGS as f64 / cutoff).ceil() as isize;
+
+            (0..num_output_samples)
+                .map(|i| {
+                    let position = i as f64 * step;
+                    let index = position.floor() as isize;
+                    let first = (index - half_width + 1).max(0);
+                    let last = (index + half_width).min(samples.len() as isize - 1);
+
+                    let mut sum = 0.0;
+                    for j in first..=last {
+                        let distance = (position - j as f64).abs() * cutoff;
+                        sum += samples[j as usize] as f64 * kernel.lookup(distance);
+                    }
+                    (sum * cutoff) as f32
+                })
+                .collect()
+        }
+    }
+}
+
+/// Blackman-windowed sinc, sampled from the center of the kernel out to its last zero crossing
+struct SincKernel {
+    table: Vec<f64>,
+}
+
+impl SincKernel {
+    fn new() -> Self {
+        // one extra entry so that linear lookups at the very edge stay in bounds
+        let table_len = SINC_ZERO_CROSSINGS * SINC_TABLE_RESOLUTION + 2;
+        let zero_crossings = SINC_ZERO_CROSSINGS as f64;
+
+        let table = (0..table_len)
+            .map(|i| {
+                let x = (i as f64 / SINC_TABLE_RESOLUTION as f64).min(zero_crossings);
+                let sinc = if x == 0.0 {
+                    1.0
+                } else {
+                    (PI * x).sin() / (PI * x)
+                };
+                let phase = PI * x / zero_crossings;
+                let window = (0.42 + 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos()).max(0.0);
+                sinc * window
+            })
+            .collect();
+
+        Self { table }
+    }
+
+    /// Linearly interpolates the kernel at a distance (in zero crossings) from its center
+    fn lookup(&self, distance: f64) -> f64 {
+        if distance >= SINC_ZERO_CROSSINGS as f64 {
+            return 0.0;
+        }
+
+        let position = distance * SINC_TABLE_RESOLUTION as f64;
+        let index = position as usize;
+        let fraction = position - index as f64;
+        let a = self.table[index];
+        let b = self.table[index + 1];
+        a + (b - a) * fraction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f64, sample_rate: u32, num_samples: usize) -> Vec<f32> {
+        (0..num_samples)
+            .map(|i| (2.0 * PI * frequency * i as f64 / sample_rate as f64).sin() as f32)
+            .collect()
+    }
+
+    /// The largest difference between two signals, ignoring the edges, where the input runs out
+    fn max_error_away_from_edges(actual: &[f32], expected: &[f32]) -> f32 {
+        let edge = actual.len() / 10;
+        actual[edge..actual.len() - edge]
+            .iter()
+            .zip(&expected[edge..])
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn quality_names_round_trip() {
+        for name in ResampleQuality::NAMES {
+            assert_eq!(ResampleQuality::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(ResampleQuality::from_name("best"), None);
+    }
+
+    #[test]
+    fn output_length_follows_the_sample_rate_ratio() {
+        let samples = vec![0.0; 44100];
+        for quality in [ResampleQuality::Fast, ResampleQuality::High] {
+            assert_eq!(resample(&samples, 44100, 48000, quality).len(), 48000);
+            assert_eq!(resample(&samples, 44100, 22050, quality).len(), 22050);
+            assert_eq!(resample(&samples[..100], 44100, 48000, quality).len(), 108);
+        }
+    }
+
+    #[test]
+    fn matching_or_invalid_rates_leave_samples_unchanged() {
+        let samples = sine(440.0, 44100, 100);
+        for quality in [ResampleQuality::Fast, ResampleQuality::High] {
+            assert_eq!(resample(&samples, 44100, 44100, quality), samples);
+            assert_eq!(resample(&samples, 0, 44100, quality), samples);
+            assert_eq!(resample(&samples, 44100, 0, quality), samples);
+        }
+        assert!(resample(&[], 44100, 48000, ResampleQuality::High).is_empty());
+    }
+
+    #[test]
+    fn preserves_pitch() {
+        let input = sine(1000.0, 44100, 4410);
+        let expected = sine(1000.0, 48000, 4800);
+
+        let high = resample(&input, 44100, 48000, ResampleQuality::High);
+        assert!(max_error_away_from_edges(&high, &expected) < 0.01);
+
+        let fast = resample(&input, 44100, 48000, ResampleQuality::Fast);
+        assert!(max_error_away_from_edges(&fast, &expected) < 0.05);
+
+        let down = resample(&input, 44100, 22050, ResampleQuality::High);
+        let expected = sine(1000.0, 22050, 2205);
+        assert!(max_error_away_from_edges(&down, &expected) < 0.01);
+    }
+
+    #[test]
+    fn downsampling_filters_out_frequencies_above_the_new_nyquist() {
+        // 15 kHz can't be represented at 22.05 kHz, and would otherwise alias to 7.05 kHz
+        let input = sine(15000.0, 44100, 4410);
+        let output = resample(&input, 44100, 22050, ResampleQuality::High);
+        let silence = vec![0.0; output.len()];
+        assert!(max_error_away_from_edges(&output, &silence) < 0.01);
+    }
+}
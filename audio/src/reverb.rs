@@ -0,0 +1,485 @@
+use crate::effect::{Effect, EffectParameter};
+
+/// Delay lengths (in samples at `TUNING_SAMPLE_RATE`) of the left channel's comb filters
+const COMB_TUNINGS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+
+/// Delay lengths (in samples at `TUNING_SAMPLE_RATE`) of the left channel's all-pass filters
+const ALL_PASS_TUNINGS: [usize; 4] = [556, 441, 341, 225];
+
+/// How much longer (in samples at `TUNING_SAMPLE_RATE`) the right channel's delays are than the left's,
+/// which decorrelates the two channels
+const STEREO_SPREAD: usize = 23;
+
+/// The sample rate that the tunings above were chosen for
+const TUNING_SAMPLE_RATE: f32 = 44100.0;
+
+/// Keeps the sum of all eight comb filters at a reasonable level
+const INPUT_GAIN: f32 = 0.015;
+
+const ALL_PASS_FEEDBACK: f32 = 0.5;
+
+/// Maps a room size of 0.0 -> 1.0 to a comb filter feedback of 0.7 -> 0.98
+const ROOM_SCALE: f32 = 0.28;
+
+const ROOM_OFFSET: f32 = 0.7;
+
+/// Maps a damping of 0.0 -> 1.0 to a comb filter low-pass coefficient of 0.0 -> 0.4
+const DAMPING_SCALE: f32 = 0.4;
+
+/// Feedback comb filter with a one-pole low-pass filter in its feedback path
+#[derive(Clone, Debug)]
+struct Comb {
+    buffer: Vec<f32>,
+    index: usize,
+    filter_store: f32,
+}
+
+impl Comb {
+    fn new(len: usize) -> Self {
+        Self {
+            buffer: vec![0.0; len.max(1)],
+            index: 0,
+            filter_store: 0.0,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.filter_store = output * (1.0 - damping) + self.filter_store * damping;
+        // flush values that would otherwise decay into (slow) subnormal numbers
+        if self.filter_store.abs() < f32::MIN_POSITIVE {
+            self.filter_store = 0.0;
+        }
+        self.buffer[self.index] = input + self.filter_store * feedback;
+
+        self.index += 1;
+        if self.index == self.buffer.len() {
+            self.index = 0;
+        }
+
+        output
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.filter_store = 0.0;
+    }
+}
+
+/// Schroeder all-pass filter, which diffuses echoes without coloring the sound
+#[derive(Clone, Debug)]
+struct AllPass {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl AllPass {
+    fn new(len: usize) -> Self {
+        Self {
+            buffer: vec![0.0; len.max(1)],
+            index: 0,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.buffer[self.index];
+        self.buffer[self.index] = input + delayed * ALL_PASS_FEEDBACK;
+
+        self.index += 1;
+        if self.index == self.buffer.len() {
+            self.index = 0;
+        }
+
+        delayed - input
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0.0);
+    }
+}
+
+/// The comb and all-pass filters for one output channel
+#[derive(Clone, Debug)]
+struct ReverbChannel {
+    combs: Vec<Comb>,
+    all_passes: Vec<AllPass>,
+}
+
+impl ReverbChannel {
+    fn new(sample_rate: u32, spread: usize) -> Self {
+        let scale = |tuning: usize| {
+            ((tuning + spread) as f32 * sample_rate as f32 / TUNING_SAMPLE_RATE).round() as usize
+        };
+
+        Self {
+            combs: COMB_TUNINGS.iter().map(|&t| Comb::new(scale(t))).collect(),
+            all_passes: ALL_PASS_TUNINGS
+                .iter()
+                .map(|&t| AllPass::new(scale(t)))
+                .collect(),
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        // combs run in parallel, then all-passes run in series
+        let mut output = 0.0;
+        for comb in &mut self.combs {
+            output += comb.process(input, feedback, damping);
+        }
+        for all_pass in &mut self.all_passes {
+            output = all_pass.process(output);
+        }
+
+        output
+    }
+
+    fn reset(&mut self) {
+        self.combs.iter_mut().for_each(Comb::reset);
+        self.all_passes.iter_mut().for_each(AllPass::reset);
+    }
+}
+
+/// Freeverb-style stereo reverb: eight parallel low-passed comb filters followed by four series
+/// all-pass filters per channel, with slightly different delay lengths in each channel.
+///
+/// The output is only the reverb itself (fully wet): its level against the dry signal
+/// is set by the mix of the `EffectChain` slot it's in.
+///
+/// All delay lines are allocated when the sample rate is set, so processing never allocates.
+/// The first two channels of a block are reverberated (a mono block is reverberated in mono);
+/// any further channels are left unchanged.
+#[derive(Clone, Debug)]
+pub struct Reverb {
+    room_size: f32,
+    damping: f32,
+    pre_delay_ms: f32,
+    width: f32,
+
+    sample_rate: u32,
+
+    left: ReverbChannel,
+    right: ReverbChannel,
+
+    /// Holds the (mono) input for up to `PRE_DELAY_MS.max` before it reaches the filters
+    pre_delay: Vec<f32>,
+    pre_delay_index: usize,
+}
+
+impl Reverb {
+    pub const ROOM_SIZE: EffectParameter = EffectParameter {
+        name: "room size",
+        min: 0.0,
+        max: 1.0,
+        default: 0.5,
+        logarithmic: false,
+        options: &[],
+    };
+
+    /// How quickly high frequencies fade relative to low frequencies
+    pub const DAMPING: EffectParameter = EffectParameter {
+        name: "damping",
+        min: 0.0,
+        max: 1.0,
+        default: 0.5,
+        logarithmic: false,
+        options: &[],
+    };
+
+    /// How long (in milliseconds) before the reverb starts
+    pub const PRE_DELAY_MS: EffectParameter = EffectParameter {
+        name: "pre-delay (ms)",
+        min: 0.0,
+        max: 250.0,
+        default: 10.0,
+        logarithmic: false,
+        options: &[],
+    };
+
+    /// How far apart the two channels of the reverb are (0.0 is mono)
+    pub const WIDTH: EffectParameter = EffectParameter {
+        name: "width",
+        min: 0.0,
+        max: 1.0,
+        default: 1.0,
+        logarithmic: false,
+        options: &[],
+    };
+
+    /// How much of the reverb a new effect chain slot mixes in
+    pub const DEFAULT_MIX: f32 = 0.33;
+
+    const PARAMETERS: [EffectParameter; 4] = [
+        Self::ROOM_SIZE,
+        Self::DAMPING,
+        Self::PRE_DELAY_MS,
+        Self::WIDTH,
+    ];
+
+    pub fn new(sample_rate: u32) -> Self {
+        let mut reverb = Self {
+            room_size: Self::ROOM_SIZE.default,
+            damping: Self::DAMPING.default,
+            pre_delay_ms: Self::PRE_DELAY_MS.default,
+            width: Self::WIDTH.default,
+            sample_rate,
+            left: ReverbChannel::new(sample_rate, 0),
+            right: ReverbChannel::new(sample_rate, STEREO_SPREAD),
+            pre_delay: Vec::new(),
+            pre_delay_index: 0,
+        };
+        reverb.set_sample_rate(sample_rate);
+        reverb
+    }
+
+    pub fn room_size(&self) -> f32 {
+        self.room_size
+    }
+
+    pub fn set_room_size(&mut self, room_size: f32) -> &mut Self {
+        self.room_size = Self::ROOM_SIZE.sanitize(room_size);
+
+        self
+    }
+
+    pub fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    pub fn set_damping(&mut self, damping: f32) -> &mut Self {
+        self.damping = Self::DAMPING.sanitize(damping);
+
+        self
+    }
+
+    pub fn pre_delay_ms(&self) -> f32 {
+        self.pre_delay_ms
+    }
+
+    pub fn set_pre_delay_ms(&mut self, pre_delay_ms: f32) -> &mut Self {
+        self.pre_delay_ms = Self::PRE_DELAY_MS.sanitize(pre_delay_ms);
+
+        self
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn set_width(&mut self, width: f32) -> &mut Self {
+        self.width = Self::WIDTH.sanitize(width);
+
+        self
+    }
+
+    /// The pre-delay, in whole samples (always less than the pre-delay line's length)
+    fn pre_delay_samples(&self) -> usize {
+        let samples = (self.pre_delay_ms * self.sample_rate as f32 / 1000.0) as usize;
+        samples.min(self.pre_delay.len() - 1)
+    }
+
+    /// Feeds one (mono) input sample through the pre-delay, returning the delayed sample
+    #[inline]
+    fn pre_delay(&mut self, input: f32, delay_samples: usize) -> f32 {
+        let len = self.pre_delay.len();
+        self.pre_delay[self.pre_delay_index] = input;
+        let delayed = self.pre_delay[(self.pre_delay_index + len - delay_samples) % len];
+        self.pre_delay_index = (self.pre_delay_index + 1) % len;
+
+        delayed
+    }
+}
+
+impl Effect for Reverb {
+    fn name(&self) -> &'static str {
+        "reverb"
+    }
+
+    fn parameters(&self) -> &'static [EffectParameter] {
+        &Self::PARAMETERS
+    }
+
+    fn parameter(&self, index: usize) -> Option<f32> {
+        match index {
+            0 => Some(self.room_size),
+            1 => Some(self.damping),
+            2 => Some(self.pre_delay_ms),
+            3 => Some(self.width),
+            _ => None,
+        }
+    }
+
+    fn set_parameter(&mut self, index: usize, value: f32) {
+        match index {
+            0 => self.set_room_size(value),
+            1 => self.set_damping(value),
+            2 => self.set_pre_delay_ms(value),
+            3 => self.set_width(value),
+            _ => return,
+        };
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        let sample_rate = sample_rate.max(1);
+        self.sample_rate = sample_rate;
+        self.left = ReverbChannel::new(sample_rate, 0);
+        self.right = ReverbChannel::new(sample_rate, STEREO_SPREAD);
+
+        let max_pre_delay_samples =
+            (Self::PRE_DELAY_MS.max * sample_rate as f32 / 1000.0).ceil() as usize;
+        self.pre_delay = vec![0.0; max_pre_delay_samples + 1];
+        self.pre_delay_index = 0;
+    }
+
+    fn default_mix(&self) -> f32 {
+        Self::DEFAULT_MIX
+    }
+
+    fn reset(&mut self) {
+        self.left.reset();
+        self.right.reset();
+        self.pre_delay.fill(0.0);
+    }
+
+    fn process_block(&mut self, block: &mut [f32], num_channels: usize) {
+        if num_channels == 0 {
+            return;
+        }
+
+        let feedback = self.room_size * ROOM_SCALE + ROOM_OFFSET;
+        let damping = self.damping * DAMPING_SCALE;
+        // the width crossfeeds each channel's reverb into the other
+        let wet_same = self.width / 2.0 + 0.5;
+        let wet_other = (1.0 - self.width) / 2.0;
+        let delay_samples = self.pre_delay_samples();
+
+        for frame in block.chunks_mut(num_channels) {
+            // both channels of a stereo block feed the (mono) input, while a mono block's single channel
+            // is only counted once, so that a mono signal doesn't reverberate louder than the same signal in stereo
+            let input = match frame {
+                [left, right, ..] => *left + *right,
+                [mono] => *mono,
+                [] => continue,
+            };
+
+            let input = self.pre_delay(input * INPUT_GAIN, delay_samples);
+            let reverb_left = self.left.process(input, feedback, damping);
+            let reverb_right = self.right.process(input, feedback, damping);
+
+            let output_left = reverb_left * wet_same + reverb_right * wet_other;
+            let output_right = reverb_right * wet_same + reverb_left * wet_other;
+            match frame {
+                [left, right, ..] => {
+                    *left = output_left;
+                    *right = output_right;
+                }
+                [mono] => {
+                    *mono = (output_left + output_right) / 2.0;
+                }
+                [] => {}
+            }
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Effect> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 44100;
+
+    fn impulse(num_frames: usize, num_channels: usize) -> Vec<f32> {
+        let mut block = vec![0.0; num_frames * num_channels];
+        block[..num_channels].fill(1.0);
+        block
+    }
+
+    #[test]
+    fn output_is_fully_wet() {
+        let mut reverb = Reverb::new(SAMPLE_RATE);
+        reverb.set_pre_delay_ms(0.0);
+        let mut block = impulse(SAMPLE_RATE as usize, 2);
+        reverb.process_block(&mut block, 2);
+
+        // nothing comes out until the input has made it through the shortest comb filter
+        let shortest_comb = COMB_TUNINGS.iter().min().copied().unwrap();
+        assert!(block[..shortest_comb * 2]
+            .iter()
+            .all(|&sample| sample == 0.0));
+        assert!(block.iter().any(|&sample| sample != 0.0));
+    }
+
+    #[test]
+    fn pre_delay_postpones_the_reverb() {
+        let first_output = |pre_delay_ms: f32| {
+            let mut reverb = Reverb::new(SAMPLE_RATE);
+            reverb.set_pre_delay_ms(pre_delay_ms);
+            let mut block = impulse(SAMPLE_RATE as usize, 2);
+            reverb.process_block(&mut block, 2);
+            block.iter().position(|&sample| sample != 0.0).unwrap() / 2
+        };
+
+        let offset = first_output(100.0) - first_output(0.0);
+        assert_eq!(offset, (100.0 * SAMPLE_RATE as f32 / 1000.0) as usize);
+    }
+
+    #[test]
+    fn mono_input_is_not_louder_than_the_same_input_in_one_stereo_channel() {
+        let mut mono_reverb = Reverb::new(SAMPLE_RATE);
+        let mut mono = impulse(SAMPLE_RATE as usize, 1);
+        mono_reverb.process_block(&mut mono, 1);
+
+        let mut stereo_reverb = Reverb::new(SAMPLE_RATE);
+        let mut stereo = vec![0.0; SAMPLE_RATE as usize * 2];
+        stereo[0] = 1.0;
+        stereo_reverb.process_block(&mut stereo, 2);
+
+        for (mono, stereo) in mono.iter().zip(stereo.chunks(2)) {
+            assert!((mono - (stereo[0] + stereo[1]) / 2.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn zero_width_is_mono() {
+        let mut reverb = Reverb::new(SAMPLE_RATE);
+        reverb.set_width(0.0);
+        let mut block = impulse(SAMPLE_RATE as usize, 2);
+        reverb.process_block(&mut block, 2);
+        assert!(block
+            .chunks(2)
+            .all(|frame| (frame[0] - frame[1]).abs() < 1e-6));
+    }
+
+    #[test]
+    fn tail_decays_and_reset_clears_it() {
+        let mut reverb = Reverb::new(SAMPLE_RATE);
+        let mut block = impulse(SAMPLE_RATE as usize * 4, 2);
+        reverb.process_block(&mut block, 2);
+        let peak = |samples: &[f32]| samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+        let last_second = &block[block.len() - SAMPLE_RATE as usize * 2..];
+        assert!(peak(last_second) < peak(&block) * 0.01);
+
+        reverb.reset();
+        let mut silence = vec![0.0; 4096];
+        reverb.process_block(&mut silence, 2);
+        assert!(silence.iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn parameters_are_clamped_to_their_ranges() {
+        let mut reverb = Reverb::new(SAMPLE_RATE);
+        for (index, parameter) in reverb.parameters().iter().enumerate() {
+            reverb.set_parameter(index, parameter.max + 1000.0);
+            assert_eq!(reverb.parameter(index), Some(parameter.max));
+            reverb.set_parameter(index, parameter.min - 1000.0);
+            assert_eq!(reverb.parameter(index), Some(parameter.min));
+        }
+        assert_eq!(reverb.parameter(reverb.parameters().len()), None);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::percentage::Percentage;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Determines how the time between grain onsets is chosen
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulerMode {
+    /// Grains start at regular intervals, optionally offset by a random jitter
+    #[default]
+    Synchronous,
+    /// Grains start at random, independent moments (a Poisson process),
+    /// averaging out to the configured number of grains per second
+    Asynchronous,
+}
+
+impl SchedulerMode {
+    pub const NAMES: [&'static str; 2] = ["synchronous", "asynchronous"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "synchronous" => Some(SchedulerMode::Synchronous),
+            "asynchronous" => Some(SchedulerMode::Asynchronous),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SchedulerMode::Synchronous => "synchronous",
+            SchedulerMode::Asynchronous => "asynchronous",
+        }
+    }
+}
+
+/// Decides on which frames new grains should start.
+///
+/// The onset rate is expressed in grains per second,
+/// so the scheduler behaves the same regardless of the sample rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrainScheduler {
+    /// The average number of grain onsets per second
+    grains_per_second: f32,
+
+    mode: SchedulerMode,
+
+    /// How far (as a percentage of the interval) each synchronous onset
+    /// can randomly deviate from the regular interval
+    jitter: Percentage,
+
+    /// The number of frames remaining until the next grain onset
+    frames_until_onset: f64,
+}
+
+impl GrainScheduler {
+    pub fn new(grains_per_second: f32, mode: SchedulerMode, jitter: impl Into<Percentage>) -> Self {
+        Self {
+            grains_per_second,
+            mode,
+            jitter: jitter.into(),
+            frames_until_onset: 0.0,
+        }
+    }
+
+    pub fn grains_per_second(&self) -> f32 {
+        self.grains_per_second
+    }
+
+    /// Takes effect starting from the next onset
+    pub fn set_grains_per_second(&mut self, grains_per_second: f32) -> &mut Self {
+        self.grains_per_second = grains_per_second;
+        self
+    }
+
+    pub fn mode(&self) -> SchedulerMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: SchedulerMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn jitter(&self) -> Percentage {
+        self.jitter
+    }
+
+    pub fn set_jitter(&mut self, jitter: impl Into<Percentage>) -> &mut Self {
+        self.jitter = jitter.into();
+        self
+    }
+
+    /// Schedules the next onset for the very next frame
+    pub fn reset(&mut self) {
+        self.frames_until_onset = 0.0;
+    }
+
+    /// Advances the scheduler by a single frame and returns
+    /// the number of grains that should start on that frame.
+    pub fn tick(&mut self, sample_rate: u32, rng: &mut impl Rng) -> u32 {
+        let mut num_onsets = 0;
+        while self.frames_until_onset <= 0.0 {
+            num_onsets += 1;
+            self.frames_until_onset += self.next_interval(sample_rate, rng);
+        }
+        self.frames_until_onset -= 1.0;
+
+        num_onsets
+    }
+
+    /// The number of frames between one onset and the next
+    fn next_interval(&self, sample_rate: u32, rng: &mut impl Rng) -> f64 {
+        let mean_interval = sample_rate as f64 / self.grains_per_second.max(f32::EPSILON) as f64;
+
+        match self.mode {
+            SchedulerMode::Synchronous => {
+                let jitter = self.jitter.get() as f64;
+                if jitter > 0.0 {
+                    mean_interval * (1.0 + rng.gen_range(-jitter..=jitter))
+                } else {
+                    mean_interval
+                }
+            }
+            SchedulerMode::Asynchronous => {
+                // exponentially distributed intervals produce a Poisson process
+                let random: f64 = rng.gen();
+                -(1.0 - random).ln() * mean_interval
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const SAMPLE_RATE: u32 = 1000;
+
+    /// The frames (out of `num_frames`) on which at least one grain started, and the total number of onsets
+    fn run(scheduler: &mut GrainScheduler, num_frames: usize) -> (Vec<usize>, u32) {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut onset_frames = Vec::new();
+        let mut num_onsets = 0;
+        for frame in 0..num_frames {
+            let onsets = scheduler.tick(SAMPLE_RATE, &mut rng);
+            if onsets > 0 {
+                onset_frames.push(frame);
+            }
+            num_onsets += onsets;
+        }
+        (onset_frames, num_onsets)
+    }
+
+    #[test]
+    fn mode_names_round_trip() {
+        for name in SchedulerMode::NAMES {
+            assert_eq!(SchedulerMode::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(SchedulerMode::from_name("random"), None);
+    }
+
+    #[test]
+    fn synchronous_onsets_are_evenly_spaced() {
+        let mut scheduler = GrainScheduler::new(10.0, SchedulerMode::Synchronous, 0.0);
+        let (onset_frames, num_onsets) = run(&mut scheduler, 350);
+        assert_eq!(onset_frames, [0, 100, 200, 300]);
+        assert_eq!(num_onsets, 4);
+    }
+
+    #[test]
+    fn rates_above_the_sample_rate_start_several_grains_per_frame() {
+        let mut scheduler = GrainScheduler::new(4000.0, SchedulerMode::Synchronous, 0.0);
+        let (onset_frames, num_onsets) = run(&mut scheduler, 10);
+        assert_eq!(onset_frames.len(), 10);
+        // onsets that fall between frames start on the following frame,
+        // so only the very first onset starts on frame 0
+        assert_eq!(num_onsets, 1 + 9 * 4);
+    }
+
+    #[test]
+    fn random_onsets_average_out_to_the_rate() {
+        for mode in [SchedulerMode::Synchronous, SchedulerMode::Asynchronous] {
+            let mut scheduler = GrainScheduler::new(50.0, mode, 0.5);
+            let (_, num_onsets) = run(&mut scheduler, 100 * SAMPLE_RATE as usize);
+            assert!(
+                (4750..=5250).contains(&num_onsets),
+                "{:?} started {} grains",
+                mode,
+                num_onsets
+            );
+        }
+    }
+
+    #[test]
+    fn reset_schedules_an_onset_for_the_next_frame() {
+        let mut scheduler = GrainScheduler::new(1.0, SchedulerMode::Synchronous, 0.0);
+        run(&mut scheduler, 10);
+        assert_eq!(run(&mut scheduler, 10).1, 0);
+
+        scheduler.reset();
+        assert_eq!(run(&mut scheduler, 1).1, 1);
+    }
+}
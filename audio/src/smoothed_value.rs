@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+
+/// Determines the curve that a `SmoothedValue` follows on its way to a new target
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmoothingType {
+    /// Moves toward the target by the same amount every frame,
+    /// arriving exactly after the ramp time
+    #[default]
+    Linear,
+    /// Moves toward the target by a fixed fraction of the remaining distance every frame
+    /// (fast at first, then slowing down), arriving after the ramp time
+    Exponential,
+}
+
+impl SmoothingType {
+    pub const NAMES: [&'static str; 2] = ["linear", "exponential"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "linear" => Some(SmoothingType::Linear),
+            "exponential" => Some(SmoothingType::Exponential),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SmoothingType::Linear => "linear",
+            SmoothingType::Exponential => "exponential",
+        }
+    }
+}
+
+/// A parameter value that ramps toward new values over time (rather than jumping to them),
+/// which prevents zipper noise and clicks when a parameter is changed during playback.
+///
+/// Call `next_value` once per frame to advance the ramp.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmoothedValue {
+    /// The value as of the most recent frame
+    current: f32,
+
+    /// The value that `current` is moving toward
+    target: f32,
+
+    smoothing_type: SmoothingType,
+
+    /// How long (in milliseconds) it takes to reach a new target
+    ramp_ms: f32,
+
+    sample_rate: u32,
+
+    /// How many frames remain until `current` arrives at `target`
+    frames_remaining: u32,
+
+    /// For linear ramps, the amount added every frame.
+    /// For exponential ramps, the fraction of the remaining distance covered every frame.
+    step: f32,
+}
+
+impl SmoothedValue {
+    /// Exponential ramps are considered to have arrived once they are within this fraction
+    /// of the original distance to the target (-60 dB)
+    const EXPONENTIAL_THRESHOLD: f32 = 0.001;
+
+    pub fn new(value: f32, ramp_ms: f32, smoothing_type: SmoothingType, sample_rate: u32) -> Self {
+        Self {
+            current: value,
+            target: value,
+            smoothing_type,
+            ramp_ms: ramp_ms.max(0.0),
+            sample_rate,
+            frames_remaining: 0,
+            step: 0.0,
+        }
+    }
+
+    /// The value as of the most recent frame
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    /// The value that is being ramped toward
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Whether the value is still on its way to the target
+    pub fn is_smoothing(&self) -> bool {
+        self.frames_remaining > 0
+    }
+
+    /// Starts a new ramp from the current value to `target`
+    pub fn set_target(&mut self, target: f32) -> &mut Self {
+        // restarting an identical ramp on every call would prevent it from ever arriving
+        if target == self.target {
+            return self;
+        }
+
+        self.target = target;
+        self.frames_remaining = self.ramp_len_in_frames();
+
+        if self.frames_remaining == 0 {
+            self.current = target;
+            return self;
+        }
+
+        self.step = match self.smoothing_type {
+            SmoothingType::Linear => (target - self.current) / self.frames_remaining as f32,
+            SmoothingType::Exponential => {
+                1.0 - Self::EXPONENTIAL_THRESHOLD.powf(1.0 / self.frames_remaining as f32)
+            }
+        };
+
+        self
+    }
+
+    /// Jumps directly to `value`, without ramping
+    pub fn set_immediate(&mut self, value: f32) -> &mut Self {
+        self.current = value;
+        self.target = value;
+        self.frames_remaining = 0;
+
+        self
+    }
+
+    /// Jumps directly to the end of the current ramp
+    pub fn skip(&mut self) -> &mut Self {
+        self.set_immediate(self.target)
+    }
+
+    pub fn ramp_ms(&self) -> f32 {
+        self.ramp_ms
+    }
+
+    /// Changes the ramp time for all future ramps (the current ramp is finished immediately)
+    pub fn set_ramp_ms(&mut self, ramp_ms: f32) -> &mut Self {
+        self.ramp_ms = ramp_ms.max(0.0);
+
+        self.skip()
+    }
+
+    pub fn smoothing_type(&self) -> SmoothingType {
+        self.smoothing_type
+    }
+
+    /// Changes the curve for all future ramps (the current ramp is finished immediately)
+    pub fn set_smoothing_type(&mut self, smoothing_type: SmoothingType) -> &mut Self {
+        self.smoothing_type = smoothing_type;
+
+        self.skip()
+    }
+
+    /// Changes the sample rate that the ramp time is measured in (the current ramp is finished immediately)
+    pub fn set_sample_rate(&mut self, sample_rate: u32) -> &mut Self {
+        self.sample_rate = sample_rate;
+
+        self.skip()
+    }
+
+    /// Advances the ramp by one frame and returns the new current value
+    pub fn next_value(&mut self) -> f32 {
+        if self.frames_remaining == 0 {
+            return self.current;
+        }
+
+        self.frames_remaining -= 1;
+
+        if self.frames_remaining == 0 {
+            // land exactly on the target, regardless of any accumulated rounding error
+            self.current = self.target;
+        } else {
+            match self.smoothing_type {
+                SmoothingType::Linear => self.current += self.step,
+                SmoothingType::Exponential => {
+                    self.current += (self.target - self.current) * self.step
+                }
+            }
+        }
+
+        self.current
+    }
+
+    fn ramp_len_in_frames(&self) -> u32 {
+        (self.ramp_ms / 1000.0 * self.sample_rate as f32) as u32
+    }
+}
+
+impl Default for SmoothedValue {
+    fn default() -> Self {
+        Self::new(0.0, 0.0, SmoothingType::default(), 44100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// At this sample rate, 1 millisecond is exactly 1 frame
+    const SAMPLE_RATE: u32 = 1000;
+
+    fn run(value: &mut SmoothedValue, num_frames: usize) -> Vec<f32> {
+        (0..num_frames).map(|_| value.next_value()).collect()
+    }
+
+    #[test]
+    fn smoothing_type_names_round_trip() {
+        for name in SmoothingType::NAMES {
+            assert_eq!(SmoothingType::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(SmoothingType::from_name("cubic"), None);
+    }
+
+    #[test]
+    fn linear_ramps_arrive_after_the_ramp_time() {
+        let mut value = SmoothedValue::new(0.0, 4.0, SmoothingType::Linear, SAMPLE_RATE);
+        value.set_target(1.0);
+        assert!(value.is_smoothing());
+        assert_eq!(run(&mut value, 5), [0.25, 0.5, 0.75, 1.0, 1.0]);
+        assert!(!value.is_smoothing());
+    }
+
+    #[test]
+    fn exponential_ramps_slow_down_before_arriving() {
+        let mut value = SmoothedValue::new(0.0, 10.0, SmoothingType::Exponential, SAMPLE_RATE);
+        value.set_target(1.0);
+        let values = run(&mut value, 10);
+
+        let steps: Vec<_> = values.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        assert!(steps[..8].windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(values[0] > 0.1);
+        assert_eq!(values[9], 1.0);
+        assert!(!value.is_smoothing());
+    }
+
+    #[test]
+    fn retargeting_starts_from_the_current_value() {
+        let mut value = SmoothedValue::new(0.0, 4.0, SmoothingType::Linear, SAMPLE_RATE);
+        value.set_target(1.0);
+        run(&mut value, 2);
+
+        value.set_target(0.0);
+        assert_eq!(run(&mut value, 4), [0.375, 0.25, 0.125, 0.0]);
+    }
+
+    #[test]
+    fn setting_the_same_target_does_not_restart_the_ramp() {
+        let mut value = SmoothedValue::new(0.0, 4.0, SmoothingType::Linear, SAMPLE_RATE);
+        value.set_target(1.0);
+        run(&mut value, 2);
+        value.set_target(1.0);
+        assert_eq!(run(&mut value, 2), [0.75, 1.0]);
+    }
+
+    #[test]
+    fn zero_length_ramps_jump_to_the_target() {
+        let mut value = SmoothedValue::new(0.0, 0.0, SmoothingType::Linear, SAMPLE_RATE);
+        value.set_target(0.5);
+        assert_eq!(value.current(), 0.5);
+        assert!(!value.is_smoothing());
+    }
+
+    #[test]
+    fn changing_settings_finishes_the_current_ramp() {
+        let mut value = SmoothedValue::new(0.0, 4.0, SmoothingType::Linear, SAMPLE_RATE);
+        value.set_target(1.0);
+        value.set_ramp_ms(8.0);
+        assert_eq!(value.current(), 1.0);
+
+        value.set_target(0.0);
+        value.set_smoothing_type(SmoothingType::Exponential);
+        assert_eq!(value.current(), 0.0);
+
+        value.set_target(1.0);
+        value.set_sample_rate(SAMPLE_RATE * 2);
+        assert_eq!(value.current(), 1.0);
+
+        value.set_target(0.0).skip();
+        assert_eq!(value.current(), 0.0);
+        assert!(!value.is_smoothing());
+    }
+}
@@ -0,0 +1,173 @@
+use crate::resample::{self, ResampleQuality};
+
+/// Multi-channel audio sample data that grains are read from.
+///
+/// Channels are stored non-interleaved (planar), so that every channel
+/// can be read from as a contiguous slice of samples.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceBuffer {
+    /// 1 array element = 1 channel of sample data
+    channels: Vec<Vec<f32>>,
+    /// The number of frames in every channel
+    num_frames: usize,
+    /// The sample rate that the sample data was recorded / decoded at
+    sample_rate: u32,
+}
+
+impl Default for SourceBuffer {
+    fn default() -> Self {
+        Self {
+            channels: Vec::new(),
+            num_frames: 0,
+            sample_rate: Self::DEFAULT_SAMPLE_RATE,
+        }
+    }
+}
+
+impl SourceBuffer {
+    pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+
+    /// Creates a buffer out of planar channel data.
+    ///
+    /// If the channels differ in length, every channel is truncated to the length of the shortest one.
+    pub fn new(mut channels: Vec<Vec<f32>>, sample_rate: u32) -> Self {
+        let num_frames = channels.iter().map(Vec::len).min().unwrap_or(0);
+        for channel in &mut channels {
+            channel.truncate(num_frames);
+        }
+
+        Self {
+            channels,
+            num_frames,
+            sample_rate,
+        }
+    }
+
+    /// Creates a single-channel buffer
+    pub fn from_mono(samples: Vec<f32>, sample_rate: u32) -> Self {
+        Self::new(vec![samples], sample_rate)
+    }
+
+    /// Creates a buffer out of interleaved sample data (i.e. L, R, L, R, ...)
+    ///
+    /// Any incomplete frame at the end of `samples` is dropped.
+    pub fn from_interleaved(samples: &[f32], num_channels: usize, sample_rate: u32) -> Self {
+        if num_channels == 0 {
+            return Self::new(Vec::new(), sample_rate);
+        }
+
+        let num_frames = samples.len() / num_channels;
+        let channels = (0..num_channels)
+            .map(|channel_i| {
+                samples
+                    .chunks_exact(num_channels)
+                    .map(|frame| frame[channel_i])
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            channels,
+            num_frames,
+            sample_rate,
+        }
+    }
+
+    pub fn num_channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    pub fn num_frames(&self) -> usize {
+        self.num_frames
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns `true` if the buffer contains no frames (or no channels)
+    pub fn is_empty(&self) -> bool {
+        self.num_frames == 0 || self.channels.is_empty()
+    }
+
+    /// Returns the sample data for a single channel.
+    ///
+    /// If `channel` is out of range, an empty slice is returned.
+    pub fn channel(&self, channel: usize) -> &[f32] {
+        self.channels.get(channel).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn channels(&self) -> &[Vec<f32>] {
+        &self.channels
+    }
+
+    /// Returns a copy of the buffer, converted to a different sample rate.
+    ///
+    /// Grains read one buffer frame per output frame (before transposition),
+    /// so buffers should be resampled to the synthesizer's sample rate before
+    /// they are played, or they will sound detuned.
+    pub fn resampled(&self, sample_rate: u32, quality: ResampleQuality) -> Self {
+        if sample_rate == self.sample_rate || sample_rate == 0 {
+            return self.clone();
+        }
+
+        let channels = self
+            .channels
+            .iter()
+            .map(|channel| resample::resample(channel, self.sample_rate, sample_rate, quality))
+            .collect();
+
+        Self::new(channels, sample_rate)
+    }
+}
+
+impl From<Vec<f32>> for SourceBuffer {
+    /// Creates a single-channel buffer at the default sample rate
+    fn from(samples: Vec<f32>) -> Self {
+        Self::from_mono(samples, Self::DEFAULT_SAMPLE_RATE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_are_truncated_to_the_shortest() {
+        let buffer = SourceBuffer::new(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0]], 48000);
+        assert_eq!(buffer.num_channels(), 2);
+        assert_eq!(buffer.num_frames(), 2);
+        assert_eq!(buffer.channel(0), [1.0, 2.0]);
+        assert_eq!(buffer.sample_rate(), 48000);
+    }
+
+    #[test]
+    fn deinterleaves_samples() {
+        let buffer = SourceBuffer::from_interleaved(&[1.0, -1.0, 2.0, -2.0, 3.0], 2, 44100);
+        assert_eq!(buffer.num_frames(), 2);
+        assert_eq!(buffer.channel(0), [1.0, 2.0]);
+        assert_eq!(buffer.channel(1), [-1.0, -2.0]);
+
+        assert!(SourceBuffer::from_interleaved(&[1.0], 0, 44100).is_empty());
+    }
+
+    #[test]
+    fn out_of_range_channels_are_empty() {
+        let buffer = SourceBuffer::from(vec![0.5; 10]);
+        assert_eq!(buffer.sample_rate(), SourceBuffer::DEFAULT_SAMPLE_RATE);
+        assert_eq!(buffer.channel(0).len(), 10);
+        assert!(buffer.channel(1).is_empty());
+        assert!(SourceBuffer::default().is_empty());
+    }
+
+    #[test]
+    fn resampling_changes_every_channel() {
+        let buffer = SourceBuffer::new(vec![vec![0.0; 441], vec![0.0; 441]], 44100);
+        let resampled = buffer.resampled(48000, ResampleQuality::Fast);
+        assert_eq!(resampled.sample_rate(), 48000);
+        assert_eq!(resampled.num_channels(), 2);
+        assert_eq!(resampled.num_frames(), 480);
+
+        assert_eq!(buffer.resampled(44100, ResampleQuality::High), buffer);
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::{bail, Context};
+use audio::source_buffer::SourceBuffer;
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::{fs::File, io::ErrorKind, path::Path};
+use symphonia::core::{
+    audio::SampleBuffer, codecs::DecoderOptions, errors::Error as DecodeError,
+    formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+};
+
+/// File extensions of the formats that `read_source_buffer` can decode
+pub const SUPPORTED_EXTENSIONS: [&str; 4] = ["wav", "flac", "mp3", "ogg"];
+
+/// Returns `true` if the file at `path` looks like something `read_source_buffer` can decode
+pub fn is_supported_file(path: &Path) -> bool {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => SUPPORTED_EXTENSIONS
+            .iter()
+            .any(|supported| extension.eq_ignore_ascii_case(supported)),
+        None => false,
+    }
+}
+
+/// Decodes an audio file from disk into a `SourceBuffer` at the file's own sample rate.
+///
+/// WAV, FLAC, MP3 and Ogg Vorbis files are supported (see `SUPPORTED_EXTENSIONS`).
+pub fn read_source_buffer(path: &Path) -> anyhow::Result<SourceBuffer> {
+    if !is_supported_file(path) {
+        bail!(
+            "{} is not a supported audio file (only {} files can be read)",
+            path.display(),
+            SUPPORTED_EXTENSIONS.join(", ")
+        );
+    }
+
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .with_context(|| format!("failed to read {}", path.display()))?
+        .format;
+    let track = format
+        .default_track()
+        .with_context(|| format!("{} doesn't contain any audio", path.display()))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .with_context(|| format!("failed to decode {}", path.display()))?;
+
+    let mut samples = Vec::new();
+    let mut num_channels = track
+        .codec_params
+        .channels
+        .map_or(0, |channels| channels.count());
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or_default();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // the end of the file is reported as an error
+            Err(DecodeError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // a corrupt packet is skipped, rather than failing the whole file
+            Err(DecodeError::DecodeError(_)) => continue,
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to decode {}", path.display()))
+            }
+        };
+        let spec = *decoded.spec();
+        num_channels = spec.channels.count();
+        sample_rate = spec.rate;
+
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buffer.samples());
+    }
+
+    if num_channels == 0 || sample_rate == 0 {
+        bail!("{} doesn't contain any audio", path.display());
+    }
+
+    Ok(SourceBuffer::from_interleaved(
+        &samples,
+        num_channels,
+        sample_rate,
+    ))
+}
+
+/// The sample encoding used for rendered files
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BitDepth {
+    #[default]
+    Int16,
+    Int24,
+    Float32,
+}
+
+impl BitDepth {
+    pub const NAMES: [&'static str; 3] = ["16", "24", "32f"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "16" => Some(BitDepth::Int16),
+            "24" => Some(BitDepth::Int24),
+            "32f" => Some(BitDepth::Float32),
+            _ => None,
+        }
+    }
+}
+
+/// Writes interleaved sample data to a WAV file.
+///
+/// Integer formats are clipped to -1.0 -> 1.0.
+pub fn write_wav(
+    path: &Path,
+    samples: &[f32],
+    num_channels: u16,
+    sample_rate: u32,
+    bit_depth: BitDepth,
+) -> anyhow::Result<()> {
+    let (bits_per_sample, sample_format) = match bit_depth {
+        BitDepth::Int16 => (16, SampleFormat::Int),
+        BitDepth::Int24 => (24, SampleFormat::Int),
+        BitDepth::Float32 => (32, SampleFormat::Float),
+    };
+    let spec = WavSpec {
+        channels: num_channels,
+        sample_rate,
+        bits_per_sample,
+        sample_format,
+    };
+
+    let mut writer = WavWriter::create(path, spec)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    match bit_depth {
+        BitDepth::Float32 => {
+            for &sample in samples {
+                writer.write_sample(sample)?;
+            }
+        }
+        BitDepth::Int16 | BitDepth::Int24 => {
+            let amplitude = ((1_i32 << (bits_per_sample - 1)) - 1) as f32;
+            for &sample in samples {
+                writer.write_sample((sample.clamp(-1.0, 1.0) * amplitude) as i32)?;
+            }
+        }
+    }
+    writer
+        .finalize()
+        .with_context(|| format!("failed to finish writing {}", path.display()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    /// A path in the system's temp directory that is unique to this test run
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        env::temp_dir().join(format!("granulator-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn recognizes_supported_extensions() {
+        assert!(is_supported_file(Path::new("a.wav")));
+        assert!(is_supported_file(Path::new("dir/b.FLAC")));
+        assert!(is_supported_file(Path::new("c.mp3")));
+        assert!(is_supported_file(Path::new("d.ogg")));
+        assert!(!is_supported_file(Path::new("e.aiff")));
+        assert!(!is_supported_file(Path::new("wav")));
+    }
+
+    #[test]
+    fn bit_depth_names_round_trip() {
+        assert_eq!(BitDepth::from_name("16"), Some(BitDepth::Int16));
+        assert_eq!(BitDepth::from_name("24"), Some(BitDepth::Int24));
+        assert_eq!(BitDepth::from_name("32f"), Some(BitDepth::Float32));
+        assert_eq!(BitDepth::from_name("8"), None);
+    }
+
+    #[test]
+    fn written_wavs_can_be_read_back() {
+        // stereo, so the round trip also checks that channels are deinterleaved
+        let samples = [0.0, 0.5, -0.25, 1.0, 0.75, -2.0];
+        for (bit_depth, tolerance) in [
+            (BitDepth::Int16, 1e-4),
+            (BitDepth::Int24, 1e-6),
+            (BitDepth::Float32, 0.0),
+        ] {
+            let path = temp_path(&format!("{:?}.wav", bit_depth));
+            write_wav(&path, &samples, 2, 22050, bit_depth).unwrap();
+            let buffer = read_source_buffer(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+
+            assert_eq!(buffer.sample_rate(), 22050);
+            assert_eq!(buffer.num_channels(), 2);
+            let expected_left = [0.0, -0.25, 0.75];
+            // integer formats clip to full scale
+            let expected_right = match bit_depth {
+                BitDepth::Float32 => [0.5, 1.0, -2.0],
+                _ => [0.5, 1.0, -1.0],
+            };
+            for (channel, expected) in [(0, expected_left), (1, expected_right)] {
+                for (actual, expected) in buffer.channel(channel).iter().zip(expected) {
+                    assert!((actual - expected).abs() <= tolerance, "{:?}", bit_depth);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unsupported_or_missing_files_are_errors() {
+        assert!(read_source_buffer(Path::new("notes.txt")).is_err());
+        assert!(read_source_buffer(&temp_path("missing.wav")).is_err());
+    }
+}
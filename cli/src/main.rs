@@ -0,0 +1,285 @@
+mod audio_file;
+mod settings;
+
+use anyhow::{bail, Context};
+use audio::{
+    granular_synthesizer::GranularSynthesizer,
+    granular_synthesizer_action::GranularSynthesizerAction,
+    offline_render::{self, RenderLength},
+    resample::ResampleQuality,
+};
+use audio_file::BitDepth;
+use clap::{App, Arg, ArgMatches};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// By default, the CLI allows as many simultaneous grains as the web demo
+const DEFAULT_MAX_GRAINS: u32 = 250;
+
+/// Appended to the names of rendered files when no output file name is given
+const OUTPUT_SUFFIX: &str = "_granulated";
+
+/// Everything needed to render a single file, which is shared by every file in batch mode
+struct RenderSettings<'a> {
+    matches: &'a ArgMatches<'a>,
+    seconds: f32,
+    num_channels: u16,
+    sample_rate: Option<u32>,
+    resample_quality: ResampleQuality,
+    bit_depth: BitDepth,
+    gain: f32,
+}
+
+fn app() -> App<'static, 'static> {
+    App::new("granulator")
+        .about("Granulates audio files from the command line")
+        .arg(Arg::with_name("input").required(true).help(
+            "A .wav, .flac, .mp3 or .ogg file, \
+                     or a directory of audio files to granulate (batch mode)",
+        ))
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .help(
+                    "The file to write (or directory, in batch mode) \
+                     [default: <input>_granulated.wav next to the input]",
+                ),
+        )
+        .arg(
+            Arg::with_name("duration")
+                .short("d")
+                .long("duration")
+                .takes_value(true)
+                .default_value("10")
+                .help("Length of the output in seconds"),
+        )
+        .arg(
+            Arg::with_name("channels")
+                .short("c")
+                .long("channels")
+                .takes_value(true)
+                .default_value("2")
+                .help("Number of output channels"),
+        )
+        .arg(
+            Arg::with_name("sample-rate")
+                .long("sample-rate")
+                .takes_value(true)
+                .help("Sample rate of the output [default: the input's sample rate]"),
+        )
+        .arg(
+            Arg::with_name("resample")
+                .long("resample")
+                .takes_value(true)
+                .possible_values(&ResampleQuality::NAMES)
+                .default_value("high")
+                .help(
+                    "Quality of the conversion when the input doesn't match the output sample rate",
+                ),
+        )
+        .arg(
+            Arg::with_name("bit-depth")
+                .long("bit-depth")
+                .takes_value(true)
+                .possible_values(&BitDepth::NAMES)
+                .default_value("16")
+                .help("Sample encoding of the output"),
+        )
+        .arg(
+            Arg::with_name("gain")
+                .short("g")
+                .long("gain")
+                .takes_value(true)
+                .default_value("1")
+                .help("Output gain"),
+        )
+        .args(&settings::args())
+}
+
+fn parse<T: std::str::FromStr>(matches: &ArgMatches, name: &str) -> anyhow::Result<T> {
+    let value = matches.value_of(name).unwrap_or_default();
+    value
+        .trim()
+        .parse::<T>()
+        .map_err(|_| anyhow::anyhow!("invalid value for --{}: {}", name, value))
+}
+
+/// Names the output of `input` when no output file name is given
+fn default_output_path(input: &Path, output_dir: &Path) -> PathBuf {
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+    output_dir.join(format!("{}{}.wav", stem, OUTPUT_SUFFIX))
+}
+
+/// Granulates a single file
+fn render_file(input: &Path, output: &Path, settings: &RenderSettings) -> anyhow::Result<()> {
+    let buffer = audio_file::read_source_buffer(input)?;
+    if buffer.is_empty() {
+        bail!("{} doesn't contain any audio", input.display());
+    }
+
+    let sample_rate = settings.sample_rate.unwrap_or_else(|| buffer.sample_rate());
+    let buffer = buffer.resampled(sample_rate, settings.resample_quality);
+
+    let mut granular_synthesizer = GranularSynthesizer::new();
+    granular_synthesizer
+        .set_sample_rate(sample_rate)
+        .set_max_number_of_channels(DEFAULT_MAX_GRAINS)
+        .set_buffer(Arc::new(buffer));
+    settings::apply(settings.matches, &mut granular_synthesizer)?;
+    let mut effect_chain = settings::effect_chain(settings.matches, sample_rate)?;
+
+    let mut samples = offline_render::render_with_effects(
+        &mut granular_synthesizer,
+        &mut effect_chain,
+        RenderLength::Seconds(settings.seconds),
+        settings.num_channels as usize,
+    );
+    for sample in &mut samples {
+        *sample *= settings.gain;
+    }
+
+    audio_file::write_wav(
+        output,
+        &samples,
+        settings.num_channels,
+        sample_rate,
+        settings.bit_depth,
+    )?;
+    eprintln!("{} -> {}", input.display(), output.display());
+
+    Ok(())
+}
+
+/// Returns `true` if `path` looks like the output of an earlier render
+fn is_rendered_file(path: &Path) -> bool {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().ends_with(OUTPUT_SUFFIX))
+        .unwrap_or(false)
+}
+
+/// Granulates every supported file in `input_dir` with the same settings.
+///
+/// Files written by earlier batches (ending in `OUTPUT_SUFFIX`) are skipped,
+/// so that batches can be re-run in place.
+fn render_dir(
+    input_dir: &Path,
+    output_dir: &Path,
+    settings: &RenderSettings,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("failed to create {}", output_dir.display()))?;
+
+    let mut inputs: Vec<PathBuf> = fs::read_dir(input_dir)
+        .with_context(|| format!("failed to read {}", input_dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file() && audio_file::is_supported_file(path) && !is_rendered_file(path)
+        })
+        .collect();
+    inputs.sort();
+
+    if inputs.is_empty() {
+        bail!(
+            "{} doesn't contain any supported audio files ({})",
+            input_dir.display(),
+            audio_file::SUPPORTED_EXTENSIONS.join(", ")
+        );
+    }
+
+    // one bad file shouldn't stop the rest of the batch
+    let mut num_failed = 0;
+    for input in &inputs {
+        let output = default_output_path(input, output_dir);
+        if let Err(e) = render_file(input, &output, settings) {
+            eprintln!("error: {:#}", e);
+            num_failed += 1;
+        }
+    }
+
+    if num_failed > 0 {
+        bail!("{} of {} files failed to render", num_failed, inputs.len());
+    }
+
+    Ok(())
+}
+
+fn run(matches: &ArgMatches) -> anyhow::Result<()> {
+    let settings = RenderSettings {
+        matches,
+        seconds: parse(matches, "duration")?,
+        num_channels: parse(matches, "channels")?,
+        sample_rate: matches
+            .is_present("sample-rate")
+            .then(|| parse(matches, "sample-rate"))
+            .transpose()?,
+        resample_quality: ResampleQuality::from_name(matches.value_of("resample").unwrap())
+            .unwrap_or_default(),
+        bit_depth: BitDepth::from_name(matches.value_of("bit-depth").unwrap()).unwrap_or_default(),
+        gain: parse(matches, "gain")?,
+    };
+
+    if settings.num_channels == 0 {
+        bail!("--channels must be at least 1");
+    }
+    if settings.sample_rate == Some(0) {
+        bail!("--sample-rate must be above 0");
+    }
+
+    let input = Path::new(matches.value_of("input").unwrap());
+    if input.is_dir() {
+        let output_dir = matches.value_of("output").map(Path::new).unwrap_or(input);
+        render_dir(input, output_dir, &settings)
+    } else {
+        let output = match matches.value_of("output") {
+            Some(output) => PathBuf::from(output),
+            None => default_output_path(input, input.parent().unwrap_or_else(|| Path::new(""))),
+        };
+        render_file(input, &output, &settings)
+    }
+}
+
+fn main() {
+    let matches = app().get_matches();
+    if let Err(e) = run(&matches) {
+        eprintln!("error: {:#}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_paths_are_named_after_their_input() {
+        assert_eq!(
+            default_output_path(Path::new("in/voice.flac"), Path::new("out")),
+            Path::new("out/voice_granulated.wav")
+        );
+    }
+
+    #[test]
+    fn recognizes_rendered_files() {
+        assert!(is_rendered_file(Path::new("out/voice_granulated.wav")));
+        assert!(!is_rendered_file(Path::new("in/voice.wav")));
+    }
+
+    #[test]
+    fn parses_command_line_values() {
+        let matches = app()
+            .get_matches_from_safe(["granulator", "in.wav", "--duration", "2.5"])
+            .unwrap();
+        assert_eq!(parse::<f32>(&matches, "duration").unwrap(), 2.5);
+        assert_eq!(parse::<u16>(&matches, "channels").unwrap(), 2);
+
+        let matches = app()
+            .get_matches_from_safe(["granulator", "in.wav", "--duration", "long"])
+            .unwrap();
+        assert!(parse::<f32>(&matches, "duration").is_err());
+    }
+}
@@ -0,0 +1,657 @@
+use anyhow::{anyhow, bail, Context};
+use audio::{
+    channel_policy::ChannelPolicy,
+    effect::Effect,
+    effect_chain::EffectChain,
+    envelope_shape::EnvelopeShape,
+    grain_filter::GrainFilterMode,
+    grain_len_mode::GrainLenMode,
+    granular_synthesizer_action::GranularSynthesizerAction,
+    interpolation::Interpolation,
+    lfo::{Lfo, LfoShape},
+    modulation_matrix::{ModulationRouting, ModulationTarget},
+    pan_law::PanLaw,
+    pitch_quantizer::{PitchQuantizer, Scale},
+    playhead::PositionMode,
+    preset::Preset,
+    reverb::Reverb,
+    scheduler::SchedulerMode,
+    smoothed_value::SmoothingType,
+};
+use clap::{Arg, ArgMatches};
+use std::{fmt::Display, fs, str::FromStr};
+
+/// Values accepted by `--interpolation`
+const INTERPOLATION_NAMES: [&str; 3] = ["linear", "cubic", "sinc"];
+
+/// Values accepted by `--normalize`
+const ON_OFF_NAMES: [&str; 2] = ["on", "off"];
+
+/// Command-line flags for every synthesizer parameter.
+///
+/// Parameters that aren't given keep the synthesizer's defaults.
+pub fn args() -> Vec<Arg<'static, 'static>> {
+    let value = |name: &'static str, help: &'static str| {
+        Arg::with_name(name)
+            .long(name)
+            .takes_value(true)
+            .allow_hyphen_values(true)
+            .help(help)
+    };
+    let choice = |name: &'static str, names: &'static [&'static str], help: &'static str| {
+        value(name, help).possible_values(names)
+    };
+
+    vec![
+        value(
+            "preset",
+            "A .json or .toml preset to start from (any other settings are applied on top of it)",
+        ),
+        value(
+            "seed",
+            "Seed for the random number generator, for reproducible output",
+        ),
+        value(
+            "selection-start",
+            "Start of the region grains are read from (0.0 -> 1.0)",
+        ),
+        value(
+            "selection-end",
+            "End of the region grains are read from (0.0 -> 1.0)",
+        ),
+        choice(
+            "grain-len-mode",
+            &GrainLenMode::NAMES,
+            "Whether grain lengths are relative to the selection or in milliseconds",
+        ),
+        value(
+            "grain-len-min",
+            "Shortest grain, relative to the selection (0.0 -> 1.0)",
+        ),
+        value(
+            "grain-len-max",
+            "Longest grain, relative to the selection (0.0 -> 1.0)",
+        ),
+        value(
+            "grain-len-min-ms",
+            "Shortest grain in milliseconds (absolute mode)",
+        ),
+        value(
+            "grain-len-max-ms",
+            "Longest grain in milliseconds (absolute mode)",
+        ),
+        value(
+            "density",
+            "Portion of the maximum number of grains that play at once (0.0 -> 1.0)",
+        ),
+        value(
+            "max-grains",
+            "The most grains that can play at once [default: 250]",
+        ),
+        value(
+            "refresh-interval",
+            "Average interval between grain onsets, in samples at 44.1kHz",
+        ),
+        value(
+            "grains-per-second",
+            "Average number of grain onsets per second",
+        ),
+        choice(
+            "scheduler",
+            &SchedulerMode::NAMES,
+            "How grain onsets are timed",
+        ),
+        value(
+            "jitter",
+            "Random deviation of synchronous grain onsets (0.0 -> 1.0)",
+        ),
+        choice(
+            "position-mode",
+            &PositionMode::NAMES,
+            "How grain start positions are picked",
+        ),
+        value(
+            "scan-speed",
+            "Speed of the playhead in scan mode (1.0 = real-time)",
+        ),
+        value(
+            "spray",
+            "Random deviation (in milliseconds) from the playhead in scan mode",
+        ),
+        value("pitch", "Transposition of every grain, in semitones"),
+        value("cents", "Fine-tuning of every grain, in cents"),
+        value(
+            "pitch-spread",
+            "Random transposition of each grain, up to +/- this many semitones",
+        ),
+        value(
+            "scale",
+            "Snaps random transpositions to a scale (major, minor, pentatonic, whole tone) \
+             or a list of semitones (e.g. 0,7,12,19)",
+        ),
+        value(
+            "scala",
+            "Snaps random transpositions to a tuning from a Scala (.scl) file",
+        ),
+        choice(
+            "interpolation",
+            &INTERPOLATION_NAMES,
+            "How samples are read between frames when transposing",
+        ),
+        value(
+            "reverse",
+            "Chance that a grain plays backwards (0.0 -> 1.0)",
+        ),
+        choice(
+            "envelope",
+            &EnvelopeShape::NAMES,
+            "Amplitude window of each grain",
+        ),
+        value(
+            "envelope-skew",
+            "Where each grain's envelope peaks (0.5 = centered)",
+        ),
+        value(
+            "amplitude-min",
+            "Quietest random grain amplitude (0.0 -> 1.0)",
+        ),
+        value(
+            "amplitude-max",
+            "Loudest random grain amplitude (0.0 -> 1.0)",
+        ),
+        choice(
+            "grain-filter",
+            &GrainFilterMode::NAMES,
+            "Type of filter that each grain is run through",
+        ),
+        value(
+            "grain-filter-cutoff-min",
+            "Lowest random grain filter cutoff in Hz",
+        ),
+        value(
+            "grain-filter-cutoff-max",
+            "Highest random grain filter cutoff in Hz",
+        ),
+        value(
+            "grain-filter-resonance",
+            "Resonance of each grain's filter (0.0 -> 1.0)",
+        ),
+        choice(
+            "normalize",
+            &ON_OFF_NAMES,
+            "Compensate the output level for the number of sounding grains",
+        ),
+        value(
+            "smoothing",
+            "Ramp time (in milliseconds) for continuous parameters",
+        ),
+        choice(
+            "smoothing-type",
+            &SmoothingType::NAMES,
+            "Curve of parameter ramps",
+        ),
+        value(
+            "channel-policy",
+            "Which input channel grains read from: pan, random, or a channel number (from 1)",
+        ),
+        value(
+            "pan-center",
+            "Stereo position grains are centered around (-1.0 -> 1.0)",
+        ),
+        value(
+            "pan-spread",
+            "Random deviation from the pan center (0.0 -> 1.0)",
+        ),
+        choice(
+            "pan-law",
+            &PanLaw::NAMES,
+            "How grain amplitude is split between channels",
+        ),
+        value(
+            "lfo",
+            "Configures an LFO as index:shape:rate:depth[:phase] (e.g. 0:sine:0.5:1)",
+        )
+        .multiple(true)
+        .number_of_values(1),
+        value(
+            "route",
+            "Routes an LFO to a parameter as index:target:amount (e.g. \"0:grain length:0.5\")",
+        )
+        .multiple(true)
+        .number_of_values(1),
+        value(
+            "reverb",
+            "Adds a reverb as room-size[:damping[:pre-delay-ms[:width[:mix]]]] (e.g. 0.8:0.5:20), \
+             where mix is how much of the reverb is heard (0.0 -> 1.0)",
+        ),
+    ]
+}
+
+/// Parses a flag's value, if it was given
+fn value<T>(matches: &ArgMatches, name: &str) -> anyhow::Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    matches
+        .value_of(name)
+        .map(|value| {
+            value
+                .trim()
+                .parse::<T>()
+                .map_err(|e| anyhow!("invalid value for --{}: {} ({})", name, value, e))
+        })
+        .transpose()
+}
+
+/// Looks up a flag's value by name (the name has already been validated by clap)
+fn choice<T>(matches: &ArgMatches, name: &str, from_name: impl Fn(&str) -> Option<T>) -> Option<T> {
+    matches.value_of(name).and_then(from_name)
+}
+
+fn interpolation_from_name(name: &str) -> Option<Interpolation> {
+    match name {
+        "linear" => Some(Interpolation::Linear),
+        "cubic" => Some(Interpolation::CubicHermite),
+        "sinc" => Some(Interpolation::WindowedSinc),
+        _ => None,
+    }
+}
+
+fn parse_channel_policy(value: &str) -> anyhow::Result<ChannelPolicy> {
+    match value {
+        "pan" => Ok(ChannelPolicy::FollowPan),
+        "random" => Ok(ChannelPolicy::Random),
+        channel => match channel.parse::<usize>() {
+            Ok(channel) if channel > 0 => Ok(ChannelPolicy::Fixed(channel - 1)),
+            _ => bail!("invalid value for --channel-policy: {}", value),
+        },
+    }
+}
+
+fn parse_pitch_quantizer(value: &str) -> anyhow::Result<PitchQuantizer> {
+    if let Some(scale) = Scale::from_name(value) {
+        return Ok(PitchQuantizer::from_scale(scale));
+    }
+
+    let semitones = value
+        .split(',')
+        .map(|semitone| semitone.trim().parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| anyhow!("invalid value for --scale: {}", value))?;
+
+    PitchQuantizer::from_semitones("custom", &semitones)
+        .ok_or_else(|| anyhow!("--scale must include at least one pitch"))
+}
+
+/// Parses `index:shape:rate:depth[:phase]`
+fn parse_lfo(value: &str) -> anyhow::Result<(usize, Lfo)> {
+    let invalid = || anyhow!("invalid value for --lfo: {}", value);
+    let parts: Vec<&str> = value.split(':').map(str::trim).collect();
+    if parts.len() < 4 || parts.len() > 5 {
+        return Err(invalid());
+    }
+
+    let index = parts[0].parse::<usize>().map_err(|_| invalid())?;
+    let shape = LfoShape::from_name(parts[1]).ok_or_else(invalid)?;
+    let rate = parts[2].parse::<f32>().map_err(|_| invalid())?;
+    let depth = parts[3].parse::<f32>().map_err(|_| invalid())?;
+    let phase_offset = match parts.get(4) {
+        Some(phase_offset) => phase_offset.parse::<f32>().map_err(|_| invalid())?,
+        None => 0.0,
+    };
+
+    Ok((index, Lfo::new(shape, rate, depth, phase_offset)))
+}
+
+/// Parses `index:target:amount`
+fn parse_routing(value: &str) -> anyhow::Result<ModulationRouting> {
+    let invalid = || anyhow!("invalid value for --route: {}", value);
+    let parts: Vec<&str> = value.split(':').map(str::trim).collect();
+    if parts.len() != 3 {
+        return Err(invalid());
+    }
+
+    let lfo_index = parts[0].parse::<usize>().map_err(|_| invalid())?;
+    let target = ModulationTarget::from_name(parts[1]).ok_or_else(invalid)?;
+    let amount = parts[2].parse::<f32>().map_err(|_| invalid())?;
+
+    Ok(ModulationRouting::new(lfo_index, target, amount))
+}
+
+/// Parses `room-size[:damping[:pre-delay-ms[:width[:mix]]]]` into the reverb and its mix,
+/// leaving any parameters that aren't given at their defaults
+fn parse_reverb(value: &str, sample_rate: u32) -> anyhow::Result<(Reverb, f32)> {
+    let invalid = || anyhow!("invalid value for --reverb: {}", value);
+    let parts = value
+        .split(':')
+        .map(|part| part.trim().parse::<f32>().map_err(|_| invalid()))
+        .collect::<anyhow::Result<Vec<f32>>>()?;
+    let mut reverb = Reverb::new(sample_rate);
+    let num_parameters = reverb.parameters().len();
+    if parts.len() > num_parameters + 1 {
+        return Err(invalid());
+    }
+
+    for (i, &parameter) in parts.iter().take(num_parameters).enumerate() {
+        reverb.set_parameter(i, parameter);
+    }
+    let mix = parts
+        .get(num_parameters)
+        .copied()
+        .unwrap_or(Reverb::DEFAULT_MIX);
+
+    Ok((reverb, mix))
+}
+
+/// Reads a preset, choosing the format from the file's extension (JSON unless it ends in `.toml`)
+fn read_preset(path: &str) -> anyhow::Result<Preset> {
+    let contents = fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?;
+    let preset = if path.to_lowercase().ends_with(".toml") {
+        Preset::from_toml(&contents)
+    } else {
+        Preset::from_json(&contents)
+    };
+
+    preset.with_context(|| format!("failed to load {}", path))
+}
+
+/// Applies every synthesizer parameter that was given on the command line.
+///
+/// The synthesizer is reseeded afterwards, so that rendering starts from the new settings
+/// (rather than ramping to them) and output is reproducible.
+pub fn apply(
+    matches: &ArgMatches,
+    synthesizer: &mut impl GranularSynthesizerAction,
+) -> anyhow::Result<()> {
+    if let Some(max_grains) = value::<u32>(matches, "max-grains")? {
+        synthesizer.set_max_number_of_channels(max_grains);
+    }
+
+    if let Some(preset_path) = matches.value_of("preset") {
+        synthesizer.apply_preset(&read_preset(preset_path)?);
+    }
+
+    if let Some(selection_start) = value::<f32>(matches, "selection-start")? {
+        synthesizer.set_selection_start(selection_start);
+    }
+    if let Some(selection_end) = value::<f32>(matches, "selection-end")? {
+        synthesizer.set_selection_end(selection_end);
+    }
+
+    if let Some(grain_len_mode) = choice(matches, "grain-len-mode", GrainLenMode::from_name) {
+        synthesizer.set_grain_len_mode(grain_len_mode);
+    }
+    // the max is set first, since setting either bound can push the other one
+    if let Some(grain_len_max) = value::<f32>(matches, "grain-len-max")? {
+        synthesizer.set_grain_len_max(grain_len_max);
+    }
+    if let Some(grain_len_min) = value::<f32>(matches, "grain-len-min")? {
+        synthesizer.set_grain_len_min(grain_len_min);
+    }
+    if let Some(grain_len_max_ms) = value::<f32>(matches, "grain-len-max-ms")? {
+        synthesizer.set_grain_len_max_ms(grain_len_max_ms);
+    }
+    if let Some(grain_len_min_ms) = value::<f32>(matches, "grain-len-min-ms")? {
+        synthesizer.set_grain_len_min_ms(grain_len_min_ms);
+    }
+
+    if let Some(density) = value::<f32>(matches, "density")? {
+        synthesizer.set_density(density);
+    }
+    if let Some(refresh_interval) = value::<u32>(matches, "refresh-interval")? {
+        synthesizer.set_refresh_interval(refresh_interval);
+    }
+    if let Some(grains_per_second) = value::<f32>(matches, "grains-per-second")? {
+        synthesizer.set_grains_per_second(grains_per_second);
+    }
+    if let Some(scheduler_mode) = choice(matches, "scheduler", SchedulerMode::from_name) {
+        synthesizer.set_scheduler_mode(scheduler_mode);
+    }
+    if let Some(scheduler_jitter) = value::<f32>(matches, "jitter")? {
+        synthesizer.set_scheduler_jitter(scheduler_jitter);
+    }
+
+    if let Some(position_mode) = choice(matches, "position-mode", PositionMode::from_name) {
+        synthesizer.set_position_mode(position_mode);
+    }
+    if let Some(scan_speed) = value::<f32>(matches, "scan-speed")? {
+        synthesizer.set_scan_speed(scan_speed);
+    }
+    if let Some(spray_ms) = value::<f32>(matches, "spray")? {
+        synthesizer.set_spray_ms(spray_ms);
+    }
+
+    if let Some(pitch_semitones) = value::<f32>(matches, "pitch")? {
+        synthesizer.set_pitch_semitones(pitch_semitones);
+    }
+    if let Some(pitch_cents) = value::<f32>(matches, "cents")? {
+        synthesizer.set_pitch_cents(pitch_cents);
+    }
+    if let Some(pitch_spread) = value::<f32>(matches, "pitch-spread")? {
+        synthesizer.set_pitch_spread(pitch_spread);
+    }
+    if let Some(scale) = matches.value_of("scale") {
+        synthesizer.set_pitch_quantizer(Some(parse_pitch_quantizer(scale)?));
+    }
+    if let Some(scala_path) = matches.value_of("scala") {
+        let contents = fs::read_to_string(scala_path)
+            .with_context(|| format!("failed to read {}", scala_path))?;
+        let pitch_quantizer = PitchQuantizer::from_scala(&contents)
+            .with_context(|| format!("failed to parse {}", scala_path))?;
+        synthesizer.set_pitch_quantizer(Some(pitch_quantizer));
+    }
+    if let Some(interpolation) = choice(matches, "interpolation", interpolation_from_name) {
+        synthesizer.set_interpolation(interpolation);
+    }
+
+    if let Some(reverse_probability) = value::<f32>(matches, "reverse")? {
+        synthesizer.set_reverse_probability(reverse_probability);
+    }
+    if let Some(envelope_shape) = choice(matches, "envelope", EnvelopeShape::from_name) {
+        synthesizer.set_envelope_shape(envelope_shape);
+    }
+    if let Some(envelope_skew) = value::<f32>(matches, "envelope-skew")? {
+        synthesizer.set_envelope_skew(envelope_skew);
+    }
+    if let Some(amplitude_min) = value::<f32>(matches, "amplitude-min")? {
+        synthesizer.set_amplitude_min(amplitude_min);
+    }
+    if let Some(amplitude_max) = value::<f32>(matches, "amplitude-max")? {
+        synthesizer.set_amplitude_max(amplitude_max);
+    }
+    if let Some(grain_filter_mode) = choice(matches, "grain-filter", GrainFilterMode::from_name) {
+        synthesizer.set_grain_filter_mode(grain_filter_mode);
+    }
+    if let Some(cutoff_min_hz) = value::<f32>(matches, "grain-filter-cutoff-min")? {
+        synthesizer.set_grain_filter_cutoff_min_hz(cutoff_min_hz);
+    }
+    if let Some(cutoff_max_hz) = value::<f32>(matches, "grain-filter-cutoff-max")? {
+        synthesizer.set_grain_filter_cutoff_max_hz(cutoff_max_hz);
+    }
+    if let Some(resonance) = value::<f32>(matches, "grain-filter-resonance")? {
+        synthesizer.set_grain_filter_resonance(resonance);
+    }
+    if let Some(normalize) = matches.value_of("normalize") {
+        synthesizer.set_normalize(normalize == "on");
+    }
+
+    if let Some(smoothing_ms) = value::<f32>(matches, "smoothing")? {
+        synthesizer.set_smoothing_ms(smoothing_ms);
+    }
+    if let Some(smoothing_type) = choice(matches, "smoothing-type", SmoothingType::from_name) {
+        synthesizer.set_smoothing_type(smoothing_type);
+    }
+
+    if let Some(channel_policy) = matches.value_of("channel-policy") {
+        synthesizer.set_channel_policy(parse_channel_policy(channel_policy)?);
+    }
+    if let Some(pan_center) = value::<f32>(matches, "pan-center")? {
+        synthesizer.set_pan_center(pan_center);
+    }
+    if let Some(pan_spread) = value::<f32>(matches, "pan-spread")? {
+        synthesizer.set_pan_spread(pan_spread);
+    }
+    if let Some(pan_law) = choice(matches, "pan-law", PanLaw::from_name) {
+        synthesizer.set_pan_law(pan_law);
+    }
+
+    for lfo in matches.values_of("lfo").into_iter().flatten() {
+        let (index, lfo) = parse_lfo(lfo)?;
+        synthesizer.set_lfo(index, lfo);
+    }
+    for routing in matches.values_of("route").into_iter().flatten() {
+        synthesizer.add_modulation_routing(parse_routing(routing)?);
+    }
+
+    let seed = value::<u64>(matches, "seed")?.unwrap_or_else(|| synthesizer.seed());
+    synthesizer.reseed(seed);
+
+    Ok(())
+}
+
+/// Builds the chain of effects that the synthesizer's output is run through
+pub fn effect_chain(matches: &ArgMatches, sample_rate: u32) -> anyhow::Result<EffectChain> {
+    let mut effect_chain = EffectChain::new(sample_rate);
+    if let Some(reverb) = matches.value_of("reverb") {
+        let (reverb, mix) = parse_reverb(reverb, sample_rate)?;
+        effect_chain.add_effect(Box::new(reverb));
+        if let Some(slot) = effect_chain.slot_mut(effect_chain.len() - 1) {
+            slot.set_mix(mix);
+        }
+    }
+
+    Ok(effect_chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use audio::granular_synthesizer::GranularSynthesizer;
+    use clap::App;
+
+    fn matches(flags: &[&str]) -> ArgMatches<'static> {
+        App::new("granulator")
+            .args(&args())
+            .get_matches_from_safe(std::iter::once("granulator").chain(flags.iter().copied()))
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_channel_policies() {
+        assert_eq!(
+            parse_channel_policy("pan").unwrap(),
+            ChannelPolicy::FollowPan
+        );
+        assert_eq!(
+            parse_channel_policy("random").unwrap(),
+            ChannelPolicy::Random
+        );
+        // channels are numbered from 1 on the command line
+        assert_eq!(parse_channel_policy("2").unwrap(), ChannelPolicy::Fixed(1));
+        assert!(parse_channel_policy("0").is_err());
+        assert!(parse_channel_policy("left").is_err());
+    }
+
+    #[test]
+    fn parses_scales_and_custom_pitch_sets() {
+        assert_eq!(
+            parse_pitch_quantizer("minor").unwrap(),
+            PitchQuantizer::from_scale(Scale::Minor)
+        );
+        assert_eq!(parse_pitch_quantizer("0, 7").unwrap().pitches(), [0.0, 7.0]);
+        assert!(parse_pitch_quantizer("0,fifth").is_err());
+    }
+
+    #[test]
+    fn parses_lfos() {
+        let (index, lfo) = parse_lfo("1:triangle:2:0.5").unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(lfo, Lfo::new(LfoShape::Triangle, 2.0, 0.5, 0.0));
+
+        let (_, lfo) = parse_lfo("0:sine:1:1:0.25").unwrap();
+        assert_eq!(lfo.phase_offset(), 0.25);
+
+        assert!(parse_lfo("0:sine:1").is_err());
+        assert!(parse_lfo("0:saw:1:1").is_err());
+        assert!(parse_lfo("0:sine:1:1:0:0").is_err());
+    }
+
+    #[test]
+    fn parses_routings() {
+        assert_eq!(
+            parse_routing("2:grain length:-0.5").unwrap(),
+            ModulationRouting::new(2, ModulationTarget::GrainLen, -0.5)
+        );
+        assert!(parse_routing("2:grain length").is_err());
+        assert!(parse_routing("2:volume:1").is_err());
+    }
+
+    #[test]
+    fn parses_reverbs_and_their_mix() {
+        let (reverb, mix) = parse_reverb("0.8:0.5", 44100).unwrap();
+        assert_eq!(reverb.parameter(0), Some(0.8));
+        assert_eq!(reverb.parameter(1), Some(0.5));
+        assert_eq!(
+            reverb.parameter(2),
+            Some(Reverb::new(44100).parameter(2).unwrap())
+        );
+        assert_eq!(mix, Reverb::DEFAULT_MIX);
+
+        let (_, mix) = parse_reverb("0.8:0.5:20:1:0.6", 44100).unwrap();
+        assert_eq!(mix, 0.6);
+
+        assert!(parse_reverb("0.8:0.5:20:1:0.6:1", 44100).is_err());
+        assert!(parse_reverb("large", 44100).is_err());
+    }
+
+    #[test]
+    fn reverbs_are_added_to_the_effect_chain() {
+        let chain = effect_chain(&matches(&["--reverb", "0.5:0.5:0:1:0.25"]), 48000).unwrap();
+        assert_eq!(chain.len(), 1);
+        assert_eq!(chain.slots()[0].effect().name(), "reverb");
+        assert_eq!(chain.slots()[0].mix(), 0.25);
+
+        assert!(effect_chain(&matches(&[]), 48000).unwrap().is_empty());
+    }
+
+    #[test]
+    fn applies_the_given_settings() {
+        let matches = matches(&[
+            "--seed",
+            "5",
+            "--pan-law",
+            "linear",
+            "--grain-len-max-ms",
+            "50",
+            "--grain-len-min-ms",
+            "100",
+            "--lfo",
+            "0:square:2:1",
+            "--route",
+            "0:pan:0.5",
+        ]);
+        let mut synthesizer = GranularSynthesizer::new();
+        apply(&matches, &mut synthesizer).unwrap();
+
+        assert_eq!(synthesizer.seed(), 5);
+        assert_eq!(synthesizer.pan_law(), PanLaw::Linear);
+        // the min pushes the max up, regardless of the order the flags were given in
+        assert_eq!(synthesizer.grain_len_min_ms(), 100.0);
+        assert_eq!(synthesizer.grain_len_max_ms(), 100.0);
+        assert_eq!(synthesizer.lfo(0).unwrap().shape(), LfoShape::Square);
+        assert_eq!(
+            synthesizer.modulation_routings(),
+            [ModulationRouting::new(0, ModulationTarget::Pan, 0.5)]
+        );
+    }
+
+    #[test]
+    fn invalid_values_are_reported() {
+        let mut synthesizer = GranularSynthesizer::new();
+        let error = apply(&matches(&["--density", "lots"]), &mut synthesizer).unwrap_err();
+        assert!(error.to_string().contains("--density"));
+
+        assert!(apply(&matches(&["--lfo", "0:sine"]), &mut synthesizer).is_err());
+    }
+}
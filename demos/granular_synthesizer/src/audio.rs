@@ -11,6 +11,7 @@ pub mod decode;
 pub mod density;
 pub mod density_action;
 pub mod density_handle;
+pub mod effect_chain_handle;
 pub mod gain;
 pub mod gain_action;
 pub mod gain_handle;
@@ -25,4 +26,5 @@ pub mod recording_status;
 pub mod recording_status_action;
 pub mod recording_status_handle;
 pub mod refresh_interval_handle;
+pub mod render;
 pub mod stream_handle;
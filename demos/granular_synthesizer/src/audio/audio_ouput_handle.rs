@@ -19,7 +19,7 @@ impl AudioOutputHandle {
 }
 
 impl AudioOutputAction for AudioOutputHandle {
-    fn add_frame(&mut self, frame: Vec<f32>) {
+    fn add_frame(&mut self, frame: &[f32]) {
         self.data.lock().unwrap().add_frame(frame)
     }
 
@@ -15,7 +15,7 @@ pub struct AudioOutput {
 impl AudioOutput {
     /// Replaces all previous frames with a new frame
     /// This is necessary when the number of channels in a frame change
-    fn recalibrate_num_channels(&mut self, new_frame: &Vec<f32>) {
+    fn recalibrate_num_channels(&mut self, new_frame: &[f32]) {
         let new_num_channels = new_frame.len();
 
         // adjust num_channels to match new new_frame
@@ -24,7 +24,7 @@ impl AudioOutput {
         // fill the prev_frame buffer with the new_frame
         self.prev_frames.clear();
         for _ in 0..self.num_frames {
-            self.prev_frames.push_back(new_frame.clone());
+            self.prev_frames.push_back(new_frame.to_vec());
         }
     }
 }
@@ -33,19 +33,20 @@ impl AudioOutputAction for AudioOutput {
     const NUM_FRAMES_DEFAULT: usize = 20;
     const NUM_CHANNELS_DEFAULT: usize = 2;
 
-    fn add_frame(&mut self, frame: Vec<f32>) {
+    fn add_frame(&mut self, frame: &[f32]) {
         // adjust frames if length doesn't match
         if frame.len() != self.num_channels {
-            self.recalibrate_num_channels(&frame)
-        } else {
-            self.prev_frames.pop_front();
-            self.prev_frames.push_back(frame);
+            self.recalibrate_num_channels(frame)
+        } else if let Some(mut oldest_frame) = self.prev_frames.pop_front() {
+            // reuse the oldest frame's memory, so that no allocation is necessary
+            oldest_frame.copy_from_slice(frame);
+            self.prev_frames.push_back(oldest_frame);
         }
     }
 
     fn get_simple_moving_average(&self) -> Vec<f32> {
         // this is a moving average of each channel's amplitude across the previous stored frames
-        let mut moving_average = Vec::from(vec![0.0; self.num_channels]);
+        let mut moving_average = vec![0.0; self.num_channels];
 
         // get sum for each channel
         for frame in self.prev_frames.iter() {
@@ -1,10 +1,6 @@
-use hound::{SampleFormat, WavSpec, WavWriter};
-use std::{
-    io::Cursor,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
-use crate::utils::download;
+use crate::utils::{download, wav};
 
 /// Holds raw `f32` sample data and exposes utilities for converting
 /// that sample data to .wav file and downloading it
@@ -39,25 +35,7 @@ impl AudioRecorderHandle {
         num_channels: impl Into<u16>,
         sample_rate: impl Into<u32>,
     ) -> Vec<u8> {
-        let wav_spec = WavSpec {
-            channels: num_channels.into(),
-            sample_rate: sample_rate.into(),
-            // these were the default used in the `hound` doc examples
-            bits_per_sample: 16,
-            sample_format: SampleFormat::Int,
-        };
-
-        let mut bytes = Vec::new();
-        let mut bytes_cursor = Cursor::new(&mut bytes);
-        let mut wav_writer = WavWriter::new(&mut bytes_cursor, wav_spec).unwrap();
         let stored_sample_data = self.data.lock().unwrap();
-        let amplitude = i16::MAX as f32;
-        for sample in stored_sample_data.iter() {
-            wav_writer
-                .write_sample((sample * amplitude) as i16)
-                .unwrap();
-        }
-        wav_writer.finalize().unwrap();
-        bytes
+        wav::encode_wav(&stored_sample_data, num_channels.into(), sample_rate.into())
     }
 }
@@ -1,3 +1,4 @@
+use audio::source_buffer::SourceBuffer;
 use std::fmt::Debug;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -10,19 +11,19 @@ use uuid::Uuid;
 /// For a different approach to this problem (with different constraints), see ```buffer_selection_handle```
 #[derive(Clone, Default)]
 pub struct BufferHandle {
-    data: Arc<Vec<f32>>,
+    data: Arc<SourceBuffer>,
     uuid: Uuid,
 }
 
 impl BufferHandle {
-    pub fn new(data: Arc<Vec<f32>>) -> Self {
+    pub fn new(data: Arc<SourceBuffer>) -> Self {
         BufferHandle {
             data: Arc::clone(&data),
             uuid: Uuid::new_v4(),
         }
     }
 
-    pub fn get_data(&self) -> Arc<Vec<f32>> {
+    pub fn get_data(&self) -> Arc<SourceBuffer> {
         Arc::clone(&self.data)
     }
 }
@@ -1,3 +1,5 @@
+use audio::{resample::ResampleQuality, source_buffer::SourceBuffer};
+use std::sync::Arc;
 use thiserror::Error;
 use wasm_bindgen::JsCast;
 use web_sys::{AudioBuffer, AudioContext};
@@ -37,3 +39,33 @@ pub async fn decode_bytes(
 
     Ok(audio_buffer)
 }
+
+/// Copies every channel of a decoded JavaScript AudioBuffer into a `SourceBuffer`
+pub fn audio_buffer_to_source_buffer(
+    audio_buffer: &AudioBuffer,
+) -> Result<SourceBuffer, DecodeBytesError> {
+    let channels = (0..audio_buffer.number_of_channels())
+        .map(|channel| {
+            audio_buffer
+                .get_channel_data(channel)
+                .map_err(|_| DecodeBytesError::TypeConversionFailure)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(SourceBuffer::new(
+        channels,
+        audio_buffer.sample_rate() as u32,
+    ))
+}
+
+/// Converts a decoded buffer to the audio output's sample rate, so that it plays at its original pitch.
+///
+/// Browsers usually decode audio at the `AudioContext`'s sample rate already,
+/// in which case the buffer is returned unchanged (as it is if `sample_rate` isn't known yet).
+pub fn match_sample_rate(buffer: Arc<SourceBuffer>, sample_rate: u32) -> Arc<SourceBuffer> {
+    if sample_rate == 0 || buffer.sample_rate() == sample_rate {
+        return buffer;
+    }
+
+    Arc::new(buffer.resampled(sample_rate, ResampleQuality::High))
+}
@@ -0,0 +1,279 @@
+use super::bump_counter::BumpCounter;
+use audio::{
+    delay::Delay,
+    effect::{EffectKind, EffectParameter},
+    effect_chain::EffectChain,
+    note_division::NoteDivision,
+};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// A copy of the settings of one effect in the chain, for displaying in the ui
+#[derive(Clone, Debug, PartialEq)]
+pub struct EffectSlotSummary {
+    pub name: &'static str,
+
+    pub parameters: &'static [EffectParameter],
+
+    /// The current value of each of `parameters`
+    pub values: Vec<f32>,
+
+    pub bypassed: bool,
+
+    pub mix: f32,
+}
+
+/// A copy of the settings of the first delay in the chain, for the dedicated delay controls
+#[derive(Clone, Debug, PartialEq)]
+pub struct DelaySettings {
+    /// Whether the delay is in the chain and isn't bypassed
+    pub enabled: bool,
+
+    pub mix: f32,
+
+    pub time_ms: f32,
+
+    pub bpm: f32,
+
+    pub division: Option<NoteDivision>,
+
+    pub feedback: f32,
+
+    pub tone_hz: f32,
+
+    pub ping_pong: bool,
+}
+
+impl Default for DelaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mix: Delay::DEFAULT_MIX,
+            time_ms: Delay::TIME_MS.default,
+            bpm: Delay::BPM.default,
+            division: Delay::sync_division(Delay::SYNC.default),
+            feedback: Delay::FEEDBACK.default,
+            tone_hz: Delay::TONE_HZ.default,
+            ping_pong: Delay::PING_PONG.default == 1.0,
+        }
+    }
+}
+
+/// Wrapper around `EffectChain`, which makes it possible to process audio on the audio thread,
+/// while also editing the chain from the UI.
+#[derive(Clone)]
+pub struct EffectChainHandle {
+    effect_chain: Arc<Mutex<EffectChain>>,
+    counter: u32,
+    uuid: Uuid,
+}
+
+impl EffectChainHandle {
+    pub fn add_effect(&mut self, effect_kind: EffectKind) -> &mut Self {
+        let mut effect_chain = self.effect_chain.lock().unwrap();
+        let effect = effect_kind.create(effect_chain.sample_rate());
+        effect_chain.add_effect(effect);
+        drop(effect_chain);
+        self.bump_counter();
+
+        self
+    }
+
+    pub fn remove_effect(&mut self, index: usize) -> &mut Self {
+        self.effect_chain.lock().unwrap().remove_effect(index);
+        self.bump_counter();
+
+        self
+    }
+
+    pub fn move_effect(&mut self, from: usize, to: usize) -> &mut Self {
+        self.effect_chain.lock().unwrap().move_effect(from, to);
+        self.bump_counter();
+
+        self
+    }
+
+    pub fn set_bypassed(&mut self, index: usize, bypassed: bool) -> &mut Self {
+        if let Some(slot) = self.effect_chain.lock().unwrap().slot_mut(index) {
+            slot.set_bypassed(bypassed);
+        }
+        self.bump_counter();
+
+        self
+    }
+
+    pub fn set_mix(&mut self, index: usize, mix: f32) -> &mut Self {
+        if let Some(slot) = self.effect_chain.lock().unwrap().slot_mut(index) {
+            slot.set_mix(mix);
+        }
+        self.bump_counter();
+
+        self
+    }
+
+    pub fn set_parameter(&mut self, index: usize, parameter_index: usize, value: f32) -> &mut Self {
+        if let Some(slot) = self.effect_chain.lock().unwrap().slot_mut(index) {
+            slot.effect_mut().set_parameter(parameter_index, value);
+        }
+        self.bump_counter();
+
+        self
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) -> &mut Self {
+        self.effect_chain
+            .lock()
+            .unwrap()
+            .set_sample_rate(sample_rate);
+        self.bump_counter();
+
+        self
+    }
+
+    /// Turns the delay next to the gain control on or off, by bypassing the first delay in the chain
+    /// (a delay is added to the end of the chain if there isn't one yet)
+    pub fn set_delay_enabled(&mut self, enabled: bool) -> &mut Self {
+        match self.delay_index() {
+            Some(index) => self.set_bypassed(index, !enabled),
+            None if enabled => self.add_effect(EffectKind::Delay),
+            None => self,
+        }
+    }
+
+    pub fn set_delay_mix(&mut self, mix: f32) -> &mut Self {
+        match self.delay_index() {
+            Some(index) => self.set_mix(index, mix),
+            None => self,
+        }
+    }
+
+    /// Sets one of `Delay`'s parameters on the first delay in the chain
+    pub fn set_delay_parameter(&mut self, parameter: &EffectParameter, value: f32) -> &mut Self {
+        let delay_index = self.delay_index();
+        let mut effect_chain = self.effect_chain.lock().unwrap();
+        if let Some(slot) = delay_index.and_then(|index| effect_chain.slot_mut(index)) {
+            let effect = slot.effect_mut();
+            if let Some(parameter_index) = effect
+                .parameters()
+                .iter()
+                .position(|delay_parameter| delay_parameter == parameter)
+            {
+                effect.set_parameter(parameter_index, value);
+            }
+        }
+        drop(effect_chain);
+        self.bump_counter();
+
+        self
+    }
+
+    /// The settings of the first delay in the chain (`None` if there's no delay in the chain)
+    pub fn delay_settings(&self) -> Option<DelaySettings> {
+        let summary = self.summaries().into_iter().nth(self.delay_index()?)?;
+        let value = |parameter: &EffectParameter| {
+            summary
+                .parameters
+                .iter()
+                .position(|delay_parameter| delay_parameter == parameter)
+                .and_then(|parameter_index| summary.values.get(parameter_index).copied())
+                .unwrap_or(parameter.default)
+        };
+
+        Some(DelaySettings {
+            enabled: !summary.bypassed,
+            mix: summary.mix,
+            time_ms: value(&Delay::TIME_MS),
+            bpm: value(&Delay::BPM),
+            division: Delay::sync_division(value(&Delay::SYNC)),
+            feedback: value(&Delay::FEEDBACK),
+            tone_hz: value(&Delay::TONE_HZ),
+            ping_pong: value(&Delay::PING_PONG) == 1.0,
+        })
+    }
+
+    /// The index of the first delay in the chain, which is the one edited by the dedicated delay controls
+    fn delay_index(&self) -> Option<usize> {
+        self.effect_chain
+            .lock()
+            .unwrap()
+            .slots()
+            .iter()
+            .position(|slot| slot.effect().name() == EffectKind::Delay.name())
+    }
+
+    pub fn len(&self) -> usize {
+        self.effect_chain.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effect_chain.lock().unwrap().is_empty()
+    }
+
+    /// Describes every effect in the chain, in order
+    pub fn summaries(&self) -> Vec<EffectSlotSummary> {
+        self.effect_chain
+            .lock()
+            .unwrap()
+            .slots()
+            .iter()
+            .map(|slot| {
+                let effect = slot.effect();
+                EffectSlotSummary {
+                    name: effect.name(),
+                    parameters: effect.parameters(),
+                    values: (0..effect.parameters().len())
+                        .filter_map(|i| effect.parameter(i))
+                        .collect(),
+                    bypassed: slot.bypassed(),
+                    mix: slot.mix(),
+                }
+            })
+            .collect()
+    }
+
+    /// Runs a block of the synthesizer's (mixed-down) output through the chain
+    pub fn process_block(&mut self, block: &mut [f32], num_channels: usize) {
+        self.effect_chain
+            .lock()
+            .unwrap()
+            .process_block(block, num_channels);
+    }
+
+    /// Returns a copy of the chain (including any ringing reverb / delay tails),
+    /// which can be rendered offline without interrupting real-time playback
+    pub fn snapshot(&self) -> EffectChain {
+        self.effect_chain.lock().unwrap().clone()
+    }
+}
+
+impl BumpCounter for EffectChainHandle {
+    fn bump_counter(&mut self) {
+        self.counter = self.counter.wrapping_add(1);
+    }
+}
+
+impl Default for EffectChainHandle {
+    fn default() -> Self {
+        Self {
+            effect_chain: Arc::new(Mutex::new(EffectChain::default())),
+            counter: Default::default(),
+            uuid: Uuid::new_v4(),
+        }
+    }
+}
+
+impl PartialEq for EffectChainHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.counter == other.counter && self.uuid == other.uuid
+    }
+}
+
+impl Debug for EffectChainHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EffectChainHandle")
+            .field("counter", &self.counter)
+            .field("uuid", &self.uuid)
+            .finish()
+    }
+}
@@ -1,7 +1,23 @@
 use super::global_defaults::MAX_NUM_CHANNELS;
+use audio::adsr::Adsr;
+use audio::channel_policy::ChannelPolicy;
+use audio::envelope_shape::EnvelopeShape;
+use audio::grain_filter::GrainFilterMode;
+use audio::grain_len_mode::GrainLenMode;
 use audio::granular_synthesizer::GranularSynthesizer;
 use audio::granular_synthesizer_action::GranularSynthesizerAction;
+use audio::interpolation::Interpolation;
+use audio::lfo::Lfo;
+use audio::modulation_matrix::ModulationRouting;
+use audio::note_voice::PlayMode;
+use audio::pan_law::PanLaw;
 use audio::percentage::Percentage;
+use audio::pitch_quantizer::PitchQuantizer;
+use audio::playhead::PositionMode;
+use audio::preset::Preset;
+use audio::scheduler::SchedulerMode;
+use audio::smoothed_value::SmoothingType;
+use audio::source_buffer::SourceBuffer;
 use std::fmt::Debug;
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
@@ -23,6 +39,24 @@ impl GranularSynthesizerAction for GranularSynthesizerHandle {
         }
     }
 
+    fn new_with_seed(seed: u64) -> GranularSynthesizerHandle {
+        Self {
+            granular_synthesizer: Arc::new(Mutex::new(GranularSynthesizer::new_with_seed(seed))),
+            counter: Default::default(),
+            uuid: Uuid::new_v4(),
+        }
+    }
+
+    fn reseed(&mut self, seed: u64) -> &mut Self {
+        self.granular_synthesizer.lock().unwrap().reseed(seed);
+
+        self
+    }
+
+    fn seed(&self) -> u64 {
+        self.granular_synthesizer.lock().unwrap().seed()
+    }
+
     fn selection_start(&self) -> Percentage {
         self.granular_synthesizer.lock().unwrap().selection_start()
     }
@@ -89,7 +123,7 @@ impl GranularSynthesizerAction for GranularSynthesizerHandle {
         self.granular_synthesizer.lock().unwrap().density()
     }
 
-    fn set_buffer(&mut self, buffer: Arc<Vec<f32>>) -> &mut Self {
+    fn set_buffer(&mut self, buffer: Arc<SourceBuffer>) -> &mut Self {
         self.granular_synthesizer.lock().unwrap().set_buffer(buffer);
 
         self
@@ -99,6 +133,274 @@ impl GranularSynthesizerAction for GranularSynthesizerHandle {
         self.granular_synthesizer.lock().unwrap().next_frame()
     }
 
+    fn process_block(&mut self, out: &mut [f32], channels: usize) {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .process_block(out, channels)
+    }
+
+    fn process_block_planar(&mut self, out: &mut [&mut [f32]]) {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .process_block_planar(out)
+    }
+
+    fn copy_last_frame(&self, frame_out: &mut Vec<f32>) {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .copy_last_frame(frame_out)
+    }
+
+    fn set_grains_per_second(&mut self, grains_per_second: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_grains_per_second(grains_per_second);
+
+        self
+    }
+
+    fn grains_per_second(&self) -> f32 {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .grains_per_second()
+    }
+
+    fn set_scheduler_mode(&mut self, scheduler_mode: SchedulerMode) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_scheduler_mode(scheduler_mode);
+
+        self
+    }
+
+    fn scheduler_mode(&self) -> SchedulerMode {
+        self.granular_synthesizer.lock().unwrap().scheduler_mode()
+    }
+
+    fn set_scheduler_jitter(&mut self, scheduler_jitter: impl Into<Percentage>) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_scheduler_jitter(scheduler_jitter);
+
+        self
+    }
+
+    fn scheduler_jitter(&self) -> Percentage {
+        self.granular_synthesizer.lock().unwrap().scheduler_jitter()
+    }
+
+    fn set_position_mode(&mut self, position_mode: PositionMode) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_position_mode(position_mode);
+
+        self
+    }
+
+    fn position_mode(&self) -> PositionMode {
+        self.granular_synthesizer.lock().unwrap().position_mode()
+    }
+
+    fn set_scan_speed(&mut self, scan_speed: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_scan_speed(scan_speed);
+
+        self
+    }
+
+    fn scan_speed(&self) -> f32 {
+        self.granular_synthesizer.lock().unwrap().scan_speed()
+    }
+
+    fn set_spray_ms(&mut self, spray_ms: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_spray_ms(spray_ms);
+
+        self
+    }
+
+    fn spray_ms(&self) -> f32 {
+        self.granular_synthesizer.lock().unwrap().spray_ms()
+    }
+
+    fn set_smoothing_ms(&mut self, smoothing_ms: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_smoothing_ms(smoothing_ms);
+
+        self
+    }
+
+    fn smoothing_ms(&self) -> f32 {
+        self.granular_synthesizer.lock().unwrap().smoothing_ms()
+    }
+
+    fn set_smoothing_type(&mut self, smoothing_type: SmoothingType) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_smoothing_type(smoothing_type);
+
+        self
+    }
+
+    fn smoothing_type(&self) -> SmoothingType {
+        self.granular_synthesizer.lock().unwrap().smoothing_type()
+    }
+
+    fn set_play_mode(&mut self, play_mode: PlayMode) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_play_mode(play_mode);
+
+        self
+    }
+
+    fn play_mode(&self) -> PlayMode {
+        self.granular_synthesizer.lock().unwrap().play_mode()
+    }
+
+    fn note_on(&mut self, note: u8, velocity: u8) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .note_on(note, velocity);
+
+        self
+    }
+
+    fn note_off(&mut self, note: u8) -> &mut Self {
+        self.granular_synthesizer.lock().unwrap().note_off(note);
+
+        self
+    }
+
+    fn all_notes_off(&mut self) -> &mut Self {
+        self.granular_synthesizer.lock().unwrap().all_notes_off();
+
+        self
+    }
+
+    fn set_root_note(&mut self, root_note: u8) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_root_note(root_note);
+
+        self
+    }
+
+    fn root_note(&self) -> u8 {
+        self.granular_synthesizer.lock().unwrap().root_note()
+    }
+
+    fn set_voice_limit(&mut self, voice_limit: usize) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_voice_limit(voice_limit);
+
+        self
+    }
+
+    fn voice_limit(&self) -> usize {
+        self.granular_synthesizer.lock().unwrap().voice_limit()
+    }
+
+    fn set_adsr(&mut self, adsr: Adsr) -> &mut Self {
+        self.granular_synthesizer.lock().unwrap().set_adsr(adsr);
+
+        self
+    }
+
+    fn adsr(&self) -> Adsr {
+        self.granular_synthesizer.lock().unwrap().adsr()
+    }
+
+    fn set_velocity_sensitivity(
+        &mut self,
+        velocity_sensitivity: impl Into<Percentage>,
+    ) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_velocity_sensitivity(velocity_sensitivity);
+
+        self
+    }
+
+    fn velocity_sensitivity(&self) -> Percentage {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .velocity_sensitivity()
+    }
+
+    fn set_lfo(&mut self, index: usize, lfo: Lfo) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_lfo(index, lfo);
+
+        self
+    }
+
+    fn lfo(&self, index: usize) -> Option<Lfo> {
+        self.granular_synthesizer.lock().unwrap().lfo(index)
+    }
+
+    fn add_modulation_routing(&mut self, routing: ModulationRouting) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .add_modulation_routing(routing);
+
+        self
+    }
+
+    fn remove_modulation_routing(&mut self, index: usize) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .remove_modulation_routing(index);
+
+        self
+    }
+
+    fn modulation_routings(&self) -> Vec<ModulationRouting> {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .modulation_routings()
+    }
+
+    fn set_playhead(&mut self, playhead: impl Into<Percentage>) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_playhead(playhead);
+
+        self
+    }
+
+    fn playhead(&self) -> Percentage {
+        self.granular_synthesizer.lock().unwrap().playhead()
+    }
+
     fn set_sample_rate(&mut self, sample_rate: u32) -> &mut Self {
         self.granular_synthesizer
             .lock()
@@ -108,6 +410,10 @@ impl GranularSynthesizerAction for GranularSynthesizerHandle {
         self
     }
 
+    fn sample_rate(&self) -> u32 {
+        self.granular_synthesizer.lock().unwrap().sample_rate()
+    }
+
     fn grain_len_min(&self) -> Percentage {
         self.granular_synthesizer.lock().unwrap().grain_len_min()
     }
@@ -116,6 +422,45 @@ impl GranularSynthesizerAction for GranularSynthesizerHandle {
         self.granular_synthesizer.lock().unwrap().grain_len_max()
     }
 
+    fn set_grain_len_mode(&mut self, grain_len_mode: GrainLenMode) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_grain_len_mode(grain_len_mode);
+
+        self
+    }
+
+    fn grain_len_mode(&self) -> GrainLenMode {
+        self.granular_synthesizer.lock().unwrap().grain_len_mode()
+    }
+
+    fn set_grain_len_min_ms(&mut self, grain_len_min_ms: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_grain_len_min_ms(grain_len_min_ms);
+
+        self
+    }
+
+    fn grain_len_min_ms(&self) -> f32 {
+        self.granular_synthesizer.lock().unwrap().grain_len_min_ms()
+    }
+
+    fn set_grain_len_max_ms(&mut self, grain_len_max_ms: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_grain_len_max_ms(grain_len_max_ms);
+
+        self
+    }
+
+    fn grain_len_max_ms(&self) -> f32 {
+        self.granular_synthesizer.lock().unwrap().grain_len_max_ms()
+    }
+
     fn refresh_interval(&self) -> u32 {
         self.granular_synthesizer.lock().unwrap().refresh_interval()
     }
@@ -128,6 +473,290 @@ impl GranularSynthesizerAction for GranularSynthesizerHandle {
 
         self
     }
+
+    fn set_pitch_semitones(&mut self, semitones: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_pitch_semitones(semitones);
+
+        self
+    }
+
+    fn pitch_semitones(&self) -> f32 {
+        self.granular_synthesizer.lock().unwrap().pitch_semitones()
+    }
+
+    fn set_pitch_cents(&mut self, cents: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_pitch_cents(cents);
+
+        self
+    }
+
+    fn pitch_cents(&self) -> f32 {
+        self.granular_synthesizer.lock().unwrap().pitch_cents()
+    }
+
+    fn set_pitch_spread(&mut self, spread: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_pitch_spread(spread);
+
+        self
+    }
+
+    fn pitch_spread(&self) -> f32 {
+        self.granular_synthesizer.lock().unwrap().pitch_spread()
+    }
+
+    fn set_pitch_quantizer(&mut self, pitch_quantizer: Option<PitchQuantizer>) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_pitch_quantizer(pitch_quantizer);
+
+        self
+    }
+
+    fn pitch_quantizer(&self) -> Option<PitchQuantizer> {
+        self.granular_synthesizer.lock().unwrap().pitch_quantizer()
+    }
+
+    fn set_interpolation(&mut self, interpolation: Interpolation) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_interpolation(interpolation);
+
+        self
+    }
+
+    fn interpolation(&self) -> Interpolation {
+        self.granular_synthesizer.lock().unwrap().interpolation()
+    }
+
+    fn set_reverse_probability(&mut self, reverse_probability: impl Into<Percentage>) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_reverse_probability(reverse_probability);
+
+        self
+    }
+
+    fn reverse_probability(&self) -> Percentage {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .reverse_probability()
+    }
+
+    fn set_envelope_shape(&mut self, envelope_shape: EnvelopeShape) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_envelope_shape(envelope_shape);
+
+        self
+    }
+
+    fn envelope_shape(&self) -> EnvelopeShape {
+        self.granular_synthesizer.lock().unwrap().envelope_shape()
+    }
+
+    fn set_envelope_skew(&mut self, envelope_skew: impl Into<Percentage>) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_envelope_skew(envelope_skew);
+
+        self
+    }
+
+    fn envelope_skew(&self) -> Percentage {
+        self.granular_synthesizer.lock().unwrap().envelope_skew()
+    }
+
+    fn set_amplitude_min(&mut self, amplitude_min: impl Into<Percentage>) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_amplitude_min(amplitude_min);
+
+        self
+    }
+
+    fn amplitude_min(&self) -> Percentage {
+        self.granular_synthesizer.lock().unwrap().amplitude_min()
+    }
+
+    fn set_amplitude_max(&mut self, amplitude_max: impl Into<Percentage>) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_amplitude_max(amplitude_max);
+
+        self
+    }
+
+    fn amplitude_max(&self) -> Percentage {
+        self.granular_synthesizer.lock().unwrap().amplitude_max()
+    }
+
+    fn set_grain_filter_mode(&mut self, grain_filter_mode: GrainFilterMode) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_grain_filter_mode(grain_filter_mode);
+
+        self
+    }
+
+    fn grain_filter_mode(&self) -> GrainFilterMode {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .grain_filter_mode()
+    }
+
+    fn set_grain_filter_cutoff_min_hz(&mut self, cutoff_min_hz: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_grain_filter_cutoff_min_hz(cutoff_min_hz);
+
+        self
+    }
+
+    fn grain_filter_cutoff_min_hz(&self) -> f32 {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .grain_filter_cutoff_min_hz()
+    }
+
+    fn set_grain_filter_cutoff_max_hz(&mut self, cutoff_max_hz: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_grain_filter_cutoff_max_hz(cutoff_max_hz);
+
+        self
+    }
+
+    fn grain_filter_cutoff_max_hz(&self) -> f32 {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .grain_filter_cutoff_max_hz()
+    }
+
+    fn set_grain_filter_resonance(&mut self, resonance: impl Into<Percentage>) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_grain_filter_resonance(resonance);
+
+        self
+    }
+
+    fn grain_filter_resonance(&self) -> Percentage {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .grain_filter_resonance()
+    }
+
+    fn set_normalize(&mut self, normalize: bool) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_normalize(normalize);
+
+        self
+    }
+
+    fn normalize(&self) -> bool {
+        self.granular_synthesizer.lock().unwrap().normalize()
+    }
+
+    fn set_channel_policy(&mut self, channel_policy: ChannelPolicy) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_channel_policy(channel_policy);
+
+        self
+    }
+
+    fn channel_policy(&self) -> ChannelPolicy {
+        self.granular_synthesizer.lock().unwrap().channel_policy()
+    }
+
+    fn set_pan_center(&mut self, pan_center: f32) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_pan_center(pan_center);
+
+        self
+    }
+
+    fn pan_center(&self) -> f32 {
+        self.granular_synthesizer.lock().unwrap().pan_center()
+    }
+
+    fn set_pan_spread(&mut self, pan_spread: impl Into<Percentage>) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_pan_spread(pan_spread);
+
+        self
+    }
+
+    fn pan_spread(&self) -> Percentage {
+        self.granular_synthesizer.lock().unwrap().pan_spread()
+    }
+
+    fn set_pan_law(&mut self, pan_law: PanLaw) -> &mut Self {
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .set_pan_law(pan_law);
+
+        self
+    }
+
+    fn pan_law(&self) -> PanLaw {
+        self.granular_synthesizer.lock().unwrap().pan_law()
+    }
+
+    fn apply_preset(&mut self, preset: &Preset) -> &mut Self {
+        // a single lock, so the audio thread never renders a half-applied preset
+        self.granular_synthesizer
+            .lock()
+            .unwrap()
+            .apply_preset(preset);
+
+        self
+    }
+
+    fn preset(&self) -> Preset {
+        self.granular_synthesizer.lock().unwrap().preset()
+    }
+}
+
+impl GranularSynthesizerHandle {
+    /// Returns a copy of the synthesizer's current state (including any playing grains),
+    /// which can be rendered offline without interrupting real-time playback
+    pub fn snapshot(&self) -> GranularSynthesizer {
+        self.granular_synthesizer.lock().unwrap().clone()
+    }
 }
 
 impl Default for GranularSynthesizerHandle {
@@ -1,16 +1,20 @@
 use super::{
     audio_output_action::AudioOutputAction, audio_recorder_handle::AudioRecorderHandle,
     buffer_selection_action::BufferSelectionAction, decode, gain_action::GainAction,
-    play_status::PlayStatus, play_status_action::PlayStatusAction,
+    global_defaults, play_status::PlayStatus, play_status_action::PlayStatusAction,
     recording_status::RecordingStatus, recording_status_action::RecordingStatusAction,
     recording_status_handle::RecordingStatusHandle,
 };
 use crate::{
     audio::stream_handle::StreamHandle,
-    components::controls_select_buffer::DEFAULT_AUDIO_FILE,
+    components::controls_select_buffer::{AUDIO_FILES, DEFAULT_AUDIO_FILE},
     state::{app_action::AppAction, app_state::AppState},
 };
-use audio::{granular_synthesizer_action::GranularSynthesizerAction, mixdown::mixdown};
+use audio::{
+    granular_synthesizer_action::GranularSynthesizerAction,
+    smoothed_value::{SmoothedValue, SmoothingType},
+    source_buffer::SourceBuffer,
+};
 use cpal::{
     traits::{DeviceTrait, HostTrait, StreamTrait},
     Stream, StreamConfig,
@@ -19,14 +23,20 @@ use gloo_net::http::Request;
 use std::sync::Arc;
 use yew::UseReducerHandle;
 
-/// Converts default mp3 file to raw audio sample data
-async fn load_default_buffer(app_state_handle: UseReducerHandle<AppState>) -> Arc<Vec<f32>> {
+/// Converts the initially selected mp3 file (the default file, unless another was shared in the url)
+/// to raw audio sample data
+async fn load_default_buffer(app_state_handle: UseReducerHandle<AppState>) -> Arc<SourceBuffer> {
     let audio_context =
         web_sys::AudioContext::new().expect("Browser should have AudioContext implemented");
+    let file_name = app_state_handle
+        .audio_file_index
+        .and_then(|index| AUDIO_FILES.get(index))
+        .copied()
+        .unwrap_or(DEFAULT_AUDIO_FILE);
 
     // audio files are copied into static director for web (same directory as source wasm file)
     // fetch a default audio file at initialization time
-    let mp3_file_bytes = Request::get(&format!("./{}", DEFAULT_AUDIO_FILE))
+    let mp3_file_bytes = Request::get(&format!("./{}", file_name))
         .send()
         .await
         .unwrap()
@@ -37,35 +47,37 @@ async fn load_default_buffer(app_state_handle: UseReducerHandle<AppState>) -> Ar
     let audio_buffer = decode::decode_bytes(&audio_context, &mp3_file_bytes)
         .await
         .unwrap();
-    let mp3_source_data = Arc::new(audio_buffer.get_channel_data(0).unwrap());
+    let mp3_source_data = Arc::new(decode::audio_buffer_to_source_buffer(&audio_buffer).unwrap());
     app_state_handle.dispatch(AppAction::SetBuffer(Arc::clone(&mp3_source_data)));
 
     mp3_source_data
 }
 
 /// This function is called periodically to write audio data into an audio output buffer
+///
+/// `block` is scratch memory that is reused across calls, so that it only has to be
+/// (re)allocated when the audio output buffer grows.
 fn write_data<T>(
     output: &mut [T],
     channels: usize,
-    next_sample: &mut dyn FnMut() -> Vec<f32>,
+    block: &mut Vec<f32>,
+    process_block: &mut dyn FnMut(&mut [f32], usize),
     recording_status_handle: RecordingStatusHandle,
     mut audio_recorder_handle: AudioRecorderHandle,
 ) where
     T: cpal::Sample,
 {
-    for frame in output.chunks_mut(channels) {
-        let output_samples = next_sample();
-
-        // clone audio data into a recording buffer
-        let is_recording = recording_status_handle.get() == RecordingStatus::Recording;
-        if is_recording {
-            let output_samples = output_samples.clone();
-            audio_recorder_handle.extend(output_samples)
-        }
+    block.resize(output.len(), 0.0);
+    process_block(block, channels);
 
-        for (i, sample) in frame.iter_mut().enumerate() {
-            *sample = cpal::Sample::from::<f32>(&output_samples[i]);
-        }
+    // copy audio data into a recording buffer
+    let is_recording = recording_status_handle.get() == RecordingStatus::Recording;
+    if is_recording {
+        audio_recorder_handle.extend(block.iter().copied())
+    }
+
+    for (sample, output_sample) in output.iter_mut().zip(block.iter()) {
+        *sample = cpal::Sample::from::<f32>(output_sample);
     }
 }
 
@@ -91,16 +103,30 @@ where
     let gain_handle = app_state_handle.gain_handle.clone();
     let status = app_state_handle.play_status_handle.clone();
     let mut granular_synthesizer_handle = app_state_handle.granular_synthesizer_handle.clone();
+    let mut effect_chain_handle = app_state_handle.effect_chain_handle.clone();
     let mut audio_output_handle = app_state_handle.audio_output_handle.clone();
 
     // make sure granular synthesizer's internal state is current with audio context state
     granular_synthesizer_handle.set_sample_rate(output_sample_rate);
+    effect_chain_handle.set_sample_rate(output_sample_rate);
+
+    // ramps the global gain toward its latest value, so that moving the gain slider doesn't click
+    let mut gain = SmoothedValue::new(
+        gain_handle.get(),
+        granular_synthesizer_handle.smoothing_ms(),
+        SmoothingType::Exponential,
+        output_sample_rate,
+    );
 
-    // Called for every audio frame to generate appropriate sample
-    let mut next_value = move || {
+    // reused across audio callbacks, so that the visualization frame doesn't allocate
+    let mut visualization_frame = Vec::with_capacity(global_defaults::MAX_NUM_CHANNELS as usize);
+
+    // Called for every audio callback to render a block of interleaved samples
+    let mut process_block = move |block: &mut [f32], channels: usize| {
         // if paused, do not process any audio, just return silence
         if let PlayStatus::Pause = status.get() {
-            return vec![0.0; output_num_channels];
+            block.fill(0.0);
+            return;
         }
 
         // always keep granular_synth up-to-date with buffer selection from UI
@@ -109,29 +135,35 @@ where
             .set_selection_start(selection_start)
             .set_selection_end(selection_end);
 
-        // get next frame from granular synth
-        let frame = granular_synthesizer_handle.next_frame();
+        // render the block from granular synth, mixed down to number of outputs
+        granular_synthesizer_handle.process_block(block, channels);
+
+        // run the mixed-down output through any effects
+        effect_chain_handle.process_block(block, channels);
 
         // copy up-to-date audio output information into context for
         // reference in audio output visualization
-        audio_output_handle.add_frame(frame.clone());
-
-        // mix multi-channel down to number of outputs
-        let output_frame = mixdown(&frame, output_num_channels as u32);
+        granular_synthesizer_handle.copy_last_frame(&mut visualization_frame);
+        audio_output_handle.add_frame(&visualization_frame);
 
-        // gate final output with global gain
-        let gain = gain_handle.get();
-        let output_frame: Vec<f32> = output_frame
-            .into_iter()
-            .map(|output| output * gain)
-            .collect();
-
-        output_frame
+        // gate final output with global gain (using the same ramp time as the synthesizer)
+        let smoothing_ms = granular_synthesizer_handle.smoothing_ms();
+        if smoothing_ms != gain.ramp_ms() {
+            gain.set_ramp_ms(smoothing_ms);
+        }
+        gain.set_target(gain_handle.get());
+        for frame in block.chunks_mut(channels) {
+            let gain = gain.next_value();
+            for sample in frame {
+                *sample *= gain;
+            }
+        }
     };
 
     let err_fn = |err| eprintln!("an error occurred on stream: {}", err);
     let recording_status_handle = app_state_handle.recording_status_handle.clone();
     let audio_recorder_handle = app_state_handle.audio_recorder_handle.clone();
+    let mut block = Vec::new();
 
     let stream = device.build_output_stream(
         stream_config,
@@ -139,7 +171,8 @@ where
             write_data(
                 data,
                 output_num_channels,
-                &mut next_value,
+                &mut block,
+                &mut process_block,
                 recording_status_handle.clone(),
                 audio_recorder_handle.clone(),
             )
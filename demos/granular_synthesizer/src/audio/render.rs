@@ -0,0 +1,63 @@
+use super::gain_action::GainAction;
+use crate::{
+    state::app_state::AppState,
+    utils::{download, wav},
+};
+use audio::{
+    granular_synthesizer_action::GranularSynthesizerAction,
+    offline_render::{self, RenderLength},
+};
+use js_sys::Promise;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::window;
+
+/// The shortest and longest durations (in seconds) that can be rendered offline
+pub const RENDER_SECONDS_MIN: f32 = 1.0;
+
+pub const RENDER_SECONDS_MAX: f32 = 600.0;
+
+pub const DEFAULT_RENDER_SECONDS: f32 = 30.0;
+
+/// Lets the browser handle events / repaint before continuing
+async fn yield_to_browser() {
+    let promise = Promise::new(&mut |resolve, _| {
+        window()
+            .unwrap()
+            .set_timeout_with_callback(&resolve)
+            .unwrap();
+    });
+    JsFuture::from(promise).await.unwrap();
+}
+
+/// Renders `seconds` of audio from a copy of the current synthesizer and effects (faster than real-time)
+/// and downloads it as a .wav file.
+///
+/// Audio is rendered one second at a time, yielding to the browser in between,
+/// so that the page stays responsive during long renders.
+pub async fn render_and_download(app_state: &AppState, seconds: f32) {
+    let mut granular_synthesizer = app_state.granular_synthesizer_handle.snapshot();
+    let mut effect_chain = app_state.effect_chain_handle.snapshot();
+    let sample_rate = granular_synthesizer.sample_rate();
+    let num_channels = (app_state.num_channels as usize).max(1);
+    let gain = app_state.gain_handle.get();
+
+    let num_frames = RenderLength::Seconds(seconds).num_frames(sample_rate);
+    let mut samples = vec![0.0; num_frames * num_channels];
+    for chunk in samples.chunks_mut((sample_rate as usize).max(1) * num_channels) {
+        offline_render::render_into_with_effects(
+            &mut granular_synthesizer,
+            &mut effect_chain,
+            chunk,
+            num_channels,
+        );
+        yield_to_browser().await;
+    }
+
+    // match the level of real-time output, which is scaled by the global gain
+    for sample in &mut samples {
+        *sample *= gain;
+    }
+
+    let wav_bytes = wav::encode_wav(&samples, num_channels as u16, sample_rate);
+    download::download_bytes(wav_bytes, "render.wav");
+}
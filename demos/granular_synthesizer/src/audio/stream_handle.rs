@@ -1,12 +1,13 @@
 use cpal::Stream;
+use std::cell::RefCell;
 use std::fmt::Debug;
-use std::sync::{Arc, Mutex};
+use std::rc::Rc;
 use uuid::Uuid;
 
 /// A wrapper around `cpal`'s Stream type for implementing `PartialEq`, etc.
 #[derive(Clone, Default)]
 pub struct StreamHandle {
-    stream: Arc<Mutex<Option<Stream>>>,
+    stream: Rc<RefCell<Option<Stream>>>,
     uuid: Uuid,
 }
 
@@ -29,12 +30,12 @@ impl Debug for StreamHandle {
 impl StreamHandle {
     pub fn new(stream: Stream) -> Self {
         StreamHandle {
-            stream: Arc::new(Mutex::new(Some(stream))),
+            stream: Rc::new(RefCell::new(Some(stream))),
             uuid: Uuid::new_v4(),
         }
     }
 
     pub fn take(&self) -> Option<Stream> {
-        self.stream.lock().unwrap().take()
+        self.stream.borrow_mut().take()
     }
 }
@@ -4,19 +4,68 @@ pub mod buffer_container;
 pub mod buffer_sample_bars_canvas;
 pub mod buffer_selection_visualizer;
 pub mod button;
+pub mod controls_amplitude_max;
+pub mod controls_amplitude_min;
+pub mod controls_attack;
+pub mod controls_channel_policy;
 pub mod controls_container;
+pub mod controls_decay;
+pub mod controls_delay;
+pub mod controls_delay_feedback;
+pub mod controls_delay_mix;
+pub mod controls_delay_ping_pong;
+pub mod controls_delay_sync;
+pub mod controls_delay_tempo;
+pub mod controls_delay_time;
+pub mod controls_delay_tone;
 pub mod controls_density;
 pub mod controls_download_audio;
+pub mod controls_effect_chain;
 pub mod controls_enable_audio;
+pub mod controls_envelope_parameter;
+pub mod controls_envelope_shape;
+pub mod controls_envelope_skew;
 pub mod controls_gain;
+pub mod controls_grain_filter_cutoff_max;
+pub mod controls_grain_filter_cutoff_min;
+pub mod controls_grain_filter_mode;
+pub mod controls_grain_filter_resonance;
+pub mod controls_grain_len_mode;
+pub mod controls_load_preset;
 pub mod controls_max_len;
 pub mod controls_min_len;
+pub mod controls_modulation;
+pub mod controls_normalize;
+pub mod controls_pan_center;
+pub mod controls_pan_law;
+pub mod controls_pan_spread;
+pub mod controls_play_mode;
 pub mod controls_play_status;
+pub mod controls_position_mode;
 pub mod controls_recording_status;
 pub mod controls_refresh_interval;
+pub mod controls_release;
+pub mod controls_render_audio;
+pub mod controls_render_seconds;
 pub mod controls_reset;
+pub mod controls_reverse_probability;
+pub mod controls_root_note;
+pub mod controls_save_preset;
+pub mod controls_scan_speed;
+pub mod controls_scheduler_jitter;
+pub mod controls_scheduler_mode;
+pub mod controls_seed;
 pub mod controls_select_buffer;
+pub mod controls_smoothing;
+pub mod controls_smoothing_type;
+pub mod controls_spray;
+pub mod controls_sustain;
 pub mod controls_upload_buffer;
+pub mod controls_velocity_sensitivity;
+pub mod controls_voice_limit;
 pub mod input_range;
+pub mod input_select;
+pub mod input_text;
 pub mod keyboard_listener;
 pub mod loading_indicator;
+pub mod midi_listener;
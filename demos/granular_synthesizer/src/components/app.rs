@@ -1,6 +1,7 @@
 use crate::{components::{
     controls_container::ControlsContainer,
     keyboard_listener::KeyboardListener,
+    midi_listener::MidiListener,
 }, state::app_context::AppContext};
 use yew::{function_component, html, prelude::*};
 
@@ -9,7 +10,9 @@ pub fn app() -> Html {
     html! {
         <ContextProvider<AppContext> context={AppContext::default()}>
             <KeyboardListener>
-                <ControlsContainer />
+                <MidiListener>
+                    <ControlsContainer />
+                </MidiListener>
             </KeyboardListener>
         </ContextProvider<AppContext>>
     }
@@ -30,7 +30,7 @@ pub fn audio_output_visualization() -> Html {
     let hidden_class = (buffer_handle.get_data().is_empty()
         || !app_context.state_handle.audio_initialized
         || app_context.state_handle.play_status_handle.get() == PlayStatus::Pause)
-        .then(|| "hidden");
+        .then_some("hidden");
 
     use_effect_with_deps(
         {
@@ -1,6 +1,6 @@
 use crate::state::app_context::{AppContext, AppContextError};
 
-use std::sync::Arc;
+use audio::source_buffer::SourceBuffer;
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 use yew::{function_component, html, prelude::*};
@@ -11,24 +11,27 @@ const BUFFER_SAMPLE_BARS_CANVAS_NUM_DATA_POINTS: usize = 100;
 /// Analyzes a buffer of raw audio sample data into ```NUM_AUDIO_DATA_POINTS``` number of samples,
 /// where each sample represents the peak from a chunk of the original audio.
 ///
+/// For multi-channel buffers, each sample is the peak across all of the buffer's channels.
+///
 /// Formatted as 0.0 -> 1.0
-pub fn get_buffer_maxes_for_canvas(buffer: &Arc<Vec<f32>>) -> Vec<f32> {
+pub fn get_buffer_maxes_for_canvas(buffer: &SourceBuffer) -> Vec<f32> {
     if buffer.is_empty() {
         return Vec::new();
     }
 
     // buffer has audio data: get averages from the buffer
-    let iteration_group_size = buffer.len() / BUFFER_SAMPLE_BARS_CANVAS_NUM_DATA_POINTS;
-    let maxes: Vec<f32> = buffer
-        .chunks(iteration_group_size)
-        .map(|samples| {
-            samples
+    let iteration_group_size =
+        (buffer.num_frames() / BUFFER_SAMPLE_BARS_CANVAS_NUM_DATA_POINTS).max(1);
+    let num_maxes = buffer.channel(0).chunks(iteration_group_size).len();
+    let mut maxes = vec![0.0; num_maxes];
+    for channel in buffer.channels() {
+        for (max, samples) in maxes.iter_mut().zip(channel.chunks(iteration_group_size)) {
+            *max = samples
                 .iter()
                 .map(|sample| f32::abs(*sample))
-                .reduce(f32::max)
-                .unwrap()
-        })
-        .collect();
+                .fold(*max, f32::max);
+        }
+    }
 
     maxes
 }
@@ -56,7 +59,7 @@ pub fn buffer_sample_bars_canvas() -> Html {
     let canvas_ref = use_node_ref();
     let hidden_class = (buffer_handle.get_data().is_empty()
         || !app_context.state_handle.audio_initialized)
-        .then(|| "hidden");
+        .then_some("hidden");
 
     use_effect_with_deps(
         {
@@ -0,0 +1,45 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+#[function_component(ControlsAmplitudeMax)]
+pub fn controls_amplitude_max() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let amplitude_max_input_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let amplitude_max = app_context.state_handle.amplitude_max.get();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let amplitude_max = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetAmplitudeMax(amplitude_max));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="max\namp"
+            id="controls-amplitude-max-input"
+            min="0.0"
+            max="1.0"
+            step="0.01"
+            oninput={handle_input}
+            value={amplitude_max.to_string()}
+            disabled={amplitude_max_input_disabled}
+        />
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+#[function_component(ControlsAmplitudeMin)]
+pub fn controls_amplitude_min() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let amplitude_min_input_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let amplitude_min = app_context.state_handle.amplitude_min.get();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let amplitude_min = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetAmplitudeMin(amplitude_min));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="min\namp"
+            id="controls-amplitude-min-input"
+            min="0.0"
+            max="1.0"
+            step="0.01"
+            oninput={handle_input}
+            value={amplitude_min.to_string()}
+            disabled={amplitude_min_input_disabled}
+        />
+    }
+}
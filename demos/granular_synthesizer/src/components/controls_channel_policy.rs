@@ -0,0 +1,86 @@
+use crate::{
+    components::input_select::InputSelect,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::channel_policy::ChannelPolicy;
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+const FOLLOW_PAN_OPTION: &str = "pan";
+
+const RANDOM_OPTION: &str = "random";
+
+/// Fixed channels are displayed starting from 1 (rather than 0)
+fn channel_policy_to_option(channel_policy: ChannelPolicy) -> AttrValue {
+    match channel_policy {
+        ChannelPolicy::FollowPan => AttrValue::from(FOLLOW_PAN_OPTION),
+        ChannelPolicy::Random => AttrValue::from(RANDOM_OPTION),
+        ChannelPolicy::Fixed(channel) => AttrValue::from((channel + 1).to_string()),
+    }
+}
+
+fn option_to_channel_policy(option: &str) -> Option<ChannelPolicy> {
+    match option {
+        FOLLOW_PAN_OPTION => Some(ChannelPolicy::FollowPan),
+        RANDOM_OPTION => Some(ChannelPolicy::Random),
+        channel => channel
+            .parse::<usize>()
+            .ok()
+            .and_then(|channel| channel.checked_sub(1))
+            .map(ChannelPolicy::Fixed),
+    }
+}
+
+#[function_component(ControlsChannelPolicy)]
+pub fn controls_channel_policy() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let select_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let channel_policy = app_context.state_handle.channel_policy;
+    let num_buffer_channels = app_context
+        .state_handle
+        .buffer_handle
+        .get_data()
+        .num_channels();
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let option = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(channel_policy) = option_to_channel_policy(&option) {
+                state_handle.dispatch(AppAction::SetChannelPolicy(channel_policy));
+            }
+        })
+    };
+
+    // make sure the currently selected channel is always listed,
+    // even if the current buffer has fewer channels
+    let num_channel_options = match channel_policy {
+        ChannelPolicy::Fixed(channel) => num_buffer_channels.max(channel + 1),
+        _ => num_buffer_channels.max(1),
+    };
+
+    let options: Vec<AttrValue> = [ChannelPolicy::FollowPan, ChannelPolicy::Random]
+        .into_iter()
+        .chain((0..num_channel_options).map(ChannelPolicy::Fixed))
+        .map(channel_policy_to_option)
+        .collect();
+
+    html! {
+        <InputSelect
+            label="Source channel"
+            id="controls-channel-policy-select"
+            options={options}
+            value={channel_policy_to_option(channel_policy)}
+            onchange={handle_change}
+            disabled={select_disabled}
+        />
+    }
+}
@@ -1,14 +1,40 @@
 use crate::{
     components::{
         audio_output_visualization::AudioOutputVisualization, buffer_container::BufferContainer,
-        controls_density::ControlsDensity, controls_download_audio::ControlsDownloadAudio,
-        controls_enable_audio::ControlsEnableAudio, controls_gain::ControlsGain,
+        controls_amplitude_max::ControlsAmplitudeMax, controls_amplitude_min::ControlsAmplitudeMin,
+        controls_attack::ControlsAttack, controls_channel_policy::ControlsChannelPolicy,
+        controls_decay::ControlsDecay, controls_delay::ControlsDelay,
+        controls_delay_feedback::ControlsDelayFeedback, controls_delay_mix::ControlsDelayMix,
+        controls_delay_ping_pong::ControlsDelayPingPong, controls_delay_sync::ControlsDelaySync,
+        controls_delay_tempo::ControlsDelayTempo, controls_delay_time::ControlsDelayTime,
+        controls_delay_tone::ControlsDelayTone, controls_density::ControlsDensity,
+        controls_download_audio::ControlsDownloadAudio, controls_effect_chain::ControlsEffectChain,
+        controls_enable_audio::ControlsEnableAudio,
+        controls_envelope_parameter::ControlsEnvelopeParameter,
+        controls_envelope_shape::ControlsEnvelopeShape,
+        controls_envelope_skew::ControlsEnvelopeSkew, controls_gain::ControlsGain,
+        controls_grain_filter_cutoff_max::ControlsGrainFilterCutoffMax,
+        controls_grain_filter_cutoff_min::ControlsGrainFilterCutoffMin,
+        controls_grain_filter_mode::ControlsGrainFilterMode,
+        controls_grain_filter_resonance::ControlsGrainFilterResonance,
+        controls_grain_len_mode::ControlsGrainLenMode, controls_load_preset::ControlsLoadPreset,
         controls_max_len::ControlsMaxLen, controls_min_len::ControlsMinLen,
-        controls_play_status::ControlsPlayStatus,
+        controls_modulation::ControlsModulation, controls_normalize::ControlsNormalize,
+        controls_pan_center::ControlsPanCenter, controls_pan_law::ControlsPanLaw,
+        controls_pan_spread::ControlsPanSpread, controls_play_mode::ControlsPlayMode,
+        controls_play_status::ControlsPlayStatus, controls_position_mode::ControlsPositionMode,
         controls_recording_status::ControlsRecordingStatus,
-        controls_refresh_interval::ControlsRefreshInterval, controls_reset::ControlsReset,
-        controls_select_buffer::ControlsSelectBuffer, controls_upload_buffer::ControlsUploadBuffer,
-        loading_indicator::LoadingIndicator,
+        controls_refresh_interval::ControlsRefreshInterval, controls_release::ControlsRelease,
+        controls_render_audio::ControlsRenderAudio, controls_render_seconds::ControlsRenderSeconds,
+        controls_reset::ControlsReset, controls_reverse_probability::ControlsReverseProbability,
+        controls_root_note::ControlsRootNote, controls_save_preset::ControlsSavePreset,
+        controls_scan_speed::ControlsScanSpeed, controls_scheduler_jitter::ControlsSchedulerJitter,
+        controls_scheduler_mode::ControlsSchedulerMode, controls_seed::ControlsSeed,
+        controls_select_buffer::ControlsSelectBuffer, controls_smoothing::ControlsSmoothing,
+        controls_smoothing_type::ControlsSmoothingType, controls_spray::ControlsSpray,
+        controls_sustain::ControlsSustain, controls_upload_buffer::ControlsUploadBuffer,
+        controls_velocity_sensitivity::ControlsVelocitySensitivity,
+        controls_voice_limit::ControlsVoiceLimit, loading_indicator::LoadingIndicator,
     },
     state::app_context::{AppContext, AppContextError},
 };
@@ -20,7 +46,7 @@ pub fn controls_container() -> Html {
     let keyboard_user_class = app_context
         .state_handle
         .is_keyboard_user
-        .then(|| "keyboard-user");
+        .then_some("keyboard-user");
 
     html! {
         <div class={classes!("controls-container", keyboard_user_class)}>
@@ -31,22 +57,72 @@ pub fn controls_container() -> Html {
                 <ControlsReset />
                 <ControlsRecordingStatus />
                 <ControlsDownloadAudio />
+                <ControlsRenderAudio />
+                <ControlsSavePreset />
+                <ControlsLoadPreset />
             </div>
             <div class="grid-slider-container">
                 <ControlsGain />
+                <ControlsDelay />
+                <ControlsDelayMix />
+                <ControlsDelayTime />
+                <ControlsDelaySync />
+                <ControlsDelayTempo />
+                <ControlsDelayFeedback />
+                <ControlsDelayTone />
+                <ControlsDelayPingPong />
                 <ControlsDensity />
                 <ControlsMinLen />
                 <ControlsMaxLen />
                 <ControlsRefreshInterval />
+                <ControlsSchedulerJitter />
+                <ControlsScanSpeed />
+                <ControlsSpray />
+                <ControlsSmoothing />
+                <ControlsReverseProbability />
+                <ControlsEnvelopeParameter />
+                <ControlsEnvelopeSkew />
+                <ControlsAmplitudeMin />
+                <ControlsAmplitudeMax />
+                <ControlsGrainFilterCutoffMin />
+                <ControlsGrainFilterCutoffMax />
+                <ControlsGrainFilterResonance />
+                <ControlsPanCenter />
+                <ControlsPanSpread />
+                <ControlsRootNote />
+                <ControlsVoiceLimit />
+                <ControlsVelocitySensitivity />
+                <ControlsAttack />
+                <ControlsDecay />
+                <ControlsSustain />
+                <ControlsRelease />
             </div>
             <div class="grid-select-container">
                 <ControlsSelectBuffer />
                 <ControlsUploadBuffer />
+                <ControlsGrainLenMode />
+                <ControlsEnvelopeShape />
+                <ControlsGrainFilterMode />
+                <ControlsChannelPolicy />
+                <ControlsPanLaw />
+                <ControlsSchedulerMode />
+                <ControlsPositionMode />
+                <ControlsPlayMode />
+                <ControlsNormalize />
+                <ControlsSmoothingType />
+                <ControlsSeed />
+                <ControlsRenderSeconds />
             </div>
             <div class="grid-buffer-container">
                 <AudioOutputVisualization />
                 <BufferContainer />
             </div>
+            <div class="grid-modulation-container">
+                <ControlsModulation />
+            </div>
+            <div class="grid-effect-chain-container">
+                <ControlsEffectChain />
+            </div>
         </div>
     }
 }
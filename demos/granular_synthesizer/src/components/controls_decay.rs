@@ -0,0 +1,58 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{adsr::Adsr, note_voice::PlayMode};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// How long (in milliseconds) each note's envelope takes to fall to the sustain level.
+///
+/// The slider is square-root scaled, so that short times get most of its range.
+#[function_component(ControlsDecay)]
+pub fn controls_decay() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    // notes are only played in notes mode
+    let decay_input_disabled = app_context.state_handle.get_are_audio_controls_disabled()
+        || app_context.state_handle.play_mode != PlayMode::Notes;
+    let adsr = app_context.state_handle.adsr;
+    let decay = adsr.decay_ms.sqrt();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let value = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number();
+            let decay_ms = (value * value) as f32;
+            state_handle.dispatch(AppAction::SetAdsr(Adsr::new(
+                adsr.attack_ms,
+                decay_ms,
+                adsr.sustain,
+                adsr.release_ms,
+            )));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="dec"
+            id="controls-decay-input"
+            min={Adsr::TIME_MS_MIN.sqrt().to_string()}
+            max={Adsr::TIME_MS_MAX.sqrt().to_string()}
+            step="0.1"
+            oninput={handle_input}
+            value={decay.to_string()}
+            disabled={decay_input_disabled}
+        />
+    }
+}
@@ -0,0 +1,51 @@
+use super::button::ButtonVariant;
+use crate::{
+    components::button::Button,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use yew::{function_component, html, prelude::*};
+
+/// Turns the delay in the effect chain on and off
+#[function_component(ControlsDelay)]
+pub fn controls_delay() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let button_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let enabled = app_context
+        .state_handle
+        .effect_chain_handle
+        .delay_settings()
+        .unwrap_or_default()
+        .enabled;
+
+    let handle_click = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |_: MouseEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+            state_handle.dispatch(AppAction::SetDelayEnabled(!enabled));
+        })
+    };
+
+    let variant = if enabled {
+        ButtonVariant::Pressed
+    } else {
+        ButtonVariant::Unpressed
+    };
+
+    html! {
+        <Button
+            aria_label="toggle delay"
+            class="controls-delay"
+            onclick={handle_click}
+            disabled={button_disabled}
+            variant={variant}
+        >
+            {"delay"}
+        </Button>
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::delay::Delay;
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// Sets how much of each delay repeat is fed back into the delay
+#[function_component(ControlsDelayFeedback)]
+pub fn controls_delay_feedback() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let delay = app_context
+        .state_handle
+        .effect_chain_handle
+        .delay_settings()
+        .unwrap_or_default();
+    let input_disabled =
+        app_context.state_handle.get_are_audio_controls_disabled() || !delay.enabled;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let feedback = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetDelayFeedback(feedback));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="delay feedback"
+            id="controls-delay-feedback-input"
+            min={Delay::FEEDBACK.min.to_string()}
+            max={Delay::FEEDBACK.max.to_string()}
+            step="0.01"
+            oninput={handle_input}
+            value={delay.feedback.to_string()}
+            disabled={input_disabled}
+        />
+    }
+}
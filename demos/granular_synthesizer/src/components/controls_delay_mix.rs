@@ -0,0 +1,49 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// Blends between the dry output and the delay repeats
+#[function_component(ControlsDelayMix)]
+pub fn controls_delay_mix() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let delay = app_context
+        .state_handle
+        .effect_chain_handle
+        .delay_settings()
+        .unwrap_or_default();
+    let input_disabled =
+        app_context.state_handle.get_are_audio_controls_disabled() || !delay.enabled;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let mix = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetDelayMix(mix));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="delay dry / wet"
+            id="controls-delay-mix-input"
+            step="0.01"
+            oninput={handle_input}
+            value={delay.mix.to_string()}
+            disabled={input_disabled}
+        />
+    }
+}
@@ -0,0 +1,52 @@
+use super::button::ButtonVariant;
+use crate::{
+    components::button::Button,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use yew::{function_component, html, prelude::*};
+
+/// Bounces the delay repeats between the left and right channels
+#[function_component(ControlsDelayPingPong)]
+pub fn controls_delay_ping_pong() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let delay = app_context
+        .state_handle
+        .effect_chain_handle
+        .delay_settings()
+        .unwrap_or_default();
+    let button_disabled =
+        app_context.state_handle.get_are_audio_controls_disabled() || !delay.enabled;
+    let ping_pong = delay.ping_pong;
+
+    let handle_click = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |_: MouseEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+            state_handle.dispatch(AppAction::SetDelayPingPong(!ping_pong));
+        })
+    };
+
+    let variant = if ping_pong {
+        ButtonVariant::Pressed
+    } else {
+        ButtonVariant::Unpressed
+    };
+
+    html! {
+        <Button
+            aria_label="toggle delay ping-pong"
+            class="controls-delay-ping-pong"
+            onclick={handle_click}
+            disabled={button_disabled}
+            variant={variant}
+        >
+            {"ping-pong"}
+        </Button>
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    components::input_select::InputSelect,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{delay::Delay, note_division::NoteDivision};
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+/// Syncs the delay time to a note division at the delay's tempo
+#[function_component(ControlsDelaySync)]
+pub fn controls_delay_sync() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let delay = app_context
+        .state_handle
+        .effect_chain_handle
+        .delay_settings()
+        .unwrap_or_default();
+    let select_disabled =
+        app_context.state_handle.get_are_audio_controls_disabled() || !delay.enabled;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let value = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            state_handle.dispatch(AppAction::SetDelayDivision(NoteDivision::from_name(&value)));
+        })
+    };
+
+    // "off" (which uses the delay time slider instead), followed by every note division
+    let options: Vec<AttrValue> = Delay::SYNC
+        .options
+        .iter()
+        .map(|&option| AttrValue::from(option))
+        .collect();
+
+    html! {
+        <InputSelect
+            label="Delay sync"
+            id="controls-delay-sync-select"
+            options={options}
+            value={delay.division.map_or(Delay::SYNC_OFF, |division| division.name())}
+            onchange={handle_change}
+            disabled={select_disabled}
+        />
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::delay::Delay;
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// Sets the tempo that a synced delay time follows
+#[function_component(ControlsDelayTempo)]
+pub fn controls_delay_tempo() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let delay = app_context
+        .state_handle
+        .effect_chain_handle
+        .delay_settings()
+        .unwrap_or_default();
+    let input_disabled = app_context.state_handle.get_are_audio_controls_disabled()
+        || !delay.enabled
+        || delay.division.is_none();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let bpm = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetDelayBpm(bpm));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="delay tempo (bpm)"
+            id="controls-delay-tempo-input"
+            min={Delay::BPM.min.to_string()}
+            max={Delay::BPM.max.to_string()}
+            step="1"
+            oninput={handle_input}
+            value={delay.bpm.to_string()}
+            disabled={input_disabled}
+        />
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::delay::Delay;
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// Sets the delay time (used while tempo sync is off)
+#[function_component(ControlsDelayTime)]
+pub fn controls_delay_time() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let delay = app_context
+        .state_handle
+        .effect_chain_handle
+        .delay_settings()
+        .unwrap_or_default();
+    let input_disabled = app_context.state_handle.get_are_audio_controls_disabled()
+        || !delay.enabled
+        || delay.division.is_some();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let time_ms = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetDelayTimeMs(time_ms));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="delay time (ms)"
+            id="controls-delay-time-input"
+            min={Delay::TIME_MS.min.to_string()}
+            max={Delay::TIME_MS.max.to_string()}
+            step="1"
+            oninput={handle_input}
+            value={delay.time_ms.to_string()}
+            disabled={input_disabled}
+        />
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::delay::Delay;
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// Sets the cutoff of the low-pass filter in the delay's feedback path (on a logarithmic slider)
+#[function_component(ControlsDelayTone)]
+pub fn controls_delay_tone() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let delay = app_context
+        .state_handle
+        .effect_chain_handle
+        .delay_settings()
+        .unwrap_or_default();
+    let input_disabled =
+        app_context.state_handle.get_are_audio_controls_disabled() || !delay.enabled;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let position = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetDelayToneHz(10.0_f32.powf(position)));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="delay tone (Hz)"
+            id="controls-delay-tone-input"
+            min={Delay::TONE_HZ.min.log10().to_string()}
+            max={Delay::TONE_HZ.max.log10().to_string()}
+            step="0.001"
+            oninput={handle_input}
+            value={delay.tone_hz.log10().to_string()}
+            disabled={input_disabled}
+        />
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{
+    components::{
+        button::{Button, ButtonVariant},
+        input_range::InputRange,
+        input_select::InputSelect,
+    },
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{
+    effect::{EffectKind, EffectParameter},
+    effect_chain::EffectChain,
+};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+/// Converts a parameter's value to the position of its slider
+fn to_slider(parameter: &EffectParameter, value: f32) -> f32 {
+    if parameter.logarithmic {
+        value.log10()
+    } else {
+        value
+    }
+}
+
+/// Converts the position of a parameter's slider to the parameter's value
+fn from_slider(parameter: &EffectParameter, position: f32) -> f32 {
+    if parameter.logarithmic {
+        10.0_f32.powf(position)
+    } else {
+        position
+    }
+}
+
+/// Adds, removes, reorders, and edits the effects that are applied to the synthesizer's output.
+///
+/// Effects are applied from top to bottom.
+#[function_component(ControlsEffectChain)]
+pub fn controls_effect_chain() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let controls_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let summaries = app_context.state_handle.effect_chain_handle.summaries();
+    let chain_full = summaries.len() >= EffectChain::MAX_EFFECTS;
+    let num_effects = summaries.len();
+
+    let new_effect_kind = use_state(EffectKind::default);
+
+    // wraps an action so that it's only dispatched while the controls are enabled
+    let dispatch = {
+        let state_handle = app_context.state_handle.clone();
+        move |action: AppAction| {
+            if !state_handle.get_are_audio_controls_disabled() {
+                state_handle.dispatch(action);
+            }
+        }
+    };
+
+    let handle_kind_change = {
+        let new_effect_kind = new_effect_kind.clone();
+        Callback::from(move |e: Event| {
+            let name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(effect_kind) = EffectKind::from_name(&name) {
+                new_effect_kind.set(effect_kind);
+            }
+        })
+    };
+
+    let handle_add_click = {
+        let dispatch = dispatch.clone();
+        let new_effect_kind = new_effect_kind.clone();
+        Callback::from(move |_: MouseEvent| dispatch(AppAction::AddEffect(*new_effect_kind)))
+    };
+
+    let kind_options: Vec<AttrValue> = EffectKind::NAMES
+        .iter()
+        .map(|name| AttrValue::from(*name))
+        .collect();
+
+    let effects = summaries
+        .into_iter()
+        .enumerate()
+        .map(|(i, summary)| {
+            let on_click = |action: AppAction| {
+                let dispatch = dispatch.clone();
+                Callback::from(move |_: MouseEvent| dispatch(action.clone()))
+            };
+
+            let handle_mix_input = {
+                let dispatch = dispatch.clone();
+                Callback::from(move |e: InputEvent| {
+                    let mix = e
+                        .target_dyn_into::<HtmlInputElement>()
+                        .unwrap()
+                        .value_as_number() as f32;
+                    dispatch(AppAction::SetEffectMix(i, mix));
+                })
+            };
+
+            let parameters = summary
+                .parameters
+                .iter()
+                .zip(&summary.values)
+                .enumerate()
+                .map(|(parameter_index, (parameter, &value))| {
+                    let id = format!("controls-effect-chain-{}-parameter-{}", i, parameter_index);
+
+                    // switches and note divisions are picked by name rather than with a slider
+                    if parameter.is_discrete() {
+                        let handle_change = {
+                            let dispatch = dispatch.clone();
+                            let parameter = *parameter;
+                            Callback::from(move |e: Event| {
+                                let name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+                                if let Some(value) = parameter.option_value(&name) {
+                                    dispatch(AppAction::SetEffectParameter(i, parameter_index, value));
+                                }
+                            })
+                        };
+                        let options: Vec<AttrValue> = parameter
+                            .options
+                            .iter()
+                            .map(|&option| AttrValue::from(option))
+                            .collect();
+
+                        return html! {
+                            <InputSelect
+                                label={parameter.name}
+                                id={id}
+                                options={options}
+                                value={parameter.option_name(value).unwrap_or_default()}
+                                onchange={handle_change}
+                                disabled={controls_disabled}
+                            />
+                        };
+                    }
+
+                    let handle_input = {
+                        let dispatch = dispatch.clone();
+                        let parameter = *parameter;
+                        Callback::from(move |e: InputEvent| {
+                            let position = e
+                                .target_dyn_into::<HtmlInputElement>()
+                                .unwrap()
+                                .value_as_number() as f32;
+                            dispatch(AppAction::SetEffectParameter(
+                                i,
+                                parameter_index,
+                                from_slider(&parameter, position),
+                            ));
+                        })
+                    };
+                    let step = (to_slider(parameter, parameter.max)
+                        - to_slider(parameter, parameter.min))
+                        / 1000.0;
+
+                    html! {
+                        <InputRange
+                            label={parameter.name}
+                            id={id}
+                            min={to_slider(parameter, parameter.min).to_string()}
+                            max={to_slider(parameter, parameter.max).to_string()}
+                            step={step.to_string()}
+                            oninput={handle_input}
+                            value={to_slider(parameter, value).to_string()}
+                            disabled={controls_disabled}
+                        />
+                    }
+                })
+                .collect::<Html>();
+
+            let bypass_variant = if summary.bypassed {
+                ButtonVariant::Pressed
+            } else {
+                ButtonVariant::Unpressed
+            };
+
+            html! {
+                <li class="controls-effect-chain-effect">
+                    <div class="controls-effect-chain-effect-header">
+                        <span>{format!("{}. {}", i + 1, summary.name)}</span>
+                        <div class="controls-effect-chain-effect-buttons">
+                            <Button
+                                aria_label="bypass effect"
+                                onclick={on_click(AppAction::SetEffectBypassed(i, !summary.bypassed))}
+                                disabled={controls_disabled}
+                                variant={bypass_variant}
+                            >
+                                {"bypass"}
+                            </Button>
+                            <Button
+                                aria_label="move effect up"
+                                onclick={on_click(AppAction::MoveEffect(i, i.saturating_sub(1)))}
+                                disabled={controls_disabled || i == 0}
+                            >
+                                {"↑"}
+                            </Button>
+                            <Button
+                                aria_label="move effect down"
+                                onclick={on_click(AppAction::MoveEffect(i, i + 1))}
+                                disabled={controls_disabled || i + 1 == num_effects}
+                            >
+                                {"↓"}
+                            </Button>
+                            <Button
+                                aria_label="remove effect"
+                                onclick={on_click(AppAction::RemoveEffect(i))}
+                                disabled={controls_disabled}
+                            >
+                                {"remove"}
+                            </Button>
+                        </div>
+                    </div>
+                    <div class="controls-effect-chain-effect-parameters">
+                        {parameters}
+                        <InputRange
+                            label="dry / wet"
+                            id={format!("controls-effect-chain-{}-mix", i)}
+                            step="0.01"
+                            oninput={handle_mix_input}
+                            value={summary.mix.to_string()}
+                            disabled={controls_disabled || summary.bypassed}
+                        />
+                    </div>
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="controls-effect-chain">
+            <ol class="controls-effect-chain-effects">
+                {effects}
+            </ol>
+            <div class="controls-effect-chain-add">
+                <InputSelect
+                    label="Effect"
+                    id="controls-effect-chain-kind-select"
+                    options={kind_options}
+                    value={new_effect_kind.name()}
+                    onchange={handle_kind_change}
+                    disabled={controls_disabled}
+                />
+                <Button
+                    aria_label="add effect"
+                    onclick={handle_add_click}
+                    disabled={controls_disabled || chain_full}
+                    variant={ButtonVariant::Unpressed}
+                >
+                    {"add"}
+                </Button>
+            </div>
+        </div>
+    }
+}
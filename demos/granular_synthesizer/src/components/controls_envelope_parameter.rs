@@ -0,0 +1,56 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::envelope_shape::EnvelopeShape;
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// Adjusts the parameter of the current envelope shape (e.g. Gaussian width, Tukey flatness)
+#[function_component(ControlsEnvelopeParameter)]
+pub fn controls_envelope_parameter() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let envelope_shape = app_context.state_handle.envelope_shape;
+    // not all envelope shapes have an adjustable parameter
+    let envelope_parameter = envelope_shape.parameter();
+    let envelope_parameter_input_disabled =
+        app_context.state_handle.get_are_audio_controls_disabled() || envelope_parameter.is_none();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let envelope_parameter = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetEnvelopeParameter(envelope_parameter));
+        })
+    };
+
+    let label = match envelope_shape {
+        EnvelopeShape::Gaussian { .. } => "width",
+        EnvelopeShape::Tukey { .. } => "flat",
+        _ => "shape",
+    };
+
+    html! {
+        <InputRange
+            label={label}
+            id="controls-envelope-parameter-input"
+            min="0.0"
+            max="1.0"
+            step="0.01"
+            oninput={handle_input}
+            value={envelope_parameter.unwrap_or_default().to_string()}
+            disabled={envelope_parameter_input_disabled}
+        />
+    }
+}
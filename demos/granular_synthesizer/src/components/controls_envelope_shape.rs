@@ -0,0 +1,48 @@
+use crate::{
+    components::input_select::InputSelect,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::envelope_shape::EnvelopeShape;
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+#[function_component(ControlsEnvelopeShape)]
+pub fn controls_envelope_shape() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let select_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let envelope_shape = app_context.state_handle.envelope_shape;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let shape_name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(envelope_shape) = EnvelopeShape::from_name(&shape_name) {
+                state_handle.dispatch(AppAction::SetEnvelopeShape(envelope_shape));
+            }
+        })
+    };
+
+    let options: Vec<AttrValue> = EnvelopeShape::NAMES
+        .iter()
+        .map(|name| AttrValue::from(*name))
+        .collect();
+
+    html! {
+        <InputSelect
+            label="Envelope"
+            id="controls-envelope-shape-select"
+            options={options}
+            value={envelope_shape.name()}
+            onchange={handle_change}
+            disabled={select_disabled}
+        />
+    }
+}
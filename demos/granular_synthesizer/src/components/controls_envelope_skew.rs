@@ -0,0 +1,45 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+#[function_component(ControlsEnvelopeSkew)]
+pub fn controls_envelope_skew() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let envelope_skew_input_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let envelope_skew = app_context.state_handle.envelope_skew.get();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let envelope_skew = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetEnvelopeSkew(envelope_skew));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="skew"
+            id="controls-envelope-skew-input"
+            min="0.0"
+            max="1.0"
+            step="0.01"
+            oninput={handle_input}
+            value={envelope_skew.to_string()}
+            disabled={envelope_skew_input_disabled}
+        />
+    }
+}
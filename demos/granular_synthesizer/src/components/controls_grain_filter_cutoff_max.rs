@@ -0,0 +1,53 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{
+    grain_filter::GrainFilterMode, granular_synthesizer::GranularSynthesizer,
+    granular_synthesizer_action::GranularSynthesizerAction,
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// Sets the highest cutoff that new grains' filters can be randomly assigned (on a logarithmic slider)
+#[function_component(ControlsGrainFilterCutoffMax)]
+pub fn controls_grain_filter_cutoff_max() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let input_disabled = app_context.state_handle.get_are_audio_controls_disabled()
+        || app_context.state_handle.grain_filter_mode == GrainFilterMode::Off;
+    let grain_filter_cutoff_max_hz = app_context.state_handle.grain_filter_cutoff_max_hz;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let position = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetGrainFilterCutoffMaxHz(
+                10.0_f32.powf(position),
+            ));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="max\ncutoff"
+            id="controls-grain-filter-cutoff-max-input"
+            min={GranularSynthesizer::GRAIN_FILTER_CUTOFF_MIN_HZ.log10().to_string()}
+            max={GranularSynthesizer::GRAIN_FILTER_CUTOFF_MAX_HZ.log10().to_string()}
+            step="0.001"
+            oninput={handle_input}
+            value={grain_filter_cutoff_max_hz.log10().to_string()}
+            disabled={input_disabled}
+        />
+    }
+}
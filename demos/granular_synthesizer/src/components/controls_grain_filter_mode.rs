@@ -0,0 +1,49 @@
+use crate::{
+    components::input_select::InputSelect,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::grain_filter::GrainFilterMode;
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+/// Chooses the type of filter (if any) that each new grain is run through
+#[function_component(ControlsGrainFilterMode)]
+pub fn controls_grain_filter_mode() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let select_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let grain_filter_mode = app_context.state_handle.grain_filter_mode;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(grain_filter_mode) = GrainFilterMode::from_name(&name) {
+                state_handle.dispatch(AppAction::SetGrainFilterMode(grain_filter_mode));
+            }
+        })
+    };
+
+    let options: Vec<AttrValue> = GrainFilterMode::NAMES
+        .iter()
+        .map(|name| AttrValue::from(*name))
+        .collect();
+
+    html! {
+        <InputSelect
+            label="Grain filter"
+            id="controls-grain-filter-mode-select"
+            options={options}
+            value={grain_filter_mode.name()}
+            onchange={handle_change}
+            disabled={select_disabled}
+        />
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::grain_filter::GrainFilterMode;
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// Sets how sharply new grains' filters emphasize their cutoff
+#[function_component(ControlsGrainFilterResonance)]
+pub fn controls_grain_filter_resonance() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let input_disabled = app_context.state_handle.get_are_audio_controls_disabled()
+        || app_context.state_handle.grain_filter_mode == GrainFilterMode::Off;
+    let resonance = app_context.state_handle.grain_filter_resonance.get();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let resonance = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetGrainFilterResonance(resonance));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="res"
+            id="controls-grain-filter-resonance-input"
+            min="0.0"
+            max="1.0"
+            step="0.01"
+            oninput={handle_input}
+            value={resonance.to_string()}
+            disabled={input_disabled}
+        />
+    }
+}
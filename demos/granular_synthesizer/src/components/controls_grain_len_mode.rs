@@ -0,0 +1,49 @@
+use crate::{
+    components::input_select::InputSelect,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::grain_len_mode::GrainLenMode;
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+/// Switches the min / max length sliders between a percentage of the selection and milliseconds
+#[function_component(ControlsGrainLenMode)]
+pub fn controls_grain_len_mode() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let select_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let grain_len_mode = app_context.state_handle.grain_len_mode;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let mode_name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(grain_len_mode) = GrainLenMode::from_name(&mode_name) {
+                state_handle.dispatch(AppAction::SetGrainLenMode(grain_len_mode));
+            }
+        })
+    };
+
+    let options: Vec<AttrValue> = GrainLenMode::NAMES
+        .iter()
+        .map(|name| AttrValue::from(*name))
+        .collect();
+
+    html! {
+        <InputSelect
+            label="Length"
+            id="controls-grain-len-mode-select"
+            options={options}
+            value={grain_len_mode.name()}
+            onchange={handle_change}
+            disabled={select_disabled}
+        />
+    }
+}
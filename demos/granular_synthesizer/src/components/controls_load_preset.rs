@@ -0,0 +1,93 @@
+use crate::{
+    icons::folder::IconFolder,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::preset::{Preset, PresetError};
+use log::info;
+use wasm_bindgen::JsCast;
+use web_sys::{window, HtmlInputElement};
+use yew::{function_component, html, prelude::*};
+
+/// Parses a preset file, choosing the format from the file's extension (JSON unless it ends in `.toml`)
+fn parse_preset(file_name: &str, contents: &str) -> Result<Preset, PresetError> {
+    if file_name.to_lowercase().ends_with(".toml") {
+        Preset::from_toml(contents)
+    } else {
+        Preset::from_json(contents)
+    }
+}
+
+/// Applies the settings from a JSON or TOML preset file
+#[function_component(ControlsLoadPreset)]
+pub fn controls_load_preset() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let input_ref = use_node_ref();
+    let input_element_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        let input_ref = input_ref.clone();
+        Callback::from(move |_| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let state_handle = state_handle.clone();
+            let input_element = input_ref
+                .get()
+                .unwrap()
+                .dyn_into::<HtmlInputElement>()
+                .unwrap();
+            wasm_bindgen_futures::spawn_local(async move {
+                let file = match input_element.files().and_then(|files| files.item(0)) {
+                    Some(file) => file,
+                    None => return,
+                };
+                info!("Loading preset {:?}", file.name());
+
+                let contents = wasm_bindgen_futures::JsFuture::from(file.text())
+                    .await
+                    .ok()
+                    .and_then(|contents| contents.as_string())
+                    .unwrap_or_default();
+
+                match parse_preset(&file.name(), &contents) {
+                    Ok(preset) => state_handle.dispatch(AppAction::ApplyPreset(Box::new(preset))),
+                    Err(e) => window()
+                        .unwrap()
+                        .alert_with_message(&format!("Error loading preset: {}", e))
+                        .unwrap(),
+                }
+
+                // allow the same file to be loaded again (e.g. after changing settings)
+                input_element.set_value("");
+            })
+        })
+    };
+
+    let disabled_class = if input_element_disabled {
+        "disabled"
+    } else {
+        ""
+    };
+
+    html! {
+        <div class={classes!("controls-load-preset", disabled_class)}>
+            <label>
+                <input
+                    aria-description="Load a preset file"
+                    type="file"
+                    accept=".json,.toml,application/json"
+                    onchange={handle_change}
+                    ref={input_ref}
+                    disabled={input_element_disabled}
+                />
+                <IconFolder />
+            </label>
+        </div>
+    }
+}
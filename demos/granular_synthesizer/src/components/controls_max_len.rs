@@ -7,17 +7,33 @@ use crate::{
     },
 };
 use audio::{
-    granular_synthesizer::GranularSynthesizer,
+    grain_len_mode::GrainLenMode, granular_synthesizer::GranularSynthesizer,
     granular_synthesizer_action::GranularSynthesizerAction,
 };
 use web_sys::HtmlInputElement;
 use yew::{function_component, html, prelude::*};
 
+/// In absolute mode, the slider moves through milliseconds on a logarithmic scale,
+/// so that short grains get as much room on the slider as long ones
 #[function_component(ControlsMaxLen)]
 pub fn controls_max_len() -> Html {
     let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
     let max_len_input_disabled = app_context.state_handle.get_are_audio_controls_disabled();
-    let grain_len_max_value = app_context.state_handle.grain_len_max.get().get();
+    let grain_len_mode = app_context.state_handle.grain_len_mode;
+    let (grain_len_max_value, min, max, step) = match grain_len_mode {
+        GrainLenMode::Relative => (
+            app_context.state_handle.grain_len_max.get().get(),
+            GranularSynthesizer::GRAIN_LEN_MAX_MIN,
+            GranularSynthesizer::GRAIN_LEN_MAX_MAX,
+            "0.01",
+        ),
+        GrainLenMode::Absolute => (
+            app_context.state_handle.grain_len_max_ms.log10(),
+            GranularSynthesizer::GRAIN_LEN_MS_MIN.log10(),
+            GranularSynthesizer::GRAIN_LEN_MS_MAX.log10(),
+            "0.001",
+        ),
+    };
 
     let handle_input = {
         let state_handle = app_context.state_handle;
@@ -30,7 +46,14 @@ pub fn controls_max_len() -> Html {
                 .target_dyn_into::<HtmlInputElement>()
                 .unwrap()
                 .value_as_number() as f32;
-            state_handle.dispatch(AppAction::SetGrainLenMax(new_grain_len_max));
+            match grain_len_mode {
+                GrainLenMode::Relative => {
+                    state_handle.dispatch(AppAction::SetGrainLenMax(new_grain_len_max))
+                }
+                GrainLenMode::Absolute => state_handle.dispatch(AppAction::SetGrainLenMaxMs(
+                    10.0_f32.powf(new_grain_len_max),
+                )),
+            }
         })
     };
 
@@ -38,9 +61,9 @@ pub fn controls_max_len() -> Html {
         <InputRange
             label="max\nlen"
             id="controls-max-length"
-            min={GranularSynthesizer::GRAIN_LEN_MAX_MIN.to_string()}
-            max={GranularSynthesizer::GRAIN_LEN_MAX_MAX.to_string()}
-            step="0.01"
+            min={min.to_string()}
+            max={max.to_string()}
+            step={step}
             oninput={handle_input}
             value={grain_len_max_value.to_string()}
             disabled={max_len_input_disabled}
@@ -0,0 +1,308 @@
+use crate::{
+    components::{
+        button::{Button, ButtonVariant},
+        input_range::InputRange,
+        input_select::InputSelect,
+    },
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{
+    lfo::{Lfo, LfoShape},
+    modulation_matrix::{ModulationMatrix, ModulationRouting, ModulationTarget},
+};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+/// Edits the synthesizer's LFOs and adds / removes routings from those LFOs to synthesizer parameters.
+///
+/// The LFO rate slider moves on a logarithmic scale, so that slow sweeps get as much room as fast wobbles.
+#[function_component(ControlsModulation)]
+pub fn controls_modulation() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let controls_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let routings_full =
+        app_context.state_handle.modulation_routings.len() >= ModulationMatrix::MAX_ROUTINGS;
+
+    // which LFO is being edited (and used as the source for new routings)
+    let selected_lfo = use_state(|| 0_usize);
+    let new_routing_target = use_state(ModulationTarget::default);
+    let new_routing_amount = use_state(|| 0.5_f32);
+
+    let lfo = app_context
+        .state_handle
+        .lfos
+        .get(*selected_lfo)
+        .cloned()
+        .unwrap_or_default();
+
+    // applies a change to the currently selected LFO
+    let update_lfo = {
+        let state_handle = app_context.state_handle.clone();
+        let selected_lfo = selected_lfo.clone();
+        move |update: fn(&mut Lfo, f32), value: f32| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let mut lfo = state_handle
+                .lfos
+                .get(*selected_lfo)
+                .cloned()
+                .unwrap_or_default();
+            update(&mut lfo, value);
+            state_handle.dispatch(AppAction::SetLfo(*selected_lfo, lfo));
+        }
+    };
+
+    let handle_lfo_select = {
+        let selected_lfo = selected_lfo.clone();
+        Callback::from(move |e: Event| {
+            let value = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Ok(lfo_number) = value.parse::<usize>() {
+                selected_lfo.set(lfo_number.saturating_sub(1));
+            }
+        })
+    };
+
+    let handle_shape_change = {
+        let state_handle = app_context.state_handle.clone();
+        let selected_lfo = selected_lfo.clone();
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let shape_name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(shape) = LfoShape::from_name(&shape_name) {
+                let mut lfo = state_handle
+                    .lfos
+                    .get(*selected_lfo)
+                    .cloned()
+                    .unwrap_or_default();
+                lfo.set_shape(shape);
+                state_handle.dispatch(AppAction::SetLfo(*selected_lfo, lfo));
+            }
+        })
+    };
+
+    let handle_rate_input = {
+        let update_lfo = update_lfo.clone();
+        Callback::from(move |e: InputEvent| {
+            let rate_log = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            update_lfo(
+                |lfo, rate_log| {
+                    lfo.set_rate(10.0_f32.powf(rate_log));
+                },
+                rate_log,
+            );
+        })
+    };
+
+    let handle_depth_input = {
+        let update_lfo = update_lfo.clone();
+        Callback::from(move |e: InputEvent| {
+            let depth = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            update_lfo(
+                |lfo, depth| {
+                    lfo.set_depth(depth);
+                },
+                depth,
+            );
+        })
+    };
+
+    let handle_phase_input = Callback::from(move |e: InputEvent| {
+        let phase_offset = e
+            .target_dyn_into::<HtmlInputElement>()
+            .unwrap()
+            .value_as_number() as f32;
+        update_lfo(
+            |lfo, phase_offset| {
+                lfo.set_phase_offset(phase_offset);
+            },
+            phase_offset,
+        );
+    });
+
+    let handle_target_change = {
+        let new_routing_target = new_routing_target.clone();
+        Callback::from(move |e: Event| {
+            let target_name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(target) = ModulationTarget::from_name(&target_name) {
+                new_routing_target.set(target);
+            }
+        })
+    };
+
+    let handle_amount_input = {
+        let new_routing_amount = new_routing_amount.clone();
+        Callback::from(move |e: InputEvent| {
+            let amount = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            new_routing_amount.set(amount);
+        })
+    };
+
+    let handle_add_click = {
+        let state_handle = app_context.state_handle.clone();
+        let selected_lfo = selected_lfo.clone();
+        let new_routing_target = new_routing_target.clone();
+        let new_routing_amount = new_routing_amount.clone();
+        Callback::from(move |_: MouseEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            state_handle.dispatch(AppAction::AddModulationRouting(ModulationRouting::new(
+                *selected_lfo,
+                *new_routing_target,
+                *new_routing_amount,
+            )));
+        })
+    };
+
+    let lfo_options: Vec<AttrValue> = (1..=ModulationMatrix::NUM_LFOS)
+        .map(|lfo_number| AttrValue::from(lfo_number.to_string()))
+        .collect();
+
+    let shape_options: Vec<AttrValue> = LfoShape::NAMES
+        .iter()
+        .map(|name| AttrValue::from(*name))
+        .collect();
+
+    let target_options: Vec<AttrValue> = ModulationTarget::NAMES
+        .iter()
+        .map(|name| AttrValue::from(*name))
+        .collect();
+
+    let routings = app_context
+        .state_handle
+        .modulation_routings
+        .iter()
+        .enumerate()
+        .map(|(i, routing)| {
+            let handle_remove_click = {
+                let state_handle = app_context.state_handle.clone();
+                Callback::from(move |_: MouseEvent| {
+                    if state_handle.get_are_audio_controls_disabled() {
+                        return;
+                    }
+
+                    state_handle.dispatch(AppAction::RemoveModulationRouting(i));
+                })
+            };
+
+            html! {
+                <li class="controls-modulation-routing">
+                    <span>
+                        {format!(
+                            "LFO {} → {} ({:+.2})",
+                            routing.lfo_index + 1,
+                            routing.target.name(),
+                            routing.amount
+                        )}
+                    </span>
+                    <Button
+                        aria_label="remove routing"
+                        onclick={handle_remove_click}
+                        disabled={controls_disabled}
+                    >
+                        {"remove"}
+                    </Button>
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="controls-modulation">
+            <div class="controls-modulation-lfo">
+                <InputSelect
+                    label="LFO"
+                    id="controls-modulation-lfo-select"
+                    options={lfo_options}
+                    value={(*selected_lfo + 1).to_string()}
+                    onchange={handle_lfo_select}
+                    disabled={controls_disabled}
+                />
+                <InputSelect
+                    label="Shape"
+                    id="controls-modulation-lfo-shape-select"
+                    options={shape_options}
+                    value={lfo.shape().name()}
+                    onchange={handle_shape_change}
+                    disabled={controls_disabled}
+                />
+                <InputRange
+                    label="rate"
+                    id="controls-modulation-lfo-rate"
+                    min={Lfo::RATE_MIN.log10().to_string()}
+                    max={Lfo::RATE_MAX.log10().to_string()}
+                    step="0.01"
+                    oninput={handle_rate_input}
+                    value={lfo.rate().log10().to_string()}
+                    disabled={controls_disabled}
+                />
+                <InputRange
+                    label="depth"
+                    id="controls-modulation-lfo-depth"
+                    step="0.01"
+                    oninput={handle_depth_input}
+                    value={lfo.depth().to_string()}
+                    disabled={controls_disabled}
+                />
+                <InputRange
+                    label="phase"
+                    id="controls-modulation-lfo-phase"
+                    step="0.01"
+                    oninput={handle_phase_input}
+                    value={lfo.phase_offset().to_string()}
+                    disabled={controls_disabled}
+                />
+            </div>
+            <ul class="controls-modulation-routings">
+                {routings}
+            </ul>
+            <div class="controls-modulation-add">
+                <InputSelect
+                    label="Target"
+                    id="controls-modulation-target-select"
+                    options={target_options}
+                    value={new_routing_target.name()}
+                    onchange={handle_target_change}
+                    disabled={controls_disabled}
+                />
+                <InputRange
+                    label="amount"
+                    id="controls-modulation-amount"
+                    min="-1.0"
+                    max="1.0"
+                    step="0.01"
+                    oninput={handle_amount_input}
+                    value={new_routing_amount.to_string()}
+                    disabled={controls_disabled}
+                />
+                <Button
+                    aria_label="add routing"
+                    onclick={handle_add_click}
+                    disabled={controls_disabled || routings_full}
+                    variant={ButtonVariant::Unpressed}
+                >
+                    {"add"}
+                </Button>
+            </div>
+        </div>
+    }
+}
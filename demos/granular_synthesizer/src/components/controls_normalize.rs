@@ -0,0 +1,50 @@
+use crate::{
+    components::input_select::InputSelect,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+const NORMALIZE_ON: &str = "on";
+
+const NORMALIZE_OFF: &str = "off";
+
+/// Toggles automatic level compensation for the number of sounding grains
+#[function_component(ControlsNormalize)]
+pub fn controls_normalize() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let select_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let normalize = app_context.state_handle.normalize;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let value = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            state_handle.dispatch(AppAction::SetNormalize(value == NORMALIZE_ON));
+        })
+    };
+
+    let options = vec![
+        AttrValue::from(NORMALIZE_ON),
+        AttrValue::from(NORMALIZE_OFF),
+    ];
+
+    html! {
+        <InputSelect
+            label="Normalize"
+            id="controls-normalize-select"
+            options={options}
+            value={if normalize { NORMALIZE_ON } else { NORMALIZE_OFF }}
+            onchange={handle_change}
+            disabled={select_disabled}
+        />
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{
+    granular_synthesizer::GranularSynthesizer,
+    granular_synthesizer_action::GranularSynthesizerAction,
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+#[function_component(ControlsPanCenter)]
+pub fn controls_pan_center() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let pan_center_input_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let pan_center = app_context.state_handle.pan_center;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let pan_center = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetPanCenter(pan_center));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="pan"
+            id="controls-pan-center-input"
+            min={GranularSynthesizer::PAN_MIN.to_string()}
+            max={GranularSynthesizer::PAN_MAX.to_string()}
+            step="0.01"
+            oninput={handle_input}
+            value={pan_center.to_string()}
+            disabled={pan_center_input_disabled}
+        />
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    components::input_select::InputSelect,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::pan_law::PanLaw;
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+#[function_component(ControlsPanLaw)]
+pub fn controls_pan_law() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let select_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let pan_law = app_context.state_handle.pan_law;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let pan_law_name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(pan_law) = PanLaw::from_name(&pan_law_name) {
+                state_handle.dispatch(AppAction::SetPanLaw(pan_law));
+            }
+        })
+    };
+
+    let options: Vec<AttrValue> = PanLaw::NAMES
+        .iter()
+        .map(|name| AttrValue::from(*name))
+        .collect();
+
+    html! {
+        <InputSelect
+            label="Pan law"
+            id="controls-pan-law-select"
+            options={options}
+            value={pan_law.name()}
+            onchange={handle_change}
+            disabled={select_disabled}
+        />
+    }
+}
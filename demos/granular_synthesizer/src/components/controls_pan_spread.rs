@@ -0,0 +1,45 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+#[function_component(ControlsPanSpread)]
+pub fn controls_pan_spread() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let pan_spread_input_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let pan_spread = app_context.state_handle.pan_spread.get();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let pan_spread = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetPanSpread(pan_spread));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="width"
+            id="controls-pan-spread-input"
+            min="0.0"
+            max="1.0"
+            step="0.01"
+            oninput={handle_input}
+            value={pan_spread.to_string()}
+            disabled={pan_spread_input_disabled}
+        />
+    }
+}
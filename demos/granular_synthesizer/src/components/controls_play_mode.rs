@@ -0,0 +1,48 @@
+use crate::{
+    components::input_select::InputSelect,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::note_voice::PlayMode;
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+#[function_component(ControlsPlayMode)]
+pub fn controls_play_mode() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let select_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let play_mode = app_context.state_handle.play_mode;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let mode_name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(play_mode) = PlayMode::from_name(&mode_name) {
+                state_handle.dispatch(AppAction::SetPlayMode(play_mode));
+            }
+        })
+    };
+
+    let options: Vec<AttrValue> = PlayMode::NAMES
+        .iter()
+        .map(|name| AttrValue::from(*name))
+        .collect();
+
+    html! {
+        <InputSelect
+            label="Mode"
+            id="controls-play-mode-select"
+            options={options}
+            value={play_mode.name()}
+            onchange={handle_change}
+            disabled={select_disabled}
+        />
+    }
+}
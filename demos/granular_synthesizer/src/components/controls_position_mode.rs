@@ -0,0 +1,48 @@
+use crate::{
+    components::input_select::InputSelect,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::playhead::PositionMode;
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+#[function_component(ControlsPositionMode)]
+pub fn controls_position_mode() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let select_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let position_mode = app_context.state_handle.position_mode;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let mode_name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(position_mode) = PositionMode::from_name(&mode_name) {
+                state_handle.dispatch(AppAction::SetPositionMode(position_mode));
+            }
+        })
+    };
+
+    let options: Vec<AttrValue> = PositionMode::NAMES
+        .iter()
+        .map(|name| AttrValue::from(*name))
+        .collect();
+
+    html! {
+        <InputSelect
+            label="Position"
+            id="controls-position-mode-select"
+            options={options}
+            value={position_mode.name()}
+            onchange={handle_change}
+            disabled={select_disabled}
+        />
+    }
+}
@@ -13,12 +13,17 @@ use audio::{
 use web_sys::HtmlInputElement;
 use yew::{function_component, html, prelude::*};
 
+/// Sets how often new grains start, in grains per second (rather than in samples between refreshes,
+/// which would change with the device's sample rate).
+///
+/// The slider moves through grains per second on a logarithmic scale,
+/// so that sparse, rhythmic rates get as much room on the slider as dense clouds
 #[function_component(ControlsRefreshInterval)]
 pub fn controls_refresh_interval() -> Html {
     let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
-    let refresh_interval_input_disabled =
+    let grains_per_second_input_disabled =
         app_context.state_handle.get_are_audio_controls_disabled();
-    let refresh_interval = app_context.state_handle.refresh_interval.get();
+    let grains_per_second = app_context.state_handle.grains_per_second;
 
     let handle_input = {
         let state_handle = app_context.state_handle;
@@ -27,24 +32,26 @@ pub fn controls_refresh_interval() -> Html {
                 return;
             }
 
-            let refresh_interval = e
+            let grains_per_second_log = e
                 .target_dyn_into::<HtmlInputElement>()
                 .unwrap()
-                .value_as_number() as u32;
-            state_handle.dispatch(AppAction::SetRefreshInterval(refresh_interval));
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetGrainsPerSecond(
+                10.0_f32.powf(grains_per_second_log),
+            ));
         })
     };
 
     html! {
         <InputRange
-            label="fade"
+            label="rate"
             id="controls-refresh-interval"
-            min={GranularSynthesizer::REFRESH_INTERVAL_MIN.to_string()}
-            max={GranularSynthesizer::REFRESH_INTERVAL_MAX.to_string()}
-            step="1"
+            min={GranularSynthesizer::GRAINS_PER_SECOND_MIN.log10().to_string()}
+            max={GranularSynthesizer::GRAINS_PER_SECOND_MAX.log10().to_string()}
+            step="0.01"
             oninput={handle_input}
-            value={refresh_interval.to_string()}
-            disabled={refresh_interval_input_disabled}
+            value={grains_per_second.log10().to_string()}
+            disabled={grains_per_second_input_disabled}
         />
     }
 }
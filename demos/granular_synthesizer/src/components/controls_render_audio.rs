@@ -0,0 +1,45 @@
+use crate::{
+    audio::render,
+    components::button::Button,
+    icons::render::IconRender,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use yew::{function_component, html, prelude::*};
+
+/// Renders `render_seconds` of audio faster than real-time and downloads it
+#[function_component(ControlsRenderAudio)]
+pub fn controls_render_audio() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let button_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+
+    let handle_click = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |_: MouseEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let state_handle = state_handle.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                state_handle.dispatch(AppAction::SetAudioLoading(true));
+                render::render_and_download(&state_handle, state_handle.render_seconds).await;
+                state_handle.dispatch(AppAction::SetAudioLoading(false));
+            })
+        })
+    };
+
+    html! {
+        <Button
+            aria_label="render and download audio"
+            class="controls-render-audio"
+            onclick={handle_click}
+            disabled={button_disabled}
+        >
+            <IconRender />
+        </Button>
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{
+    components::input_text::InputText,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// How many seconds of audio "Render & download" produces
+#[function_component(ControlsRenderSeconds)]
+pub fn controls_render_seconds() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let render_seconds_input_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let render_seconds = app_context.state_handle.render_seconds;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let input_element = e.target_dyn_into::<HtmlInputElement>().unwrap();
+            match input_element.value().trim().parse::<f32>() {
+                Ok(render_seconds) if render_seconds.is_finite() => {
+                    state_handle.dispatch(AppAction::SetRenderSeconds(render_seconds))
+                }
+                // restore the current duration if the input isn't a valid number
+                _ => input_element.set_value(&state_handle.render_seconds.to_string()),
+            }
+        })
+    };
+
+    html! {
+        <InputText
+            label="Render (s)"
+            id="controls-render-seconds-input"
+            inputmode="decimal"
+            value={render_seconds.to_string()}
+            onchange={handle_change}
+            disabled={render_seconds_input_disabled}
+        />
+    }
+}
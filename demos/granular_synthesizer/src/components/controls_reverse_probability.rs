@@ -0,0 +1,46 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+#[function_component(ControlsReverseProbability)]
+pub fn controls_reverse_probability() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let reverse_probability_input_disabled =
+        app_context.state_handle.get_are_audio_controls_disabled();
+    let reverse_probability = app_context.state_handle.reverse_probability.get();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let reverse_probability = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetReverseProbability(reverse_probability));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="reverse"
+            id="controls-reverse-probability-input"
+            min="0.0"
+            max="1.0"
+            step="0.01"
+            oninput={handle_input}
+            value={reverse_probability.to_string()}
+            disabled={reverse_probability_input_disabled}
+        />
+    }
+}
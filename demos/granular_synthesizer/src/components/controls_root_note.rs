@@ -0,0 +1,50 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::note_voice::PlayMode;
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// The MIDI note that plays the buffer at its original pitch
+#[function_component(ControlsRootNote)]
+pub fn controls_root_note() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    // notes are only played in notes mode
+    let root_note_input_disabled = app_context.state_handle.get_are_audio_controls_disabled()
+        || app_context.state_handle.play_mode != PlayMode::Notes;
+    let root_note = app_context.state_handle.root_note;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let value = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number();
+            let root_note = value as u8;
+            state_handle.dispatch(AppAction::SetRootNote(root_note));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="root"
+            id="controls-root-note-input"
+            min={"0"}
+            max={"127"}
+            step="1"
+            oninput={handle_input}
+            value={root_note.to_string()}
+            disabled={root_note_input_disabled}
+        />
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    components::button::Button,
+    icons::save::IconSave,
+    state::{
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+    utils::download,
+};
+use web_sys::window;
+use yew::{function_component, html, prelude::*};
+
+const PRESET_FILE_NAME: &str = "granular_synthesizer_preset.json";
+
+/// Downloads the synthesizer's current settings as a JSON preset
+#[function_component(ControlsSavePreset)]
+pub fn controls_save_preset() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let button_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+
+    let handle_click = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |_: MouseEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            match state_handle.preset().to_json() {
+                Ok(json) => download::download_bytes(json, PRESET_FILE_NAME),
+                Err(e) => window()
+                    .unwrap()
+                    .alert_with_message(&format!("Error saving preset: {}", e))
+                    .unwrap(),
+            }
+        })
+    };
+
+    html! {
+        <Button
+            aria_label="save preset"
+            class="controls-save-preset"
+            onclick={handle_click}
+            disabled={button_disabled}
+        >
+            <IconSave />
+        </Button>
+    }
+}
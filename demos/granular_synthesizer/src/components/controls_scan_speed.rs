@@ -0,0 +1,51 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{
+    granular_synthesizer::GranularSynthesizer,
+    granular_synthesizer_action::GranularSynthesizerAction, playhead::PositionMode,
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+#[function_component(ControlsScanSpeed)]
+pub fn controls_scan_speed() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    // the playhead is only used when scanning
+    let scan_speed_input_disabled = app_context.state_handle.get_are_audio_controls_disabled()
+        || app_context.state_handle.position_mode != PositionMode::Scan;
+    let scan_speed = app_context.state_handle.scan_speed;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let scan_speed = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetScanSpeed(scan_speed));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="scan"
+            id="controls-scan-speed-input"
+            min={GranularSynthesizer::SCAN_SPEED_MIN.to_string()}
+            max={GranularSynthesizer::SCAN_SPEED_MAX.to_string()}
+            step="0.01"
+            oninput={handle_input}
+            value={scan_speed.to_string()}
+            disabled={scan_speed_input_disabled}
+        />
+    }
+}
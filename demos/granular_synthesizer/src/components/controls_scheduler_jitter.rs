@@ -0,0 +1,49 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::scheduler::SchedulerMode;
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+#[function_component(ControlsSchedulerJitter)]
+pub fn controls_scheduler_jitter() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    // jitter only applies to regularly-timed grains
+    let scheduler_jitter_input_disabled =
+        app_context.state_handle.get_are_audio_controls_disabled()
+            || app_context.state_handle.scheduler_mode != SchedulerMode::Synchronous;
+    let scheduler_jitter = app_context.state_handle.scheduler_jitter.get();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let scheduler_jitter = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetSchedulerJitter(scheduler_jitter));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="jitter"
+            id="controls-scheduler-jitter-input"
+            min="0.0"
+            max="1.0"
+            step="0.01"
+            oninput={handle_input}
+            value={scheduler_jitter.to_string()}
+            disabled={scheduler_jitter_input_disabled}
+        />
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    components::input_text::InputText,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+#[function_component(ControlsSeed)]
+pub fn controls_seed() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let seed_input_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let seed = app_context.state_handle.seed;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let input_element = e.target_dyn_into::<HtmlInputElement>().unwrap();
+            match input_element.value().trim().parse::<u64>() {
+                Ok(seed) => state_handle.dispatch(AppAction::SetSeed(seed)),
+                // restore the current seed if the input isn't a valid seed
+                Err(_) => input_element.set_value(&state_handle.seed.to_string()),
+            }
+        })
+    };
+
+    html! {
+        <InputText
+            label="Seed"
+            id="controls-seed-input"
+            inputmode="numeric"
+            value={seed.to_string()}
+            onchange={handle_change}
+            disabled={seed_input_disabled}
+        />
+    }
+}
@@ -23,6 +23,7 @@ pub fn controls_select_buffer() -> Html {
     let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
     let select_ref = use_node_ref();
     let select_element_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let audio_file_index = app_context.state_handle.audio_file_index;
 
     let handle_change = {
         let state_handle = app_context.state_handle;
@@ -43,8 +44,8 @@ pub fn controls_select_buffer() -> Html {
                     .unwrap()
                     .dyn_into::<HtmlSelectElement>()
                     .unwrap();
-                let selected_index = select_element.selected_index();
-                let request_url = format!("./{}", AUDIO_FILES[selected_index as usize]);
+                let selected_index = select_element.selected_index() as usize;
+                let request_url = format!("./{}", AUDIO_FILES[selected_index]);
 
                 // audio files are copied into static directory for web (same directory as the source wasm file)
                 let mp3_file_bytes = Request::get(&request_url)
@@ -61,11 +62,12 @@ pub fn controls_select_buffer() -> Html {
                 let audio_buffer_result =
                     decode::decode_bytes(&audio_context, &mp3_file_bytes).await;
 
-                match audio_buffer_result {
-                    Ok(audio_buffer) => {
-                        let buffer_data = Arc::new(audio_buffer.get_channel_data(0).unwrap());
-
-                        state_handle.dispatch(AppAction::SetBuffer(buffer_data));
+                match audio_buffer_result
+                    .and_then(|audio_buffer| decode::audio_buffer_to_source_buffer(&audio_buffer))
+                {
+                    Ok(buffer_data) => {
+                        state_handle.dispatch(AppAction::SetBuffer(Arc::new(buffer_data)));
+                        state_handle.dispatch(AppAction::SetAudioFileIndex(Some(selected_index)));
                     }
                     Err(_) => {
                         window()
@@ -98,9 +100,9 @@ pub fn controls_select_buffer() -> Html {
                 ref={select_ref}
                 disabled={select_element_disabled}
             >
-                {AUDIO_FILES.iter().map(|file_name| {
+                {AUDIO_FILES.iter().enumerate().map(|(i, file_name)| {
                     html!{
-                        <option selected={file_name == &DEFAULT_AUDIO_FILE}>
+                        <option selected={audio_file_index == Some(i)}>
                             {file_name}
                         </option>
                     }
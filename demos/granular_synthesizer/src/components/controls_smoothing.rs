@@ -0,0 +1,50 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{
+    granular_synthesizer::GranularSynthesizer,
+    granular_synthesizer_action::GranularSynthesizerAction,
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// How long (in milliseconds) gain and continuous synthesizer parameters take to reach new values
+#[function_component(ControlsSmoothing)]
+pub fn controls_smoothing() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let smoothing_input_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let smoothing_ms = app_context.state_handle.smoothing_ms;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let smoothing_ms = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetSmoothingMs(smoothing_ms));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="smooth"
+            id="controls-smoothing-input"
+            min={GranularSynthesizer::SMOOTHING_MS_MIN.to_string()}
+            max={GranularSynthesizer::SMOOTHING_MS_MAX.to_string()}
+            step="1"
+            oninput={handle_input}
+            value={smoothing_ms.to_string()}
+            disabled={smoothing_input_disabled}
+        />
+    }
+}
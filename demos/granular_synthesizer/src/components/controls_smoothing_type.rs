@@ -0,0 +1,48 @@
+use crate::{
+    components::input_select::InputSelect,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::smoothed_value::SmoothingType;
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, prelude::*, virtual_dom::AttrValue};
+
+#[function_component(ControlsSmoothingType)]
+pub fn controls_smoothing_type() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let select_disabled = app_context.state_handle.get_are_audio_controls_disabled();
+    let smoothing_type = app_context.state_handle.smoothing_type;
+
+    let handle_change = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: Event| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let type_name = e.target_dyn_into::<HtmlSelectElement>().unwrap().value();
+            if let Some(smoothing_type) = SmoothingType::from_name(&type_name) {
+                state_handle.dispatch(AppAction::SetSmoothingType(smoothing_type));
+            }
+        })
+    };
+
+    let options: Vec<AttrValue> = SmoothingType::NAMES
+        .iter()
+        .map(|name| AttrValue::from(*name))
+        .collect();
+
+    html! {
+        <InputSelect
+            label="Smoothing"
+            id="controls-smoothing-type-select"
+            options={options}
+            value={smoothing_type.name()}
+            onchange={handle_change}
+            disabled={select_disabled}
+        />
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{
+    granular_synthesizer::GranularSynthesizer,
+    granular_synthesizer_action::GranularSynthesizerAction, playhead::PositionMode,
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+#[function_component(ControlsSpray)]
+pub fn controls_spray() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    // spray is measured around the playhead, which is only used when scanning
+    let spray_input_disabled = app_context.state_handle.get_are_audio_controls_disabled()
+        || app_context.state_handle.position_mode != PositionMode::Scan;
+    let spray_ms = app_context.state_handle.spray_ms;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let spray_ms = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number() as f32;
+            state_handle.dispatch(AppAction::SetSprayMs(spray_ms));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="spray"
+            id="controls-spray-input"
+            min={GranularSynthesizer::SPRAY_MS_MIN.to_string()}
+            max={GranularSynthesizer::SPRAY_MS_MAX.to_string()}
+            step="1"
+            oninput={handle_input}
+            value={spray_ms.to_string()}
+            disabled={spray_input_disabled}
+        />
+    }
+}
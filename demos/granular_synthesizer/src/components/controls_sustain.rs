@@ -0,0 +1,56 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{adsr::Adsr, note_voice::PlayMode};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// The amplitude (0.0 -> 1.0) that each note holds at for as long as it is held
+#[function_component(ControlsSustain)]
+pub fn controls_sustain() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    // notes are only played in notes mode
+    let sustain_input_disabled = app_context.state_handle.get_are_audio_controls_disabled()
+        || app_context.state_handle.play_mode != PlayMode::Notes;
+    let adsr = app_context.state_handle.adsr;
+    let sustain = adsr.sustain;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let value = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number();
+            let sustain = value as f32;
+            state_handle.dispatch(AppAction::SetAdsr(Adsr::new(
+                adsr.attack_ms,
+                adsr.decay_ms,
+                sustain,
+                adsr.release_ms,
+            )));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="sus"
+            id="controls-sustain-input"
+            min={"0"}
+            max={"1"}
+            step="0.01"
+            oninput={handle_input}
+            value={sustain.to_string()}
+            disabled={sustain_input_disabled}
+        />
+    }
+}
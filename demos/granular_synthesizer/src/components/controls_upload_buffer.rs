@@ -60,12 +60,12 @@ pub fn controls_upload_buffer() -> Html {
                         let audio_buffer_result =
                             decode::decode_bytes(&audio_context, &file_bytes).await;
 
-                        match audio_buffer_result {
-                            Ok(audio_buffer) => {
-                                let buffer_data =
-                                    Arc::new(audio_buffer.get_channel_data(0).unwrap());
-
-                                state_handle.dispatch(AppAction::SetBuffer(buffer_data));
+                        match audio_buffer_result.and_then(|audio_buffer| {
+                            decode::audio_buffer_to_source_buffer(&audio_buffer)
+                        }) {
+                            Ok(buffer_data) => {
+                                state_handle.dispatch(AppAction::SetBuffer(Arc::new(buffer_data)));
+                                state_handle.dispatch(AppAction::SetAudioFileIndex(None));
                             }
                             Err(_) => {
                                 window()
@@ -0,0 +1,51 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::note_voice::PlayMode;
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// How much a note's velocity affects its amplitude (0.0 -> 1.0)
+#[function_component(ControlsVelocitySensitivity)]
+pub fn controls_velocity_sensitivity() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    // notes are only played in notes mode
+    let velocity_sensitivity_input_disabled =
+        app_context.state_handle.get_are_audio_controls_disabled()
+            || app_context.state_handle.play_mode != PlayMode::Notes;
+    let velocity_sensitivity = app_context.state_handle.velocity_sensitivity.get();
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let value = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number();
+            let velocity_sensitivity = value as f32;
+            state_handle.dispatch(AppAction::SetVelocitySensitivity(velocity_sensitivity));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="vel"
+            id="controls-velocity-sensitivity-input"
+            min={"0"}
+            max={"1"}
+            step="0.01"
+            oninput={handle_input}
+            value={velocity_sensitivity.to_string()}
+            disabled={velocity_sensitivity_input_disabled}
+        />
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    components::input_range::InputRange,
+    state::{
+        app_action::AppAction,
+        app_context::{AppContext, AppContextError},
+        app_selector::AppSelector,
+    },
+};
+use audio::{
+    granular_synthesizer::GranularSynthesizer,
+    granular_synthesizer_action::GranularSynthesizerAction, note_voice::PlayMode,
+};
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, prelude::*};
+
+/// The most notes that can sound at once
+#[function_component(ControlsVoiceLimit)]
+pub fn controls_voice_limit() -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    // notes are only played in notes mode
+    let voice_limit_input_disabled = app_context.state_handle.get_are_audio_controls_disabled()
+        || app_context.state_handle.play_mode != PlayMode::Notes;
+    let voice_limit = app_context.state_handle.voice_limit;
+
+    let handle_input = {
+        let state_handle = app_context.state_handle;
+        Callback::from(move |e: InputEvent| {
+            if state_handle.get_are_audio_controls_disabled() {
+                return;
+            }
+
+            let value = e
+                .target_dyn_into::<HtmlInputElement>()
+                .unwrap()
+                .value_as_number();
+            let voice_limit = value as usize;
+            state_handle.dispatch(AppAction::SetVoiceLimit(voice_limit));
+        })
+    };
+
+    html! {
+        <InputRange
+            label="voices"
+            id="controls-voice-limit-input"
+            min={"1"}
+            max={GranularSynthesizer::MAX_VOICES.to_string()}
+            step="1"
+            oninput={handle_input}
+            value={voice_limit.to_string()}
+            disabled={voice_limit_input_disabled}
+        />
+    }
+}
@@ -0,0 +1,41 @@
+use yew::{prelude::*, virtual_dom::AttrValue};
+
+#[derive(Properties, PartialEq)]
+pub struct InputSelectProps {
+    #[prop_or_default]
+    pub disabled: bool,
+    pub label: AttrValue,
+    pub id: AttrValue,
+    /// The list of options, where each option is used as both the displayed text and the value
+    pub options: Vec<AttrValue>,
+    /// The currently selected option
+    pub value: AttrValue,
+    #[prop_or_default]
+    pub onchange: Callback<Event>,
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+#[function_component(InputSelect)]
+pub fn input_select(props: &InputSelectProps) -> Html {
+    let disabled_class = if props.disabled { "disabled" } else { "" };
+
+    html! {
+        <div class={classes!("input-select", disabled_class, props.class.clone())}>
+            <label for={props.id.clone()}>{&props.label}</label>
+            <select
+                id={props.id.clone()}
+                onchange={&props.onchange}
+                disabled={props.disabled}
+            >
+                {props.options.iter().map(|option| {
+                    html!{
+                        <option value={option.clone()} selected={option == &props.value}>
+                            {option}
+                        </option>
+                    }
+                }).collect::<Html>()}
+            </select>
+        </div>
+    }
+}
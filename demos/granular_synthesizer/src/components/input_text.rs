@@ -0,0 +1,37 @@
+use yew::{prelude::*, virtual_dom::AttrValue};
+
+#[derive(Properties, PartialEq)]
+pub struct InputTextProps {
+    #[prop_or_default]
+    pub disabled: bool,
+    pub label: AttrValue,
+    pub id: AttrValue,
+    pub value: AttrValue,
+    /// Hint to virtual keyboards about what kind of data is expected (e.g. "numeric")
+    #[prop_or("text".into())]
+    pub inputmode: AttrValue,
+    /// Called once the user commits a new value (on enter or when the input loses focus)
+    #[prop_or_default]
+    pub onchange: Callback<Event>,
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+#[function_component(InputText)]
+pub fn input_text(props: &InputTextProps) -> Html {
+    let disabled_class = if props.disabled { "disabled" } else { "" };
+
+    html! {
+        <div class={classes!("input-text", disabled_class, props.class.clone())}>
+            <label for={props.id.clone()}>{&props.label}</label>
+            <input
+                id={props.id.clone()}
+                type="text"
+                inputmode={props.inputmode.clone()}
+                value={props.value.clone()}
+                onchange={&props.onchange}
+                disabled={props.disabled}
+            />
+        </div>
+    }
+}
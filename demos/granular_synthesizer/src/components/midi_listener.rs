@@ -0,0 +1,101 @@
+use crate::state::app_context::{AppContext, AppContextError};
+use audio::granular_synthesizer_action::GranularSynthesizerAction;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{window, MidiAccess, MidiInput, MidiMessageEvent};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct MidiListenerProps {
+    pub children: Children,
+}
+
+/// Calls `f` for every MIDI input that is currently connected
+fn for_each_midi_input(midi_access: &MidiAccess, mut f: impl FnMut(MidiInput)) {
+    midi_access
+        .inputs()
+        .unchecked_into::<js_sys::Map>()
+        .for_each(&mut |input: JsValue, _| f(input.unchecked_into()));
+}
+
+/// Forwards messages from every connected MIDI input to the granular synthesizer
+/// (if the browser supports Web MIDI).
+#[function_component(MidiListener)]
+pub fn midi_listener(props: &MidiListenerProps) -> Html {
+    let app_context = use_context::<AppContext>().expect(AppContextError::NOT_FOUND);
+    let granular_synthesizer_handle = app_context.state_handle.granular_synthesizer_handle.clone();
+
+    // the synthesizer handle is replaced when the app state is reset,
+    // so MIDI inputs have to be reconnected to the new one
+    use_effect_with_deps(
+        move |granular_synthesizer_handle| {
+            let mut granular_synthesizer_handle = granular_synthesizer_handle.clone();
+            let handle_midi_message = Closure::wrap(Box::new(move |e: MidiMessageEvent| {
+                if let Ok(data) = e.data() {
+                    granular_synthesizer_handle.handle_midi(&data);
+                }
+            })
+                as Box<dyn FnMut(MidiMessageEvent)>);
+
+            // keep callback valid until the effect is cleaned up
+            let handle_midi_message = Rc::new(handle_midi_message);
+            let midi_access_handle: Rc<RefCell<Option<MidiAccess>>> = Default::default();
+            // access is requested asynchronously, so the effect may be cleaned up before it's granted
+            let is_cleaned_up = Rc::new(Cell::new(false));
+
+            {
+                let handle_midi_message = Rc::clone(&handle_midi_message);
+                let midi_access_handle = Rc::clone(&midi_access_handle);
+                let is_cleaned_up = Rc::clone(&is_cleaned_up);
+                wasm_bindgen_futures::spawn_local(async move {
+                    let request = match window().unwrap().navigator().request_midi_access() {
+                        Ok(request) => request,
+                        Err(_) => {
+                            log::info!("Web MIDI is not supported in this browser");
+                            return;
+                        }
+                    };
+
+                    let midi_access: MidiAccess = match JsFuture::from(request).await {
+                        Ok(midi_access) => midi_access.unchecked_into(),
+                        Err(_) => {
+                            log::info!("MIDI access was denied");
+                            return;
+                        }
+                    };
+
+                    if is_cleaned_up.get() {
+                        return;
+                    }
+
+                    for_each_midi_input(&midi_access, |input| {
+                        input.set_onmidimessage(Some(
+                            handle_midi_message.as_ref().as_ref().unchecked_ref(),
+                        ))
+                    });
+                    midi_access_handle.borrow_mut().replace(midi_access);
+                });
+            }
+
+            // disconnect inputs on unmount
+            move || {
+                is_cleaned_up.set(true);
+                if let Some(midi_access) = midi_access_handle.borrow_mut().take() {
+                    for_each_midi_input(&midi_access, |input| input.set_onmidimessage(None));
+                }
+                drop(handle_midi_message);
+            }
+        },
+        granular_synthesizer_handle,
+    );
+
+    html! {
+     <>
+         {for props.children.iter()}
+     </>
+    }
+}
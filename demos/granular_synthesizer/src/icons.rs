@@ -1,8 +1,11 @@
 pub mod download;
+pub mod folder;
 pub mod pause;
 pub mod play;
 pub mod power;
 pub mod record;
+pub mod render;
 pub mod reset;
+pub mod save;
 pub mod stop_recording;
 pub mod upload;
\ No newline at end of file
@@ -1,3 +1,6 @@
+// yew 0.19's `html!` macro expands to code that trips these lints.
+#![allow(clippy::unnecessary_operation, clippy::let_unit_value)]
+
 use wasm_bindgen::prelude::*;
 
 pub mod audio;
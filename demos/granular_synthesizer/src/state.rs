@@ -3,3 +3,4 @@ pub mod app_context;
 pub mod app_reducer;
 pub mod app_selector;
 pub mod app_state;
+pub mod url_state;
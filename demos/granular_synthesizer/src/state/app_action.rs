@@ -1,13 +1,21 @@
 use crate::audio::{
     play_status::PlayStatus, recording_status::RecordingStatus, stream_handle::StreamHandle,
 };
+use audio::{
+    adsr::Adsr, channel_policy::ChannelPolicy, effect::EffectKind, envelope_shape::EnvelopeShape,
+    grain_filter::GrainFilterMode, grain_len_mode::GrainLenMode, lfo::Lfo,
+    modulation_matrix::ModulationRouting, note_division::NoteDivision, note_voice::PlayMode,
+    pan_law::PanLaw, playhead::PositionMode, preset::Preset, scheduler::SchedulerMode,
+    smoothed_value::SmoothingType, source_buffer::SourceBuffer,
+};
 use std::sync::Arc;
 
 use super::app_state::NunChannels;
 
 #[derive(Debug, Clone)]
 pub enum AppAction {
-    SetBuffer(Arc<Vec<f32>>),
+    SetBuffer(Arc<SourceBuffer>),
+    SetAudioFileIndex(Option<usize>),
     SetStreamHandle(StreamHandle),
     SetBufferSelectionStart(f32),
     IncrementBufferSelectionStart,
@@ -17,6 +25,16 @@ pub enum AppAction {
     SetBufferSelectionEnd(f32),
     SetBufferSelectionMouseDown(bool),
     SetGain(f32),
+    /// Turns the delay next to the gain control (the first delay in the effect chain) on or off
+    SetDelayEnabled(bool),
+    SetDelayMix(f32),
+    SetDelayTimeMs(f32),
+    SetDelayBpm(f32),
+    /// Syncs the delay to a note division (or turns tempo sync off with `None`)
+    SetDelayDivision(Option<NoteDivision>),
+    SetDelayFeedback(f32),
+    SetDelayToneHz(f32),
+    SetDelayPingPong(bool),
     SetPlayStatus(PlayStatus),
     SetAudioInitialized(bool),
     SetAudioLoading(bool),
@@ -24,10 +42,55 @@ pub enum AppAction {
     SetDensity(f32),
     SetGrainLenMax(f32),
     SetGrainLenMin(f32),
+    SetGrainLenMode(GrainLenMode),
+    SetGrainLenMaxMs(f32),
+    SetGrainLenMinMs(f32),
     SetRefreshInterval(u32),
+    SetGrainsPerSecond(f32),
+    SetSchedulerMode(SchedulerMode),
+    SetSchedulerJitter(f32),
+    SetPositionMode(PositionMode),
+    SetScanSpeed(f32),
+    SetSprayMs(f32),
+    SetReverseProbability(f32),
+    SetEnvelopeShape(EnvelopeShape),
+    SetEnvelopeParameter(f32),
+    SetEnvelopeSkew(f32),
+    SetAmplitudeMin(f32),
+    SetAmplitudeMax(f32),
+    SetGrainFilterMode(GrainFilterMode),
+    SetGrainFilterCutoffMinHz(f32),
+    SetGrainFilterCutoffMaxHz(f32),
+    SetGrainFilterResonance(f32),
+    SetNormalize(bool),
+    SetSmoothingMs(f32),
+    SetSmoothingType(SmoothingType),
+    SetLfo(usize, Lfo),
+    AddModulationRouting(ModulationRouting),
+    RemoveModulationRouting(usize),
+    SetPlayMode(PlayMode),
+    SetRootNote(u8),
+    SetVoiceLimit(usize),
+    SetAdsr(Adsr),
+    SetVelocitySensitivity(f32),
+    SetSeed(u64),
+    SetChannelPolicy(ChannelPolicy),
+    SetPanCenter(f32),
+    SetPanSpread(f32),
+    SetPanLaw(PanLaw),
+    AddEffect(EffectKind),
+    RemoveEffect(usize),
+    /// Moves the effect at the first index to the second index
+    MoveEffect(usize, usize),
+    SetEffectBypassed(usize, bool),
+    SetEffectMix(usize, f32),
+    /// Sets the effect at the first index's parameter (at the second index) to a value
+    SetEffectParameter(usize, usize, f32),
+    ApplyPreset(Box<Preset>),
     ResetState,
     SetRecordingStatus(RecordingStatus),
     SetNumChannels(NunChannels),
     DownloadAudio,
+    SetRenderSeconds(f32),
     SetIsKeyboardUser,
 }
@@ -15,7 +15,7 @@ impl AppContextError {
 impl Default for AppContext {
     fn default() -> Self {
         AppContext {
-            state_handle: use_reducer_eq(AppState::default),
+            state_handle: use_reducer_eq(AppState::from_url),
         }
     }
 }
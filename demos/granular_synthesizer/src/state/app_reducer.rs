@@ -1,13 +1,14 @@
-use super::{app_action::AppAction, app_state::AppState};
+use super::{app_action::AppAction, app_state::AppState, url_state};
 use crate::{
     audio::{
-        buffer_handle::BufferHandle, buffer_selection_action::BufferSelectionAction,
+        buffer_handle::BufferHandle, buffer_selection_action::BufferSelectionAction, decode,
         density_action::DensityAction, gain_action::GainAction,
         play_status_action::PlayStatusAction, recording_status_action::RecordingStatusAction,
+        render,
     },
     components::buffer_sample_bars_canvas::get_buffer_maxes_for_canvas,
 };
-use audio::granular_synthesizer_action::GranularSynthesizerAction;
+use audio::{delay::Delay, granular_synthesizer_action::GranularSynthesizerAction};
 use std::{rc::Rc, sync::Arc};
 use yew::Reducible;
 
@@ -22,6 +23,7 @@ impl Reducible for AppState {
             let action = action;
             match action {
                 AppAction::SetBuffer(buffer) => {
+                    let buffer = decode::match_sample_rate(buffer, next_state.sample_rate);
                     next_state.buffer_maxes_for_canvas = get_buffer_maxes_for_canvas(&buffer);
                     next_state
                         .granular_synthesizer_handle
@@ -47,6 +49,42 @@ impl Reducible for AppState {
                 AppAction::SetGain(gain) => {
                     next_state.gain_handle.set(gain);
                 }
+                AppAction::SetDelayEnabled(enabled) => {
+                    next_state.effect_chain_handle.set_delay_enabled(enabled);
+                }
+                AppAction::SetDelayMix(mix) => {
+                    next_state.effect_chain_handle.set_delay_mix(mix);
+                }
+                AppAction::SetDelayTimeMs(time_ms) => {
+                    next_state
+                        .effect_chain_handle
+                        .set_delay_parameter(&Delay::TIME_MS, time_ms);
+                }
+                AppAction::SetDelayBpm(bpm) => {
+                    next_state
+                        .effect_chain_handle
+                        .set_delay_parameter(&Delay::BPM, bpm);
+                }
+                AppAction::SetDelayDivision(division) => {
+                    next_state
+                        .effect_chain_handle
+                        .set_delay_parameter(&Delay::SYNC, Delay::sync_value(division));
+                }
+                AppAction::SetDelayFeedback(feedback) => {
+                    next_state
+                        .effect_chain_handle
+                        .set_delay_parameter(&Delay::FEEDBACK, feedback);
+                }
+                AppAction::SetDelayToneHz(tone_hz) => {
+                    next_state
+                        .effect_chain_handle
+                        .set_delay_parameter(&Delay::TONE_HZ, tone_hz);
+                }
+                AppAction::SetDelayPingPong(ping_pong) => {
+                    next_state
+                        .effect_chain_handle
+                        .set_delay_parameter(&Delay::PING_PONG, if ping_pong { 1.0 } else { 0.0 });
+                }
                 AppAction::SetPlayStatus(play_status) => {
                     next_state.play_status_handle.set(play_status);
                 }
@@ -62,6 +100,18 @@ impl Reducible for AppState {
                     next_state
                         .granular_synthesizer_handle
                         .set_sample_rate(sample_rate);
+                    next_state.effect_chain_handle.set_sample_rate(sample_rate);
+
+                    // buffers loaded before the output's sample rate was known may not match it
+                    let buffer = next_state.buffer_handle.get_data();
+                    if !buffer.is_empty() && buffer.sample_rate() != sample_rate {
+                        let buffer = decode::match_sample_rate(buffer, sample_rate);
+                        next_state.buffer_maxes_for_canvas = get_buffer_maxes_for_canvas(&buffer);
+                        next_state
+                            .granular_synthesizer_handle
+                            .set_buffer(Arc::clone(&buffer));
+                        next_state.buffer_handle = BufferHandle::new(buffer);
+                    }
                 }
                 AppAction::SetDensity(density) => {
                     next_state.density_handle.set(density);
@@ -100,6 +150,284 @@ impl Reducible for AppState {
                     next_state
                         .refresh_interval
                         .set(next_state.granular_synthesizer_handle.refresh_interval());
+                    next_state.grains_per_second =
+                        next_state.granular_synthesizer_handle.grains_per_second();
+                }
+                AppAction::SetGrainsPerSecond(grains_per_second) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_grains_per_second(grains_per_second);
+                    next_state.grains_per_second =
+                        next_state.granular_synthesizer_handle.grains_per_second();
+                    next_state
+                        .refresh_interval
+                        .set(next_state.granular_synthesizer_handle.refresh_interval());
+                }
+                AppAction::SetGrainLenMode(grain_len_mode) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_grain_len_mode(grain_len_mode);
+                    next_state.grain_len_mode =
+                        next_state.granular_synthesizer_handle.grain_len_mode();
+                }
+                AppAction::SetGrainLenMaxMs(max_len_ms) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_grain_len_max_ms(max_len_ms);
+                    next_state.grain_len_min_ms =
+                        next_state.granular_synthesizer_handle.grain_len_min_ms();
+                    next_state.grain_len_max_ms =
+                        next_state.granular_synthesizer_handle.grain_len_max_ms();
+                }
+                AppAction::SetGrainLenMinMs(min_len_ms) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_grain_len_min_ms(min_len_ms);
+                    next_state.grain_len_min_ms =
+                        next_state.granular_synthesizer_handle.grain_len_min_ms();
+                    next_state.grain_len_max_ms =
+                        next_state.granular_synthesizer_handle.grain_len_max_ms();
+                }
+                AppAction::SetPositionMode(position_mode) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_position_mode(position_mode);
+                    next_state.position_mode =
+                        next_state.granular_synthesizer_handle.position_mode();
+                }
+                AppAction::SetScanSpeed(scan_speed) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_scan_speed(scan_speed);
+                    next_state.scan_speed = next_state.granular_synthesizer_handle.scan_speed();
+                }
+                AppAction::SetSprayMs(spray_ms) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_spray_ms(spray_ms);
+                    next_state.spray_ms = next_state.granular_synthesizer_handle.spray_ms();
+                }
+                AppAction::SetSchedulerMode(scheduler_mode) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_scheduler_mode(scheduler_mode);
+                    next_state.scheduler_mode =
+                        next_state.granular_synthesizer_handle.scheduler_mode();
+                }
+                AppAction::SetSchedulerJitter(scheduler_jitter) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_scheduler_jitter(scheduler_jitter);
+                    next_state.scheduler_jitter =
+                        next_state.granular_synthesizer_handle.scheduler_jitter();
+                }
+                AppAction::SetReverseProbability(reverse_probability) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_reverse_probability(reverse_probability);
+                    next_state.reverse_probability =
+                        next_state.granular_synthesizer_handle.reverse_probability();
+                }
+                AppAction::SetEnvelopeShape(envelope_shape) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_envelope_shape(envelope_shape);
+                    next_state.envelope_shape =
+                        next_state.granular_synthesizer_handle.envelope_shape();
+                }
+                AppAction::SetEnvelopeParameter(parameter) => {
+                    let envelope_shape = next_state.envelope_shape.with_parameter(parameter);
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_envelope_shape(envelope_shape);
+                    next_state.envelope_shape =
+                        next_state.granular_synthesizer_handle.envelope_shape();
+                }
+                AppAction::SetAmplitudeMin(amplitude_min) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_amplitude_min(amplitude_min);
+                    next_state.amplitude_min =
+                        next_state.granular_synthesizer_handle.amplitude_min();
+                    next_state.amplitude_max =
+                        next_state.granular_synthesizer_handle.amplitude_max();
+                }
+                AppAction::SetAmplitudeMax(amplitude_max) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_amplitude_max(amplitude_max);
+                    next_state.amplitude_min =
+                        next_state.granular_synthesizer_handle.amplitude_min();
+                    next_state.amplitude_max =
+                        next_state.granular_synthesizer_handle.amplitude_max();
+                }
+                AppAction::SetGrainFilterMode(grain_filter_mode) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_grain_filter_mode(grain_filter_mode);
+                    next_state.grain_filter_mode =
+                        next_state.granular_synthesizer_handle.grain_filter_mode();
+                }
+                AppAction::SetGrainFilterCutoffMinHz(cutoff_min_hz) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_grain_filter_cutoff_min_hz(cutoff_min_hz);
+                    next_state.grain_filter_cutoff_min_hz = next_state
+                        .granular_synthesizer_handle
+                        .grain_filter_cutoff_min_hz();
+                    next_state.grain_filter_cutoff_max_hz = next_state
+                        .granular_synthesizer_handle
+                        .grain_filter_cutoff_max_hz();
+                }
+                AppAction::SetGrainFilterCutoffMaxHz(cutoff_max_hz) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_grain_filter_cutoff_max_hz(cutoff_max_hz);
+                    next_state.grain_filter_cutoff_min_hz = next_state
+                        .granular_synthesizer_handle
+                        .grain_filter_cutoff_min_hz();
+                    next_state.grain_filter_cutoff_max_hz = next_state
+                        .granular_synthesizer_handle
+                        .grain_filter_cutoff_max_hz();
+                }
+                AppAction::SetGrainFilterResonance(resonance) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_grain_filter_resonance(resonance);
+                    next_state.grain_filter_resonance = next_state
+                        .granular_synthesizer_handle
+                        .grain_filter_resonance();
+                }
+                AppAction::SetNormalize(normalize) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_normalize(normalize);
+                    next_state.normalize = next_state.granular_synthesizer_handle.normalize();
+                }
+                AppAction::SetSmoothingMs(smoothing_ms) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_smoothing_ms(smoothing_ms);
+                    next_state.smoothing_ms = next_state.granular_synthesizer_handle.smoothing_ms();
+                }
+                AppAction::SetSmoothingType(smoothing_type) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_smoothing_type(smoothing_type);
+                    next_state.smoothing_type =
+                        next_state.granular_synthesizer_handle.smoothing_type();
+                }
+                AppAction::SetLfo(index, lfo) => {
+                    next_state.granular_synthesizer_handle.set_lfo(index, lfo);
+                    if let Some(lfo) = next_state.granular_synthesizer_handle.lfo(index) {
+                        if let Some(state_lfo) = next_state.lfos.get_mut(index) {
+                            *state_lfo = lfo;
+                        }
+                    }
+                }
+                AppAction::AddModulationRouting(routing) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .add_modulation_routing(routing);
+                    next_state.modulation_routings =
+                        next_state.granular_synthesizer_handle.modulation_routings();
+                }
+                AppAction::RemoveModulationRouting(index) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .remove_modulation_routing(index);
+                    next_state.modulation_routings =
+                        next_state.granular_synthesizer_handle.modulation_routings();
+                }
+                AppAction::SetPlayMode(play_mode) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_play_mode(play_mode);
+                    next_state.play_mode = next_state.granular_synthesizer_handle.play_mode();
+                }
+                AppAction::SetRootNote(root_note) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_root_note(root_note);
+                    next_state.root_note = next_state.granular_synthesizer_handle.root_note();
+                }
+                AppAction::SetVoiceLimit(voice_limit) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_voice_limit(voice_limit);
+                    next_state.voice_limit = next_state.granular_synthesizer_handle.voice_limit();
+                }
+                AppAction::SetAdsr(adsr) => {
+                    next_state.granular_synthesizer_handle.set_adsr(adsr);
+                    next_state.adsr = next_state.granular_synthesizer_handle.adsr();
+                }
+                AppAction::SetVelocitySensitivity(velocity_sensitivity) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_velocity_sensitivity(velocity_sensitivity);
+                    next_state.velocity_sensitivity = next_state
+                        .granular_synthesizer_handle
+                        .velocity_sensitivity();
+                }
+                AppAction::SetEnvelopeSkew(envelope_skew) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_envelope_skew(envelope_skew);
+                    next_state.envelope_skew =
+                        next_state.granular_synthesizer_handle.envelope_skew();
+                }
+                AppAction::SetSeed(seed) => {
+                    next_state.granular_synthesizer_handle.reseed(seed);
+                    next_state.seed = next_state.granular_synthesizer_handle.seed();
+                }
+                AppAction::SetChannelPolicy(channel_policy) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_channel_policy(channel_policy);
+                    next_state.channel_policy =
+                        next_state.granular_synthesizer_handle.channel_policy();
+                }
+                AppAction::SetPanCenter(pan_center) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_pan_center(pan_center);
+                    next_state.pan_center = next_state.granular_synthesizer_handle.pan_center();
+                }
+                AppAction::SetPanSpread(pan_spread) => {
+                    next_state
+                        .granular_synthesizer_handle
+                        .set_pan_spread(pan_spread);
+                    next_state.pan_spread = next_state.granular_synthesizer_handle.pan_spread();
+                }
+                AppAction::SetPanLaw(pan_law) => {
+                    next_state.granular_synthesizer_handle.set_pan_law(pan_law);
+                    next_state.pan_law = next_state.granular_synthesizer_handle.pan_law();
+                }
+                AppAction::AddEffect(effect_kind) => {
+                    next_state.effect_chain_handle.add_effect(effect_kind);
+                }
+                AppAction::RemoveEffect(index) => {
+                    next_state.effect_chain_handle.remove_effect(index);
+                }
+                AppAction::MoveEffect(from, to) => {
+                    next_state.effect_chain_handle.move_effect(from, to);
+                }
+                AppAction::SetEffectBypassed(index, bypassed) => {
+                    next_state.effect_chain_handle.set_bypassed(index, bypassed);
+                }
+                AppAction::SetEffectMix(index, mix) => {
+                    next_state.effect_chain_handle.set_mix(index, mix);
+                }
+                AppAction::SetEffectParameter(index, parameter_index, value) => {
+                    next_state
+                        .effect_chain_handle
+                        .set_parameter(index, parameter_index, value);
+                }
+                AppAction::ApplyPreset(preset) => {
+                    next_state.apply_preset(&preset);
+                }
+                AppAction::SetAudioFileIndex(audio_file_index) => {
+                    next_state.audio_file_index = audio_file_index;
                 }
                 AppAction::ResetState => {
                     // drop previous stream's handle to stop audio
@@ -151,12 +479,19 @@ impl Reducible for AppState {
                 AppAction::DownloadAudio => next_state
                     .audio_recorder_handle
                     .download_as_wav(next_state.num_channels as u16, next_state.sample_rate),
+                AppAction::SetRenderSeconds(render_seconds) => {
+                    next_state.render_seconds = render_seconds
+                        .clamp(render::RENDER_SECONDS_MIN, render::RENDER_SECONDS_MAX);
+                }
                 AppAction::SetIsKeyboardUser => {
                     next_state.is_keyboard_user = true;
                 }
             }
         }
 
+        // keep the page's url shareable
+        url_state::write_url(&next_state);
+
         Rc::new(next_state)
     }
 }
@@ -1,17 +1,38 @@
 use crate::audio::audio_ouput_handle::AudioOutputHandle;
 use crate::audio::audio_recorder_handle::AudioRecorderHandle;
 use crate::audio::buffer_handle::BufferHandle;
+use crate::audio::buffer_selection_action::BufferSelectionAction;
 use crate::audio::buffer_selection_handle::BufferSelectionHandle;
+use crate::audio::density_action::DensityAction;
 use crate::audio::density_handle::DensityHandle;
+use crate::audio::effect_chain_handle::EffectChainHandle;
 use crate::audio::gain_handle::GainHandle;
 use crate::audio::grain_len_handle::GrainLenHandle;
 use crate::audio::granular_synthesizer_handle::GranularSynthesizerHandle;
 use crate::audio::play_status_handle::PlayStatusHandle;
 use crate::audio::recording_status_handle::RecordingStatusHandle;
 use crate::audio::refresh_interval_handle::RefreshIntervalHandle;
+use crate::audio::render::DEFAULT_RENDER_SECONDS;
 use crate::audio::stream_handle::StreamHandle;
+use crate::components::controls_select_buffer::DEFAULT_AUDIO_FILE_INDEX;
+use audio::adsr::Adsr;
+use audio::channel_policy::ChannelPolicy;
+use audio::envelope_shape::EnvelopeShape;
+use audio::grain_filter::GrainFilterMode;
+use audio::grain_len_mode::GrainLenMode;
 use audio::granular_synthesizer::GranularSynthesizer;
 use audio::granular_synthesizer_action::GranularSynthesizerAction;
+use audio::lfo::Lfo;
+use audio::modulation_matrix::{ModulationMatrix, ModulationRouting};
+use audio::note_voice::PlayMode;
+use audio::pan_law::PanLaw;
+use audio::percentage::Percentage;
+use audio::playhead::PositionMode;
+use audio::preset::Preset;
+use audio::scheduler::SchedulerMode;
+use audio::smoothed_value::SmoothingType;
+
+use super::url_state;
 
 pub type SampleRate = u32;
 pub type NunChannels = u32;
@@ -40,6 +61,9 @@ pub struct AppState {
     /// The currently loaded audio buffer
     pub buffer_handle: BufferHandle,
 
+    /// Which of the built-in `AUDIO_FILES` is loaded (`None` when the buffer was uploaded)
+    pub audio_file_index: Option<usize>,
+
     /// A list with a set length of max amplitudes from the original audio buffer.
     /// This makes re-rendering the audio buffer visualization and O(1) operation instead of O(n),
     /// where n is the length of buffer samples.
@@ -82,16 +106,124 @@ pub struct AppState {
     /// from the `GranularSynthesizer` on every frame (0.0 -> 1.0)
     pub density_handle: DensityHandle,
 
-    /// This is the maximum length (in milliseconds) that a grain sample can play for
+    /// This is the maximum length (as a percentage of the selection) that a grain sample can play for
     pub grain_len_max: GrainLenHandle,
 
-    /// This is the minimum length (in milliseconds) that a grain sample can play for
+    /// This is the minimum length (as a percentage of the selection) that a grain sample can play for
     pub grain_len_min: GrainLenHandle,
 
+    /// Whether the min / max grain length controls are relative to the selection or in milliseconds
+    pub grain_len_mode: GrainLenMode,
+
+    /// This is the maximum length (in milliseconds) that a grain sample can play for in absolute mode
+    pub grain_len_max_ms: f32,
+
+    /// This is the minimum length (in milliseconds) that a grain sample can play for in absolute mode
+    pub grain_len_min_ms: f32,
+
+    /// The average interval between new grains, in samples at 44.1kHz
+    /// (an alternate representation of `grains_per_second`)
     pub refresh_interval: RefreshIntervalHandle,
 
+    /// The average number of new grains started every second
+    pub grains_per_second: f32,
+
+    /// Whether new grains start at regular or random intervals
+    pub scheduler_mode: SchedulerMode,
+
+    /// How far regularly-timed grains can randomly deviate from their interval (0.0 -> 1.0)
+    pub scheduler_jitter: Percentage,
+
+    /// Whether new grains start anywhere in the selection or around a moving playhead
+    pub position_mode: PositionMode,
+
+    /// How fast the playhead moves through the selection (1.0 = original speed)
+    pub scan_speed: f32,
+
+    /// How far (in milliseconds) around the playhead new grains can start
+    pub spray_ms: f32,
+
+    /// The chance that a new grain will play backwards through the buffer (0.0 -> 1.0)
+    pub reverse_probability: Percentage,
+
+    /// The amplitude window applied to newly created grains
+    pub envelope_shape: EnvelopeShape,
+
+    /// Where the peak of each new grain's envelope lands (0.5 = centered)
+    pub envelope_skew: Percentage,
+
+    /// The quietest amplitude that new grains can be randomly assigned (0.0 -> 1.0)
+    pub amplitude_min: Percentage,
+
+    /// The loudest amplitude that new grains can be randomly assigned (0.0 -> 1.0)
+    pub amplitude_max: Percentage,
+
+    /// The type of filter that new grains are run through
+    pub grain_filter_mode: GrainFilterMode,
+
+    /// The lowest cutoff (in Hz) that new grains' filters can be randomly assigned
+    pub grain_filter_cutoff_min_hz: f32,
+
+    /// The highest cutoff (in Hz) that new grains' filters can be randomly assigned
+    pub grain_filter_cutoff_max_hz: f32,
+
+    /// How sharply new grains' filters emphasize their cutoff (0.0 -> 1.0)
+    pub grain_filter_resonance: Percentage,
+
+    /// Whether the output level automatically compensates for the number of sounding grains
+    pub normalize: bool,
+
+    /// How long (in milliseconds) gain and continuous synthesizer parameters take to reach new values
+    pub smoothing_ms: f32,
+
+    /// The curve that continuous synthesizer parameters follow when reaching new values
+    pub smoothing_type: SmoothingType,
+
+    /// Settings of each of the synthesizer's LFOs
+    pub lfos: Vec<Lfo>,
+
+    /// Which LFOs modulate which synthesizer parameters
+    pub modulation_routings: Vec<ModulationRouting>,
+
+    /// Whether grains play continuously or only while MIDI notes are held
+    pub play_mode: PlayMode,
+
+    /// The MIDI note that plays the buffer at its original pitch
+    pub root_note: u8,
+
+    /// The most notes that can sound at once
+    pub voice_limit: usize,
+
+    /// The amplitude envelope applied to every note
+    pub adsr: Adsr,
+
+    /// How much a note's velocity affects its amplitude (0.0 -> 1.0)
+    pub velocity_sensitivity: Percentage,
+
+    /// The seed of the synthesizer's random number generator.
+    /// Replaying the same seed with the same settings reproduces the same audio.
+    pub seed: u64,
+
+    /// Which channel of the (possibly multi-channel) buffer new grains read from
+    pub channel_policy: ChannelPolicy,
+
+    /// The stereo position that new grains are centered around (-1.0 -> 1.0)
+    pub pan_center: f32,
+
+    /// How far new grains can be randomly panned away from `pan_center` (0.0 -> 1.0)
+    pub pan_spread: Percentage,
+
+    /// How each grain's amplitude is split between output channels
+    pub pan_law: PanLaw,
+
+    /// Effects applied (in order) to the synthesizer's output
+    pub effect_chain_handle: EffectChainHandle,
+
     pub audio_recorder_handle: AudioRecorderHandle,
 
+    /// How many seconds of audio "Render & download" produces
+    pub render_seconds: f32,
+
     pub recording_status_handle: RecordingStatusHandle,
 
     /// Contains a buffer of the last few audio frames to give an moving average of channel amplitudes.
@@ -107,10 +239,12 @@ impl Default for AppState {
 
         granular_synthesizer_handle
             .set_grain_len_max(GranularSynthesizer::GRAIN_LEN_MAX_MAX)
-            .set_grain_len_min(GranularSynthesizer::GRAIN_LEN_MIN_MIN);
+            .set_grain_len_min(GranularSynthesizer::GRAIN_LEN_MIN_MIN)
+            .set_normalize(true);
 
         Self {
             buffer_handle: Default::default(),
+            audio_file_index: Some(DEFAULT_AUDIO_FILE_INDEX),
             buffer_maxes_for_canvas: Default::default(),
             stream_handle: Default::default(),
             gain_handle: Default::default(),
@@ -119,10 +253,12 @@ impl Default for AppState {
             audio_loading: Default::default(),
             sample_rate: Default::default(),
             audio_recorder_handle: Default::default(),
+            render_seconds: DEFAULT_RENDER_SECONDS,
             recording_status_handle: Default::default(),
             num_channels: Default::default(),
             audio_output_handle: Default::default(),
             is_keyboard_user: Default::default(),
+            effect_chain_handle: Default::default(),
 
             // make sure these initial settings reflect the synth's actual internal state
             buffer_selection_handle: BufferSelectionHandle::new(
@@ -132,8 +268,119 @@ impl Default for AppState {
             density_handle: granular_synthesizer_handle.density().get().into(),
             grain_len_min: granular_synthesizer_handle.grain_len_min().get().into(),
             grain_len_max: granular_synthesizer_handle.grain_len_max().get().into(),
+            grain_len_mode: granular_synthesizer_handle.grain_len_mode(),
+            grain_len_min_ms: granular_synthesizer_handle.grain_len_min_ms(),
+            grain_len_max_ms: granular_synthesizer_handle.grain_len_max_ms(),
             refresh_interval: granular_synthesizer_handle.refresh_interval().into(),
+            grains_per_second: granular_synthesizer_handle.grains_per_second(),
+            scheduler_mode: granular_synthesizer_handle.scheduler_mode(),
+            scheduler_jitter: granular_synthesizer_handle.scheduler_jitter(),
+            position_mode: granular_synthesizer_handle.position_mode(),
+            scan_speed: granular_synthesizer_handle.scan_speed(),
+            spray_ms: granular_synthesizer_handle.spray_ms(),
+            reverse_probability: granular_synthesizer_handle.reverse_probability(),
+            envelope_shape: granular_synthesizer_handle.envelope_shape(),
+            envelope_skew: granular_synthesizer_handle.envelope_skew(),
+            amplitude_min: granular_synthesizer_handle.amplitude_min(),
+            amplitude_max: granular_synthesizer_handle.amplitude_max(),
+            grain_filter_mode: granular_synthesizer_handle.grain_filter_mode(),
+            grain_filter_cutoff_min_hz: granular_synthesizer_handle.grain_filter_cutoff_min_hz(),
+            grain_filter_cutoff_max_hz: granular_synthesizer_handle.grain_filter_cutoff_max_hz(),
+            grain_filter_resonance: granular_synthesizer_handle.grain_filter_resonance(),
+            normalize: granular_synthesizer_handle.normalize(),
+            smoothing_ms: granular_synthesizer_handle.smoothing_ms(),
+            smoothing_type: granular_synthesizer_handle.smoothing_type(),
+            lfos: (0..ModulationMatrix::NUM_LFOS)
+                .filter_map(|i| granular_synthesizer_handle.lfo(i))
+                .collect(),
+            modulation_routings: granular_synthesizer_handle.modulation_routings(),
+            play_mode: granular_synthesizer_handle.play_mode(),
+            root_note: granular_synthesizer_handle.root_note(),
+            voice_limit: granular_synthesizer_handle.voice_limit(),
+            adsr: granular_synthesizer_handle.adsr(),
+            velocity_sensitivity: granular_synthesizer_handle.velocity_sensitivity(),
+            seed: granular_synthesizer_handle.seed(),
+            channel_policy: granular_synthesizer_handle.channel_policy(),
+            pan_center: granular_synthesizer_handle.pan_center(),
+            pan_spread: granular_synthesizer_handle.pan_spread(),
+            pan_law: granular_synthesizer_handle.pan_law(),
             granular_synthesizer_handle,
         }
     }
 }
+
+impl AppState {
+    /// The default state, with any settings that were shared through the page's URL applied on top
+    pub fn from_url() -> Self {
+        let mut state = Self::default();
+        url_state::read_url(&mut state);
+        state
+    }
+
+    /// Captures the synthesizer's current settings, including the ui's buffer selection
+    /// (which the synthesizer only picks up while audio is playing)
+    pub fn preset(&self) -> Preset {
+        let mut preset = self.granular_synthesizer_handle.preset();
+        let (selection_start, selection_end) =
+            self.buffer_selection_handle.get_buffer_start_and_end();
+        preset.selection_start = selection_start;
+        preset.selection_end = selection_end;
+        preset
+    }
+
+    pub fn apply_preset(&mut self, preset: &Preset) {
+        self.granular_synthesizer_handle.apply_preset(preset);
+
+        // the audio thread copies the ui's selection into the synthesizer on every block
+        self.buffer_selection_handle
+            .set_mouse_start(preset.selection_start)
+            .set_mouse_end(preset.selection_end);
+        self.sync_with_synthesizer();
+    }
+
+    /// Copies every setting from the synthesizer into the ui's state,
+    /// for when many settings change at once (e.g. when a preset is applied)
+    pub fn sync_with_synthesizer(&mut self) {
+        let synth = &self.granular_synthesizer_handle;
+
+        self.density_handle.set(synth.density());
+        self.grain_len_min.set(synth.grain_len_min());
+        self.grain_len_max.set(synth.grain_len_max());
+        self.grain_len_mode = synth.grain_len_mode();
+        self.grain_len_min_ms = synth.grain_len_min_ms();
+        self.grain_len_max_ms = synth.grain_len_max_ms();
+        self.refresh_interval.set(synth.refresh_interval());
+        self.grains_per_second = synth.grains_per_second();
+        self.scheduler_mode = synth.scheduler_mode();
+        self.scheduler_jitter = synth.scheduler_jitter();
+        self.position_mode = synth.position_mode();
+        self.scan_speed = synth.scan_speed();
+        self.spray_ms = synth.spray_ms();
+        self.reverse_probability = synth.reverse_probability();
+        self.envelope_shape = synth.envelope_shape();
+        self.envelope_skew = synth.envelope_skew();
+        self.amplitude_min = synth.amplitude_min();
+        self.amplitude_max = synth.amplitude_max();
+        self.grain_filter_mode = synth.grain_filter_mode();
+        self.grain_filter_cutoff_min_hz = synth.grain_filter_cutoff_min_hz();
+        self.grain_filter_cutoff_max_hz = synth.grain_filter_cutoff_max_hz();
+        self.grain_filter_resonance = synth.grain_filter_resonance();
+        self.normalize = synth.normalize();
+        self.smoothing_ms = synth.smoothing_ms();
+        self.smoothing_type = synth.smoothing_type();
+        self.lfos = (0..ModulationMatrix::NUM_LFOS)
+            .filter_map(|i| synth.lfo(i))
+            .collect();
+        self.modulation_routings = synth.modulation_routings();
+        self.play_mode = synth.play_mode();
+        self.root_note = synth.root_note();
+        self.voice_limit = synth.voice_limit();
+        self.adsr = synth.adsr();
+        self.velocity_sensitivity = synth.velocity_sensitivity();
+        self.seed = synth.seed();
+        self.channel_policy = synth.channel_policy();
+        self.pan_center = synth.pan_center();
+        self.pan_spread = synth.pan_spread();
+        self.pan_law = synth.pan_law();
+    }
+}
@@ -0,0 +1,101 @@
+//! Mirrors the app's settings into the page's URL, so that a sound can be shared as a link.
+//!
+//! Every setting is stored in its own query parameter, and each one is read independently:
+//! a parameter that is missing, invalid, or from an older version of the app is ignored,
+//! leaving that setting at its default.
+
+use super::app_state::AppState;
+use crate::{audio::gain_action::GainAction, components::controls_select_buffer::AUDIO_FILES};
+use audio::{granular_synthesizer_action::GranularSynthesizerAction, preset::Preset};
+use log::warn;
+use web_sys::{window, UrlSearchParams};
+
+/// The name of the built-in audio file that is loaded
+const FILE_PARAM: &str = "file";
+
+const GAIN_PARAM: &str = "gain";
+
+const SEED_PARAM: &str = "seed";
+
+/// Every synthesizer setting, stored as a compact JSON `Preset`
+const PRESET_PARAM: &str = "preset";
+
+fn search_params() -> Option<UrlSearchParams> {
+    let search = window()?.location().search().ok()?;
+    UrlSearchParams::new_with_str(&search).ok()
+}
+
+/// Applies any settings found in the page's URL to `state`
+pub fn read_url(state: &mut AppState) {
+    let params = match search_params() {
+        Some(params) => params,
+        None => return,
+    };
+
+    if let Some(file_name) = params.get(FILE_PARAM) {
+        match AUDIO_FILES.iter().position(|&name| name == file_name) {
+            Some(index) => state.audio_file_index = Some(index),
+            None => warn!("Ignoring unknown audio file in url: {:?}", file_name),
+        }
+    }
+
+    if let Some(preset) = params.get(PRESET_PARAM) {
+        match Preset::from_json(&preset) {
+            Ok(preset) => state.apply_preset(&preset),
+            Err(e) => warn!("Ignoring preset in url: {}", e),
+        }
+    }
+
+    if let Some(gain) = params.get(GAIN_PARAM) {
+        match gain.parse::<f32>() {
+            Ok(gain) if gain.is_finite() => state.gain_handle.set(gain),
+            _ => warn!("Ignoring invalid gain in url: {:?}", gain),
+        }
+    }
+
+    if let Some(seed) = params.get(SEED_PARAM) {
+        match seed.parse::<u64>() {
+            Ok(seed) => {
+                state.granular_synthesizer_handle.reseed(seed);
+                state.seed = seed;
+            }
+            Err(_) => warn!("Ignoring invalid seed in url: {:?}", seed),
+        }
+    }
+}
+
+/// Replaces the page's URL with one that encodes the settings in `state`.
+///
+/// The URL is replaced in place (rather than pushed), so that changing settings
+/// doesn't fill the browser's history.
+pub fn write_url(state: &AppState) {
+    let (window, params) = match (window(), UrlSearchParams::new()) {
+        (Some(window), Ok(params)) => (window, params),
+        _ => return,
+    };
+
+    // uploaded files can't be shared, so the default file is loaded instead
+    if let Some(file_name) = state
+        .audio_file_index
+        .and_then(|index| AUDIO_FILES.get(index))
+    {
+        params.set(FILE_PARAM, file_name);
+    }
+    params.set(GAIN_PARAM, &state.gain_handle.get().to_string());
+    params.set(SEED_PARAM, &state.seed.to_string());
+    match state.preset().to_compact_json() {
+        Ok(preset) => params.set(PRESET_PARAM, &preset),
+        Err(e) => warn!("Error encoding preset into url: {}", e),
+    }
+
+    let search = format!("?{}", String::from(params.to_string()));
+    let location = window.location();
+    if location.search().ok().as_deref() == Some(search.as_str()) {
+        return;
+    }
+
+    // browsers may throttle frequent url updates, in which case the next update catches up
+    if let Ok(history) = window.history() {
+        let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&search));
+    }
+}
@@ -1,2 +1,3 @@
 pub mod animation;
 pub mod download;
+pub mod wav;
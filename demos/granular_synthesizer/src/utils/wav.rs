@@ -0,0 +1,25 @@
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::io::Cursor;
+
+/// Encodes interleaved `f32` sample data as a 16-bit .wav binary file
+pub fn encode_wav(samples: &[f32], num_channels: u16, sample_rate: u32) -> Vec<u8> {
+    let wav_spec = WavSpec {
+        channels: num_channels,
+        sample_rate,
+        // these were the default used in the `hound` doc examples
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+
+    let mut bytes = Vec::new();
+    let mut bytes_cursor = Cursor::new(&mut bytes);
+    let mut wav_writer = WavWriter::new(&mut bytes_cursor, wav_spec).unwrap();
+    let amplitude = i16::MAX as f32;
+    for sample in samples {
+        wav_writer
+            .write_sample((sample * amplitude) as i16)
+            .unwrap();
+    }
+    wav_writer.finalize().unwrap();
+    bytes
+}